@@ -3,7 +3,9 @@
 
 extern crate test;
 
-use nexus_benchmarks::{runner::run_benchmark, utils::get_timestamped_filename};
+use nexus_benchmarks::{
+    runner::run_benchmark, utils::get_timestamped_filename, workloads::run_workloads,
+};
 use nexus_common_testing::emulator::EmulatorType;
 use postcard::to_allocvec_cobs;
 use test::Bencher;
@@ -63,6 +65,13 @@ fn test_benchmark_keccak_powers() {
     }
 }
 
+#[test]
+#[ignore]
+fn test_benchmark_workloads() {
+    let results_file = get_timestamped_filename("workloads");
+    run_workloads(EmulatorType::TwoPass, &results_file, 3);
+}
+
 /// Benchmark Harvard emulator performance.
 #[bench]
 fn bench_harvard_fib1000(b: &mut Bencher) {
@@ -0,0 +1,35 @@
+// Verifies secp256k1 ECDSA signatures.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+use k256::ecdsa::{
+    signature::{Signer, Verifier},
+    Signature, SigningKey, VerifyingKey,
+};
+
+const NUM_VERIFICATIONS: usize = 4;
+
+#[nexus_rt::main]
+fn main() {
+    let signing_key = SigningKey::from_slice(&[7u8; 32]).expect("invalid secret key");
+    let verifying_key = VerifyingKey::from(&signing_key);
+
+    let message = b"transfer 100 tokens from alice to bob";
+    let signature: Signature = signing_key.sign(message);
+
+    for _ in 0..NUM_VERIFICATIONS {
+        verifying_key
+            .verify(message, &signature)
+            .expect("invalid signature");
+    }
+    assert!(verifying_key
+        .verify(b"transfer 1000 tokens from alice to bob", &signature)
+        .is_err());
+
+    println!("verified {} signatures", NUM_VERIFICATIONS);
+}
@@ -0,0 +1,121 @@
+// Runs a bytecode interpreter for a small stack machine, counting primes by trial division.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+const LIMIT: i32 = 3000;
+// Number of primes below `LIMIT`.
+const EXPECTED: i32 = 430;
+
+#[derive(Clone, Copy)]
+enum Op {
+    Push(i32),
+    Load(usize),
+    Store(usize),
+    Add,
+    Mul,
+    Rem,
+    /// Pops `b` and `a`, pushes `1` if `a < b` and `0` otherwise.
+    Lt,
+    Jump(usize),
+    /// Pops a value and jumps if it is zero.
+    JumpIfZero(usize),
+    Halt,
+}
+
+// Locals: 0 = n, 1 = divisor, 2 = count, 3 = limit.
+const PROGRAM: &[Op] = &[
+    // n = 2
+    Op::Push(2),
+    Op::Store(0),
+    // 2: while n < limit
+    Op::Load(0),
+    Op::Load(3),
+    Op::Lt,
+    Op::JumpIfZero(33),
+    // divisor = 2
+    Op::Push(2),
+    Op::Store(1),
+    // 8: if n < divisor * divisor, n is prime
+    Op::Load(0),
+    Op::Load(1),
+    Op::Load(1),
+    Op::Mul,
+    Op::Lt,
+    Op::JumpIfZero(19),
+    Op::Load(2),
+    Op::Push(1),
+    Op::Add,
+    Op::Store(2),
+    Op::Jump(28),
+    // 19: if n % divisor == 0, n is composite
+    Op::Load(0),
+    Op::Load(1),
+    Op::Rem,
+    Op::JumpIfZero(28),
+    // divisor += 1
+    Op::Load(1),
+    Op::Push(1),
+    Op::Add,
+    Op::Store(1),
+    Op::Jump(8),
+    // 28: n += 1
+    Op::Load(0),
+    Op::Push(1),
+    Op::Add,
+    Op::Store(0),
+    Op::Jump(2),
+    // 33
+    Op::Halt,
+];
+
+/// Runs `program` and returns the number of executed instructions.
+fn run(program: &[Op], locals: &mut [i32]) -> usize {
+    let mut stack: Vec<i32> = Vec::with_capacity(8);
+    let mut pc = 0;
+    let mut steps = 0;
+
+    loop {
+        steps += 1;
+        let op = program[pc];
+        pc += 1;
+
+        match op {
+            Op::Push(value) => stack.push(value),
+            Op::Load(slot) => stack.push(locals[slot]),
+            Op::Store(slot) => locals[slot] = stack.pop().unwrap(),
+            Op::Add | Op::Mul | Op::Rem | Op::Lt => {
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                stack.push(match op {
+                    Op::Add => a.wrapping_add(b),
+                    Op::Mul => a.wrapping_mul(b),
+                    Op::Rem => a % b,
+                    _ => (a < b) as i32,
+                });
+            }
+            Op::Jump(target) => pc = target,
+            Op::JumpIfZero(target) => {
+                if stack.pop().unwrap() == 0 {
+                    pc = target;
+                }
+            }
+            Op::Halt => return steps,
+        }
+    }
+}
+
+#[nexus_rt::main]
+fn main() {
+    let mut locals = [0, 0, 0, LIMIT];
+    let steps = run(PROGRAM, &mut locals);
+    assert_eq!(locals[2], EXPECTED);
+
+    println!("{} primes below {} in {} steps", locals[2], LIMIT, steps);
+}
@@ -0,0 +1,73 @@
+// Parses a JSON document of orders into typed structs with serde and aggregates them.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::{format, string::String, vec::Vec};
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+use serde::Deserialize;
+
+const NUM_ORDERS: u32 = 500;
+
+#[derive(Deserialize)]
+struct Order {
+    id: u32,
+    customer: String,
+    paid: bool,
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    sku: String,
+    quantity: u32,
+    price_cents: u64,
+}
+
+fn document(num_orders: u32) -> String {
+    let mut doc = String::from("[");
+    for id in 0..num_orders {
+        if id > 0 {
+            doc.push(',');
+        }
+        doc.push_str(&format!(
+            r#"{{"id":{id},"customer":"customer-{}","paid":{},"items":[{{"sku":"A-{id}","quantity":{},"price_cents":1999}},{{"sku":"B-{id}","quantity":1,"price_cents":{}}}]}}"#,
+            id % 97,
+            id % 3 == 0,
+            id % 5 + 1,
+            500 + id
+        ));
+    }
+    doc.push(']');
+    doc
+}
+
+#[nexus_rt::main]
+fn main() {
+    let doc = document(NUM_ORDERS);
+    let orders: Vec<Order> = serde_json::from_str(&doc).expect("invalid document");
+    assert_eq!(orders.len(), NUM_ORDERS as usize);
+
+    let revenue: u64 = orders
+        .iter()
+        .filter(|order| order.paid)
+        .flat_map(|order| &order.items)
+        .map(|item| item.quantity as u64 * item.price_cents)
+        .sum();
+    let checksum: usize = orders
+        .iter()
+        .map(|order| order.id as usize + order.customer.len())
+        .chain(
+            orders
+                .iter()
+                .flat_map(|order| &order.items)
+                .map(|item| item.sku.len()),
+        )
+        .sum();
+
+    println!("revenue: {}, checksum: {}", revenue, checksum);
+}
@@ -0,0 +1,83 @@
+// Builds a SHA-256 Merkle tree and verifies inclusion proofs against its root.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+use sha2::{Digest, Sha256};
+
+type Hash = [u8; 32];
+
+const DEPTH: usize = 10;
+const PROOF_STRIDE: usize = 16;
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Returns all levels of the tree, from the leaves up to the root.
+fn build_tree(leaves: Vec<Hash>) -> Vec<Vec<Hash>> {
+    let mut levels = alloc::vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn proof(levels: &[Vec<Hash>], mut index: usize) -> Vec<Hash> {
+    levels[..levels.len() - 1]
+        .iter()
+        .map(|level| {
+            let sibling = level[index ^ 1];
+            index /= 2;
+            sibling
+        })
+        .collect()
+}
+
+fn verify(root: &Hash, leaf: &Hash, mut index: usize, proof: &[Hash]) -> bool {
+    let mut node = *leaf;
+    for sibling in proof {
+        node = if index % 2 == 0 {
+            hash_pair(&node, sibling)
+        } else {
+            hash_pair(sibling, &node)
+        };
+        index /= 2;
+    }
+    node == *root
+}
+
+#[nexus_rt::main]
+fn main() {
+    let leaves: Vec<Hash> = (0..1u32 << DEPTH)
+        .map(|i| Sha256::digest(i.to_le_bytes()).into())
+        .collect();
+    let levels = build_tree(leaves.clone());
+    let root = levels[DEPTH][0];
+
+    let mut verified = 0;
+    for index in (0..leaves.len()).step_by(PROOF_STRIDE) {
+        let proof = proof(&levels, index);
+        assert!(verify(&root, &leaves[index], index, &proof));
+        // A proof must not verify for a different leaf.
+        assert!(!verify(&root, &leaves[index ^ 1], index, &proof));
+        verified += 1;
+    }
+
+    println!("verified {} proofs against {:02x?}", verified, root);
+}
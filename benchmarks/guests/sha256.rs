@@ -0,0 +1,25 @@
+// Hashes 1 MiB of data with SHA-256.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec;
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+use sha2::{Digest, Sha256};
+
+const DATA_LEN: usize = 1 << 20;
+
+#[nexus_rt::main]
+fn main() {
+    let mut data = vec![0u8; DATA_LEN];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = ((i as u32).wrapping_mul(2654435761) >> 24) as u8;
+    }
+
+    let digest = Sha256::digest(&data);
+    println!("{:02x?}", digest.as_slice());
+}
@@ -0,0 +1,42 @@
+// Sorts large pseudo-random vectors.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+#[cfg(target_arch = "riscv32")]
+use nexus_rt::println;
+#[cfg(not(target_arch = "riscv32"))]
+use std::println;
+
+const LEN: usize = 1 << 14;
+
+fn xorshift(state: &mut u32) -> u32 {
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+    *state
+}
+
+#[nexus_rt::main]
+fn main() {
+    let mut state = 0x9e3779b9;
+    let mut numbers: Vec<u32> = (0..LEN).map(|_| xorshift(&mut state)).collect();
+    numbers.sort_unstable();
+    assert!(numbers.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    // Stable sort of records by key, keeping the insertion order of equal keys.
+    let mut records: Vec<(u8, u32)> = (0..LEN as u32)
+        .map(|i| ((xorshift(&mut state) % 64) as u8, i))
+        .collect();
+    records.sort_by_key(|&(key, _)| key);
+    assert!(records
+        .windows(2)
+        .all(|pair| pair[0].0 < pair[1].0 || (pair[0].0 == pair[1].0 && pair[0].1 < pair[1].1)));
+
+    let checksum = numbers
+        .iter()
+        .step_by(LEN / 16)
+        .fold(0u32, |acc, n| acc.rotate_left(5) ^ n);
+    println!("checksum: {}", checksum);
+}
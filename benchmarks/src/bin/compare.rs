@@ -0,0 +1,87 @@
+//! Compares benchmark results against a saved baseline and fails on regressions.
+//!
+//! ```text
+//! cargo run --release -p nexus-benchmarks --bin compare -- --baseline <CSV> [--current <CSV>]
+//!     [--threshold <PERCENT>] [--iters <N>] [--format markdown|json] [--output <PATH>]
+//! ```
+//!
+//! Without `--current`, the regression suite is run first and its results are used. Exits with code 1 if any metric
+//! regressed by more than the threshold (5% by default).
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::{Parser, ValueEnum};
+use nexus_benchmarks::{
+    compare::{compare, load_results},
+    paths::{benchmarks_dir, results_file},
+    runner::run_regression_suite,
+    utils::get_timestamped_filename,
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+#[command(about = "Compare benchmark results against a saved baseline")]
+struct Args {
+    /// Results of the baseline run, in CSV.
+    #[arg(long)]
+    baseline: PathBuf,
+    /// Results of the current run, in CSV. The regression suite is run if omitted.
+    #[arg(long)]
+    current: Option<PathBuf>,
+    /// Maximum allowed regression of a metric, in percent.
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+    /// Number of iterations of each benchmark when running the regression suite.
+    #[arg(long, default_value_t = 5)]
+    iters: u32,
+    #[arg(long, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+    /// Write the summary to a file instead of stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    // Running the regression suite changes the working directory, resolve paths given by the caller first.
+    let baseline_path =
+        std::fs::canonicalize(&args.baseline).expect("Failed to find baseline results");
+    let current_path = args
+        .current
+        .map(|path| std::fs::canonicalize(path).expect("Failed to find current results"));
+    let output_path = args
+        .output
+        .map(|path| std::path::absolute(path).expect("Failed to resolve output path"));
+
+    let baseline = load_results(&baseline_path).expect("Failed to load baseline results");
+    let current_path = current_path.unwrap_or_else(|| {
+        // Benchmarks resolve guest and runtime paths relative to the crate directory.
+        std::env::set_current_dir(benchmarks_dir()).expect("Failed to enter benchmarks directory");
+        let results = get_timestamped_filename("regression_suite");
+        run_regression_suite(&results, args.iters);
+        results_file(&results)
+    });
+    let current = load_results(&current_path).expect("Failed to load current results");
+
+    let report = compare(&baseline, &current, args.threshold);
+    let summary = match args.format {
+        Format::Markdown => report.to_markdown(),
+        Format::Json => report.to_json(),
+    };
+    match &output_path {
+        Some(path) => std::fs::write(path, &summary).expect("Failed to write summary"),
+        None => println!("{summary}"),
+    }
+
+    if report.has_regressions() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
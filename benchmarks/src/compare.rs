@@ -0,0 +1,332 @@
+use serde::Serialize;
+use std::{error::Error, fmt::Write, path::Path};
+
+use crate::models::{BenchmarkResult, StageStats};
+
+/// Metrics tracked for regressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Metric {
+    EmulationSpeedKhz,
+    ProvingDurationSecs,
+    PeakMemoryGb,
+}
+
+impl Metric {
+    pub const ALL: [Self; 3] = [
+        Self::EmulationSpeedKhz,
+        Self::ProvingDurationSecs,
+        Self::PeakMemoryGb,
+    ];
+
+    fn higher_is_better(self) -> bool {
+        matches!(self, Self::EmulationSpeedKhz)
+    }
+
+    fn sample(self, result: &BenchmarkResult) -> Sample {
+        let stage =
+            |mins: &StageStats, avgs: &StageStats, maxs: &StageStats, f: fn(&StageStats) -> f64| {
+                Sample::new(f(mins), f(avgs), f(maxs))
+            };
+        match self {
+            Self::EmulationSpeedKhz => stage(
+                &result.emulation_mins,
+                &result.emulation_avgs,
+                &result.emulation_maxs,
+                |stats| stats.speed_khz as f64,
+            ),
+            Self::ProvingDurationSecs => stage(
+                &result.proving_mins,
+                &result.proving_avgs,
+                &result.proving_maxs,
+                |stats| stats.duration.as_secs_f64(),
+            ),
+            Self::PeakMemoryGb => Sample::new(
+                result.piecewise_min_total_peak_memory_gb,
+                result.avg_total_peak_memory_gb,
+                result.piecewise_max_total_peak_memory_gb,
+            ),
+        }
+    }
+}
+
+/// Range and average of a metric over the iterations of a benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Sample {
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+impl Sample {
+    /// Stage minimums hold the speed of the fastest run, which is the maximum speed, hence the sort.
+    fn new(a: f64, avg: f64, b: f64) -> Self {
+        Self {
+            min: a.min(b),
+            avg,
+            max: a.max(b),
+        }
+    }
+}
+
+/// Comparison of a single metric of a benchmark against the baseline.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricComparison {
+    pub test: String,
+    pub emulator_type: String,
+    pub total_steps: u32,
+    pub metric: Metric,
+    pub baseline: Sample,
+    pub current: Sample,
+    /// Relative change of the average in percent, positive values are always worse.
+    pub change_percent: f64,
+    /// Whether the ranges observed over all iterations of both runs don't overlap.
+    pub significant: bool,
+    /// Significant change for the worse exceeding the threshold.
+    pub regression: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonReport {
+    pub threshold_percent: f64,
+    pub comparisons: Vec<MetricComparison>,
+    /// Benchmarks of the baseline without a matching current result.
+    pub missing: Vec<String>,
+}
+
+fn key(result: &BenchmarkResult) -> (&str, &str, u32) {
+    (&result.test, &result.emulator_type, result.total_steps)
+}
+
+fn describe(result: &BenchmarkResult) -> String {
+    format!(
+        "{} ({}, {} steps)",
+        result.test, result.emulator_type, result.total_steps
+    )
+}
+
+/// Loads benchmark results from a CSV file written by [`record_benchmark_results`](crate::utils::record_benchmark_results).
+pub fn load_results(path: &Path) -> Result<Vec<BenchmarkResult>, Box<dyn Error>> {
+    BenchmarkResult::from_csv_reader(csv::Reader::from_path(path)?)
+}
+
+/// Compares current results against the baseline, matched by test, emulator and number of steps.
+///
+/// The CSV only keeps the minimum, average and maximum of each metric, so a change is considered significant when the
+/// ranges observed in both runs don't overlap, i.e. every current iteration was worse than every baseline one. Such
+/// a change is a regression if the average got worse by more than `threshold_percent`. If a benchmark occurs several
+/// times in a file, the latest result is used.
+pub fn compare(
+    baseline: &[BenchmarkResult],
+    current: &[BenchmarkResult],
+    threshold_percent: f64,
+) -> ComparisonReport {
+    let mut comparisons = Vec::new();
+    let mut missing = Vec::new();
+
+    for (idx, base) in baseline.iter().enumerate() {
+        if baseline[idx + 1..]
+            .iter()
+            .any(|later| key(later) == key(base))
+        {
+            continue;
+        }
+        let Some(curr) = current.iter().rev().find(|curr| key(curr) == key(base)) else {
+            missing.push(describe(base));
+            continue;
+        };
+
+        for metric in Metric::ALL {
+            let baseline = metric.sample(base);
+            let current = metric.sample(curr);
+
+            let sign = if metric.higher_is_better() { -1.0 } else { 1.0 };
+            let change_percent = if baseline.avg == 0.0 {
+                0.0
+            } else {
+                sign * (current.avg - baseline.avg) / baseline.avg * 100.0
+            };
+            let significant = current.min > baseline.max || current.max < baseline.min;
+
+            comparisons.push(MetricComparison {
+                test: base.test.clone(),
+                emulator_type: base.emulator_type.clone(),
+                total_steps: base.total_steps,
+                metric,
+                baseline,
+                current,
+                change_percent,
+                significant,
+                regression: significant && change_percent > threshold_percent,
+            });
+        }
+    }
+
+    ComparisonReport {
+        threshold_percent,
+        comparisons,
+        missing,
+    }
+}
+
+impl ComparisonReport {
+    pub fn has_regressions(&self) -> bool {
+        self.comparisons
+            .iter()
+            .any(|comparison| comparison.regression)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize comparison report")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let regressions = self.comparisons.iter().filter(|c| c.regression).count();
+        writeln!(out, "## Benchmark comparison\n").unwrap();
+        writeln!(
+            out,
+            "{} regression(s) above {}% out of {} metric(s).\n",
+            regressions,
+            self.threshold_percent,
+            self.comparisons.len()
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            "| test | emulator | steps | metric | baseline | current | change | status |"
+        )
+        .unwrap();
+        writeln!(out, "|---|---|---|---|---|---|---|---|").unwrap();
+        for c in &self.comparisons {
+            let status = if c.regression {
+                "regression"
+            } else if c.significant && c.change_percent < 0.0 {
+                "improvement"
+            } else if c.significant {
+                "changed"
+            } else {
+                "ok"
+            };
+            writeln!(
+                out,
+                "| {} | {} | {} | {:?} | {:.3} | {:.3} | {:+.2}% | {} |",
+                c.test,
+                c.emulator_type,
+                c.total_steps,
+                c.metric,
+                c.baseline.avg,
+                c.current.avg,
+                c.change_percent,
+                status
+            )
+            .unwrap();
+        }
+
+        if !self.missing.is_empty() {
+            writeln!(out, "\nMissing from the current run:\n").unwrap();
+            for missing in &self.missing {
+                writeln!(out, "- {}", missing).unwrap();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn stage(speed_khz: f32, duration_secs: f32) -> StageStats {
+        StageStats {
+            speed_khz,
+            overhead: 1.0,
+            peak_cpu_percentage: 100.0,
+            peak_memory_gb: 1.0,
+            duration: Duration::from_secs_f32(duration_secs),
+            sys_time: Duration::ZERO,
+            user_time: Duration::ZERO,
+        }
+    }
+
+    /// Result of a benchmark whose proving took between `proving.0` and `proving.1` seconds.
+    fn result(proving: (f32, f32)) -> BenchmarkResult {
+        let emulation = || stage(100.0, 1.0);
+        BenchmarkResult {
+            timestamp: String::new(),
+            test: "fib".to_string(),
+            emulator_type: "TwoPass".to_string(),
+            piecewise_min_total_speed_khz: 0.0,
+            piecewise_min_total_duration: Duration::ZERO,
+            piecewise_min_total_overhead: 0.0,
+            avg_total_speed_khz: 0.0,
+            avg_total_duration: Duration::ZERO,
+            avg_total_overhead: 0.0,
+            piecewise_max_total_speed_khz: 0.0,
+            piecewise_max_total_duration: Duration::ZERO,
+            piecewise_max_total_overhead: 0.0,
+            total_steps: 1000,
+            cpu_cores: 1,
+            total_ram_gb: 1.0,
+            piecewise_min_total_peak_cpu_percentage: 0.0,
+            piecewise_min_total_peak_memory_gb: 1.0,
+            avg_total_peak_cpu_percentage: 0.0,
+            avg_total_peak_memory_gb: 1.0,
+            piecewise_max_total_peak_cpu_percentage: 0.0,
+            piecewise_max_total_peak_memory_gb: 1.0,
+            num_loads: 0,
+            num_stores: 0,
+            stack_size: 0,
+            heap_size: 0,
+            native_mins: emulation(),
+            native_avgs: emulation(),
+            native_maxs: emulation(),
+            emulation_mins: emulation(),
+            emulation_avgs: emulation(),
+            emulation_maxs: emulation(),
+            proving_mins: stage(0.0, proving.0),
+            proving_avgs: stage(0.0, (proving.0 + proving.1) / 2.0),
+            proving_maxs: stage(0.0, proving.1),
+            verification_mins: emulation(),
+            verification_avgs: emulation(),
+            verification_maxs: emulation(),
+        }
+    }
+
+    fn proving(report: &ComparisonReport) -> &MetricComparison {
+        report
+            .comparisons
+            .iter()
+            .find(|c| c.metric == Metric::ProvingDurationSecs)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = [result((9.0, 11.0))];
+
+        // Overlapping ranges are noise, even with a worse average.
+        let report = compare(&baseline, &[result((10.5, 12.5))], 5.0);
+        assert!(!proving(&report).significant);
+        assert!(!report.has_regressions());
+
+        let report = compare(&baseline, &[result((12.0, 14.0))], 5.0);
+        assert!(proving(&report).regression);
+        assert!(report.has_regressions());
+        assert!(report.to_markdown().contains("regression"));
+
+        // Significant, but below the threshold.
+        let report = compare(&baseline, &[result((12.0, 14.0))], 50.0);
+        assert!(proving(&report).significant);
+        assert!(!report.has_regressions());
+
+        // Improvements never fail.
+        let report = compare(&baseline, &[result((5.0, 6.0))], 5.0);
+        assert!(proving(&report).change_percent < 0.0);
+        assert!(!report.has_regressions());
+
+        let report = compare(&baseline, &[], 5.0);
+        assert_eq!(report.missing.len(), 1);
+    }
+}
@@ -1,4 +1,6 @@
+pub mod compare;
 pub mod models;
 pub mod paths;
 pub mod runner;
 pub mod utils;
+pub mod workloads;
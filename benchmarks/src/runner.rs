@@ -1,11 +1,12 @@
 use chrono;
 use nexus_common::memory::traits::MemoryRecord;
 use nexus_common_testing::emulator::{
-    compile_guest_project, setup_guest_project, write_guest_source_code, EmulatorType,
+    add_guest_dependencies, compile_guest_project, setup_guest_project, write_guest_source_code,
+    EmulatorType,
 };
 use nexus_vm::elf::ElfFile;
 use nexus_vm::trace::{k_trace, Trace};
-use nexus_vm_prover::{prove, verify};
+use nexus_vm_prover::{prove, prove_with_report, verify};
 use num_cpus;
 use postcard;
 use serde::{de::DeserializeOwned, Serialize};
@@ -15,11 +16,21 @@ use sysinfo::System;
 
 use crate::{
     models::{BenchmarkResult, StageStats},
-    utils::{phase_end, phase_start, record_benchmark_results, PhasesTracker},
+    utils::{
+        phase_end, phase_start, record_benchmark_results, record_prover_report, PhasesTracker,
+    },
 };
 
 const K: usize = 1;
 
+/// Environment variable enabling the prover report, see [`nexus_vm_prover::ProverReport`].
+pub const PROVER_REPORT_ENV: &str = "NEXUS_BENCH_PROVER_REPORT";
+
+/// Returns whether benchmarks should record a prover report, set `NEXUS_BENCH_PROVER_REPORT=1` to enable it.
+fn prover_report_enabled() -> bool {
+    std::env::var(PROVER_REPORT_ENV).is_ok_and(|value| value == "1")
+}
+
 /// Executes and measures the native execution speed of a Rust program.
 fn measure_native_execution<T>(
     path: &PathBuf,
@@ -95,6 +106,32 @@ pub fn run_benchmark<T>(
     iters: u32,
 ) where
     T: DeserializeOwned + Serialize + std::fmt::Display,
+{
+    run_benchmark_with_dependencies::<T>(
+        test,
+        compile_flags,
+        &[],
+        emulator_type,
+        public_input,
+        private_input,
+        results_file,
+        iters,
+    )
+}
+
+/// Benchmarks a test program that depends on crates besides `nexus-rt`, see [`add_guest_dependencies`].
+#[allow(clippy::too_many_arguments)]
+pub fn run_benchmark_with_dependencies<T>(
+    test: &str,
+    compile_flags: &str,
+    dependencies: &[&str],
+    emulator_type: EmulatorType,
+    public_input: Vec<u8>,
+    private_input: Vec<u8>,
+    results_file: &str,
+    iters: u32,
+) where
+    T: DeserializeOwned + Serialize + std::fmt::Display,
 {
     // Get system info at start.
     let cpu_cores = num_cpus::get();
@@ -112,6 +149,7 @@ pub fn run_benchmark<T>(
     let tmp_project_path = tmp_dir.path().join("integration");
 
     // Compile test to RISC-V ELF.
+    add_guest_dependencies(&tmp_project_path, dependencies);
     write_guest_source_code(&tmp_project_path, &format!("{}.rs", test));
     let elf_contents = compile_guest_project(
         &tmp_project_path,
@@ -162,7 +200,15 @@ pub fn run_benchmark<T>(
     }
 
     // Measure proving.
-    let mut proof = prove(&execution_trace, &view).unwrap(); // warm up and make sure we work
+    let mut proof = if prover_report_enabled() {
+        // The report adds overhead to trace generation, only collect it in the warm up run.
+        let (proof, report) = prove_with_report(&execution_trace, &view).unwrap(); // warm up and make sure we work
+        println!("Prover report for {test}:\n{report}");
+        record_prover_report(&report, test, results_file);
+        proof
+    } else {
+        prove(&execution_trace, &view).unwrap() // warm up and make sure we work
+    };
 
     let mut proving_tracker = PhasesTracker::default();
     for _ in 0..iters {
@@ -436,3 +482,31 @@ pub fn run_benchmark<T>(
 
     record_benchmark_results(&result, results_file);
 }
+
+/// Runs the benchmarks tracked for regressions, see [`crate::compare`].
+pub fn run_regression_suite(results_file: &str, iters: u32) {
+    run_benchmark::<u32>(
+        "../examples/src/bin/fib",
+        "-C opt-level=3",
+        EmulatorType::TwoPass,
+        Vec::new(),
+        Vec::new(),
+        results_file,
+        iters,
+    );
+
+    // Inputs corresponding to step count powers of 2 from 2^12 to 2^14.
+    for mut input in [6u32, 16, 37] {
+        let public_input_bytes =
+            postcard::to_allocvec_cobs(&mut input).expect("Failed to serialize input");
+        run_benchmark::<u32>(
+            "../examples/src/bin/fib_input",
+            "-C opt-level=3",
+            EmulatorType::TwoPass,
+            public_input_bytes,
+            Vec::new(),
+            results_file,
+            iters,
+        );
+    }
+}
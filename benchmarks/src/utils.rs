@@ -1,6 +1,12 @@
 use chrono;
 use libc::{getrusage, rusage, RUSAGE_CHILDREN, RUSAGE_SELF};
-use std::{fs::OpenOptions, io::Write, time::Duration};
+use nexus_vm_prover::ProverReport;
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    time::Duration,
+};
 
 use crate::{models::BenchmarkResult, paths::results_file};
 
@@ -27,6 +33,18 @@ pub fn record_benchmark_results(result: &BenchmarkResult, filename: &str) {
     writeln!(file, "{}", result).expect("Failed to write benchmark results");
 }
 
+/// Write the prover report of a benchmark as JSON next to its results file.
+pub fn record_prover_report(report: &ProverReport, test: &str, results_filename: &str) {
+    let stem = Path::new(results_filename)
+        .file_stem()
+        .map_or_else(|| results_filename.into(), |stem| stem.to_string_lossy());
+    let file_path = results_file(&format!("{stem}_{test}_prover_report.json"));
+    let file = File::create(&file_path)
+        .unwrap_or_else(|_| panic!("Failed to create {}", file_path.display()));
+
+    serde_json::to_writer_pretty(file, report).expect("Failed to write prover report");
+}
+
 /// Start timing and return resource usage.
 pub fn start_timer(usage_type: i32) -> rusage {
     let mut usage: rusage = unsafe { std::mem::zeroed() };
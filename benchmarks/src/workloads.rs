@@ -0,0 +1,69 @@
+use nexus_common_testing::emulator::EmulatorType;
+
+use crate::runner::run_benchmark_with_dependencies;
+
+/// A guest program representative of workloads proven in practice.
+pub struct Workload {
+    pub name: &'static str,
+    /// Path of the guest source without the `.rs` extension, relative to the benchmarks directory.
+    pub path: &'static str,
+    /// Crates the guest depends on, in `cargo add` syntax.
+    ///
+    /// Versions are pinned so that results stay comparable with a stored baseline, bump them together with the
+    /// baseline.
+    pub dependencies: &'static [&'static str],
+}
+
+/// Realistic guest workloads, sources are in the `guests` directory.
+pub const WORKLOADS: &[Workload] = &[
+    Workload {
+        name: "json_parse",
+        path: "guests/json_parse",
+        dependencies: &[
+            "serde@=1.0.217 --no-default-features --features derive,alloc",
+            "serde_json@=1.0.134 --no-default-features --features alloc",
+        ],
+    },
+    Workload {
+        name: "sha256",
+        path: "guests/sha256",
+        dependencies: &["sha2@=0.10.8 --no-default-features"],
+    },
+    Workload {
+        name: "merkle",
+        path: "guests/merkle",
+        dependencies: &["sha2@=0.10.8 --no-default-features"],
+    },
+    Workload {
+        name: "ecdsa",
+        path: "guests/ecdsa",
+        dependencies: &["k256@=0.13.4 --no-default-features --features ecdsa"],
+    },
+    Workload {
+        name: "interpreter",
+        path: "guests/interpreter",
+        dependencies: &[],
+    },
+    Workload {
+        name: "sort",
+        path: "guests/sort",
+        dependencies: &[],
+    },
+];
+
+/// Benchmarks emulation and proving of every workload in [`WORKLOADS`].
+pub fn run_workloads(emulator_type: EmulatorType, results_file: &str, iters: u32) {
+    for workload in WORKLOADS {
+        println!("Benchmarking workload {}", workload.name);
+        run_benchmark_with_dependencies::<u32>(
+            workload.path,
+            "-C opt-level=3",
+            workload.dependencies,
+            emulator_type.clone(),
+            Vec::new(),
+            Vec::new(),
+            results_file,
+            iters,
+        );
+    }
+}
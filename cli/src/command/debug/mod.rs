@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    ops::Range,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use clap::Args;
+use nexus_progress_bar::TerminalHandle;
+
+mod session;
+
+use session::{parse_register, Halt, Session, StopReason};
+
+#[derive(Debug, Args)]
+pub struct DebugArgs {
+    /// Path to the guest ELF file.
+    #[arg(name = "elf")]
+    pub elf: PathBuf,
+    /// File with the raw bytes of the public input.
+    #[arg(long)]
+    pub public_input: Option<PathBuf>,
+    /// File with the raw bytes of the private input.
+    #[arg(long)]
+    pub private_input: Option<PathBuf>,
+}
+
+const HELP: &str = "\
+Commands:
+  break [addr|symbol]        set a breakpoint, or list breakpoints without an argument (b)
+  delete <addr|symbol>       remove a breakpoint
+  continue                   run until a breakpoint, a watchpoint or the end of the program (c)
+  step [n]                   execute n instructions, 1 by default (s)
+  regs [reg]                 print all registers, or a single one by name (r)
+  mem <addr|symbol> [len]    print len bytes of memory, 64 by default (x)
+  disas [addr|symbol]        disassemble from the address to the end of its basic block, the pc by default (d)
+  watch [addr|symbol] [len]  stop on loads and stores of len bytes at the address, 4 by default, or list watchpoints (w)
+  unwatch <addr|symbol>      remove a watchpoint
+  help                       print this message (h)
+  quit                       exit the debugger (q)";
+
+pub fn handle_command(args: DebugArgs) -> anyhow::Result<()> {
+    let read_input = |path: Option<PathBuf>| -> anyhow::Result<Vec<u8>> {
+        path.map_or(Ok(Vec::new()), |path| {
+            fs::read(&path).with_context(|| format!("failed to read {}", path.display()))
+        })
+    };
+    let public_input = read_input(args.public_input)?;
+    let private_input = read_input(args.private_input)?;
+    let elf_bytes =
+        fs::read(&args.elf).with_context(|| format!("failed to read {}", args.elf.display()))?;
+
+    let mut session = Session::from_elf_bytes(elf_bytes, &public_input, &private_input)?;
+    println!(
+        "Loaded {}, entry point {:#x}",
+        args.elf.display(),
+        session.pc()
+    );
+    println!("Type `help` for the list of commands.");
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(nexus) ");
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            continue;
+        };
+        match execute(&mut session, command, args) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => println!("error: {err}"),
+        }
+    }
+    Ok(())
+}
+
+/// Executes a single command, returns `false` if the debugger should exit.
+fn execute(session: &mut Session, command: &str, args: &[&str]) -> anyhow::Result<bool> {
+    let arg = |idx: usize| args.get(idx).copied();
+    let count = |idx: usize, default: u32| -> anyhow::Result<u32> {
+        arg(idx).map_or(Ok(default), |n| {
+            n.parse()
+                .with_context(|| format!("expected a number, found {n}"))
+        })
+    };
+
+    match command {
+        "break" | "b" => match arg(0) {
+            Some(location) => {
+                let address = session.resolve(location)?;
+                if session.add_breakpoint(address) {
+                    println!("Breakpoint at {address:#x}");
+                }
+            }
+            None => {
+                for address in session.breakpoints() {
+                    println!("Breakpoint at {address:#x}");
+                }
+            }
+        },
+        "delete" => {
+            let address = session.resolve(arg(0).context("expected an address")?)?;
+            if !session.remove_breakpoint(address) {
+                anyhow::bail!("no breakpoint at {address:#x}");
+            }
+        }
+        "continue" | "c" => {
+            let mut term = TerminalHandle::new_enabled();
+            let reason = {
+                let mut ctx = term
+                    .context("Running")
+                    .on_step(|_step| "program".to_string())
+                    .completion_header("Stopped");
+                let _guard = ctx.display_step();
+                session.resume()
+            };
+            // Wait for the terminal output to be flushed before printing.
+            drop(term);
+            report(session, reason);
+        }
+        "step" | "s" => {
+            let reason = session.step(count(0, 1)? as usize);
+            report(session, reason);
+        }
+        "regs" | "r" => match arg(0) {
+            Some(name) => {
+                let reg =
+                    parse_register(name).with_context(|| format!("unknown register: {name}"))?;
+                let value = session.register(reg);
+                println!("{reg} = {value:#010x} ({value})");
+            }
+            None => {
+                print!("{}", session.registers());
+                println!("pc = {:#010x}", session.pc());
+            }
+        },
+        "mem" | "x" => {
+            let address = session.resolve(arg(0).context("expected an address")?)?;
+            print_memory(address, &session.read_memory(address, count(1, 64)?));
+        }
+        "disas" | "d" => {
+            let address = arg(0).map_or(Ok(session.pc()), |location| session.resolve(location))?;
+            let (start, block) = session.basic_block_at(address)?;
+            println!("pc = {:#x}", session.pc());
+            block.print_with_offset(start as usize);
+        }
+        "watch" | "w" => match arg(0) {
+            Some(location) => {
+                let address = session.resolve(location)?;
+                let len = count(1, 4)?;
+                session.add_watchpoint(address, len);
+                println!("Watchpoint at {address:#x}, {len} byte(s)");
+            }
+            None => {
+                for watchpoint in session.watchpoints() {
+                    let Range { start, end } = watchpoint.range;
+                    println!("Watchpoint at {start:#x}, {} byte(s)", end - start);
+                }
+            }
+        },
+        "unwatch" => {
+            let address = session.resolve(arg(0).context("expected an address")?)?;
+            if !session.remove_watchpoint(address) {
+                anyhow::bail!("no watchpoint at {address:#x}");
+            }
+        }
+        "help" | "h" => println!("{HELP}"),
+        "quit" | "q" => return Ok(false),
+        _ => anyhow::bail!("unknown command {command}, type `help` for the list of commands"),
+    }
+    Ok(true)
+}
+
+fn report(session: &Session, reason: StopReason) {
+    match reason {
+        StopReason::Step => {}
+        StopReason::Breakpoint(address) => println!("Breakpoint hit at {address:#x}"),
+        StopReason::Watchpoint { pc, kind, address } => {
+            println!("Watchpoint: instruction at {pc:#x} stopped before a {kind} of {address:#x}")
+        }
+        StopReason::Halted(Halt::Exited(code)) => println!("Program exited with code {code}"),
+        StopReason::Halted(Halt::Error(err)) => println!("Program stopped: {err}"),
+    }
+    println!(
+        "pc = {:#010x} after {} instruction(s)",
+        session.pc(),
+        session.steps()
+    );
+}
+
+fn print_memory(address: u32, bytes: &[Option<u8>]) {
+    for (idx, row) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = row
+            .iter()
+            .map(|byte| byte.map_or("??".to_string(), |byte| format!("{byte:02x}")))
+            .collect();
+        let ascii: String = row
+            .iter()
+            .map(|byte| match byte {
+                Some(byte) if byte.is_ascii_graphic() => *byte as char,
+                _ => '.',
+            })
+            .collect();
+        println!(
+            "{:#010x}: {:<47}  {ascii}",
+            address.wrapping_add(idx as u32 * 16),
+            hex.join(" ")
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use nexus_vm::{
+    elf::ElfFile,
+    emulator::{Emulator, HarvardEmulator},
+    error::{AccessKind, AccessReport, AccessViolation, VMError},
+    memory::{MemAccessSize, MemoryProcessor, WatchKind, Watchpoint},
+    riscv::{BasicBlock, Register},
+    WORD_SIZE,
+};
+
+/// Reason for the execution to return control to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// Requested number of instructions was executed.
+    Step,
+    /// The program counter reached a breakpoint.
+    Breakpoint(u32),
+    /// The instruction at `pc` is about to access a watched range, it has not been executed yet.
+    Watchpoint {
+        pc: u32,
+        kind: AccessKind,
+        address: u32,
+    },
+    /// The program is no longer running, see [`Halt`].
+    Halted(Halt),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Halt {
+    Exited(u32),
+    Error(String),
+}
+
+/// Debugging session of a single program running on [`HarvardEmulator`].
+pub struct Session {
+    emulator: HarvardEmulator,
+    /// Addresses of the program instructions.
+    text: Range<u32>,
+    /// Raw ELF bytes used for symbol lookups, symbols are not retained by [`ElfFile`].
+    elf_bytes: Option<Vec<u8>>,
+    breakpoints: BTreeSet<u32>,
+    /// Whether the last instruction was stopped by a watchpoint, in which case the next step executes it
+    /// with the watchpoints lifted.
+    on_watchpoint: bool,
+    halted: Option<Halt>,
+}
+
+impl Session {
+    pub fn new(emulator: HarvardEmulator, text: Range<u32>, elf_bytes: Option<Vec<u8>>) -> Self {
+        Self {
+            emulator,
+            text,
+            elf_bytes,
+            breakpoints: BTreeSet::new(),
+            on_watchpoint: false,
+            halted: None,
+        }
+    }
+
+    pub fn from_elf_bytes(
+        elf_bytes: Vec<u8>,
+        public_input: &[u8],
+        private_input: &[u8],
+    ) -> anyhow::Result<Self> {
+        let elf = ElfFile::from_bytes(&elf_bytes)?;
+        let emulator = HarvardEmulator::from_elf(&elf, public_input, private_input);
+        let text = elf.base..elf.base + (elf.instructions.len() * WORD_SIZE) as u32;
+        Ok(Self::new(emulator, text, Some(elf_bytes)))
+    }
+
+    pub fn pc(&self) -> u32 {
+        self.emulator.executor.cpu.pc.value
+    }
+
+    /// Number of executed instructions.
+    pub fn steps(&self) -> usize {
+        self.emulator.executor.global_clock - 1
+    }
+
+    pub fn halted(&self) -> Option<&Halt> {
+        self.halted.as_ref()
+    }
+
+    pub fn register(&self, reg: Register) -> u32 {
+        self.emulator.executor.cpu.registers[reg]
+    }
+
+    /// Formatted table of all registers.
+    pub fn registers(&self) -> String {
+        self.emulator.executor.cpu.registers.to_string()
+    }
+
+    /// Resolves an address given as a decimal or `0x`-prefixed hex number, or as an ELF symbol.
+    pub fn resolve(&self, location: &str) -> anyhow::Result<u32> {
+        if let Some(address) = parse_number(location) {
+            return Ok(address);
+        }
+        let Some(elf_bytes) = &self.elf_bytes else {
+            anyhow::bail!("no symbols loaded, expected an address: {location}");
+        };
+        ElfFile::symbol_address(elf_bytes, location)?
+            .ok_or_else(|| anyhow::anyhow!("unknown symbol: {location}"))
+    }
+
+    pub fn breakpoints(&self) -> &BTreeSet<u32> {
+        &self.breakpoints
+    }
+
+    /// Returns `false` if the breakpoint was already set.
+    pub fn add_breakpoint(&mut self, address: u32) -> bool {
+        self.breakpoints.insert(address)
+    }
+
+    pub fn remove_breakpoint(&mut self, address: u32) -> bool {
+        self.breakpoints.remove(&address)
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        self.emulator.data_memory.watchpoints()
+    }
+
+    /// Stops on loads and stores touching `len` bytes starting at `address`.
+    pub fn add_watchpoint(&mut self, address: u32, len: u32) {
+        let range = address..address.saturating_add(len.max(1));
+        self.emulator
+            .data_memory
+            .add_watchpoint(range, WatchKind::ReadWrite);
+    }
+
+    /// Removes the watchpoints starting at `address`.
+    pub fn remove_watchpoint(&mut self, address: u32) -> bool {
+        let ranges: Vec<Range<u32>> = self
+            .watchpoints()
+            .iter()
+            .filter(|watchpoint| watchpoint.range.start == address)
+            .map(|watchpoint| watchpoint.range.clone())
+            .collect();
+        for range in &ranges {
+            self.emulator.data_memory.remove_watchpoint(range);
+        }
+        !ranges.is_empty()
+    }
+
+    /// Reads `len` bytes of data memory starting at `address`, `None` for unmapped bytes.
+    pub fn read_memory(&self, address: u32, len: u32) -> Vec<Option<u8>> {
+        (0..len)
+            .map(|offset| {
+                let address = address.checked_add(offset)?;
+                self.emulator
+                    .data_memory
+                    .read(address, MemAccessSize::Byte)
+                    .ok()
+                    .map(|op| op.get_value() as u8)
+            })
+            .collect()
+    }
+
+    /// Returns the instructions from `address` up to the end of its basic block, i.e. up to and including
+    /// the next branch or jump. `address` must be the start of an instruction.
+    pub fn basic_block_at(&mut self, address: u32) -> anyhow::Result<(u32, BasicBlock)> {
+        if !self.text.contains(&address) {
+            anyhow::bail!("address {address:#x} is outside of the program");
+        }
+        let (entry, at) = self.emulator.fetch_block_at(address)?;
+        Ok((address, BasicBlock::new(entry.block.0[at..].to_vec())))
+    }
+
+    /// Executes at most `n` instructions, stopping early on a watchpoint or when the program halts.
+    pub fn step(&mut self, n: usize) -> StopReason {
+        for _ in 0..n {
+            if let Some(reason) = self.step_instruction() {
+                return reason;
+            }
+        }
+        StopReason::Step
+    }
+
+    /// Executes until a breakpoint or a watchpoint is hit, or the program halts.
+    ///
+    /// A breakpoint at the current program counter is stepped over.
+    pub fn resume(&mut self) -> StopReason {
+        loop {
+            if let Some(reason) = self.step_instruction() {
+                return reason;
+            }
+            let pc = self.pc();
+            if self.breakpoints.contains(&pc) {
+                return StopReason::Breakpoint(pc);
+            }
+        }
+    }
+
+    fn step_instruction(&mut self) -> Option<StopReason> {
+        if let Some(halt) = &self.halted {
+            return Some(StopReason::Halted(halt.clone()));
+        }
+
+        // Step over the access a watchpoint stopped, the watchpoints are restored right after.
+        let lifted = if std::mem::take(&mut self.on_watchpoint) {
+            self.watchpoints().to_vec()
+        } else {
+            Vec::new()
+        };
+        for watchpoint in &lifted {
+            self.emulator
+                .data_memory
+                .remove_watchpoint(&watchpoint.range);
+        }
+
+        let pc = self.pc();
+        let result = self.emulator.fetch_block_at(pc).and_then(|(entry, at)| {
+            let instruction = entry.block.0.get(at).ok_or(VMError::VMOutOfInstructions)?;
+            self.emulator.execute_instruction(instruction, false)
+        });
+
+        for watchpoint in lifted {
+            self.emulator
+                .data_memory
+                .add_watchpoint(watchpoint.range, watchpoint.kind);
+        }
+
+        match result {
+            Ok(_) => None,
+            Err(VMError::AccessViolation(AccessReport {
+                pc,
+                kind,
+                address,
+                violation: AccessViolation::Watchpoint,
+            })) => {
+                self.on_watchpoint = true;
+                Some(StopReason::Watchpoint { pc, kind, address })
+            }
+            Err(err) => {
+                let halt = match err {
+                    VMError::VMExited(code) => Halt::Exited(code),
+                    err => Halt::Error(err.to_string()),
+                };
+                self.halted = Some(halt.clone());
+                Some(StopReason::Halted(halt))
+            }
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Parses a register by its ABI name (`a0`, `sp`) or its index (`x10`).
+pub fn parse_register(s: &str) -> Option<Register> {
+    (0..32u8)
+        .map(Register::from)
+        .find(|reg| reg.abi_name() == s || reg.name() == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::riscv::{BuiltinOpcode, Instruction, Opcode};
+
+    fn session() -> Session {
+        // Stores 4 and then 5 at address 0x100, and loads it back.
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 3, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 3, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 4, 3, 0),
+        ])];
+        let emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        let entry = emulator.executor.cpu.pc.value;
+        Session::new(emulator, entry..entry + 6 * WORD_SIZE as u32, None)
+    }
+
+    #[test]
+    fn test_step_and_breakpoints() {
+        let mut session = session();
+        let entry = session.pc();
+
+        assert_eq!(session.step(2), StopReason::Step);
+        assert_eq!(session.pc(), entry + 8);
+        assert_eq!(session.register(Register::X1), 4);
+        assert_eq!(session.register(parse_register("gp").unwrap()), 0x100);
+
+        assert!(session.add_breakpoint(entry + 16));
+        assert_eq!(session.resume(), StopReason::Breakpoint(entry + 16));
+        assert_eq!(session.register(Register::X1), 5);
+        assert_eq!(
+            session.read_memory(0x100, 4),
+            [Some(4), Some(0), Some(0), Some(0)]
+        );
+
+        assert!(matches!(
+            session.resume(),
+            StopReason::Halted(Halt::Error(_))
+        ));
+        assert_eq!(session.steps(), 6);
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut session = session();
+        let entry = session.pc();
+
+        session.add_watchpoint(0x102, 1);
+        // Stops before the store, and steps over it on the next resume.
+        assert_eq!(
+            session.resume(),
+            StopReason::Watchpoint {
+                pc: entry + 8,
+                kind: AccessKind::Write,
+                address: 0x100,
+            }
+        );
+        assert_eq!(session.pc(), entry + 8);
+        assert_eq!(
+            session.resume(),
+            StopReason::Watchpoint {
+                pc: entry + 16,
+                kind: AccessKind::Write,
+                address: 0x100,
+            }
+        );
+        assert_eq!(session.read_memory(0x100, 1), [Some(4)]);
+        assert_eq!(session.watchpoints().len(), 1);
+
+        // Loads are stopped too.
+        assert_eq!(
+            session.resume(),
+            StopReason::Watchpoint {
+                pc: entry + 20,
+                kind: AccessKind::Read,
+                address: 0x100,
+            }
+        );
+        assert!(session.remove_watchpoint(0x102));
+        assert!(session.watchpoints().is_empty());
+        assert!(matches!(session.resume(), StopReason::Halted(_)));
+        assert_eq!(session.register(Register::X4), 5);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let session = session();
+        assert_eq!(session.resolve("0x10").unwrap(), 16);
+        assert_eq!(session.resolve("16").unwrap(), 16);
+        assert!(session.resolve("main").is_err());
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let mut session = session();
+        let entry = session.pc();
+        let (start, block) = session.basic_block_at(entry + 8).unwrap();
+        assert_eq!(start, entry + 8);
+        assert_eq!(block.len(), 4);
+        assert!(session.basic_block_at(entry + 10).is_err());
+        assert!(session.basic_block_at(entry + 24).is_err());
+    }
+}
@@ -13,22 +13,31 @@ pub struct HostArgs {
     pub rev: Option<String>,
     #[arg(long, name = "tag", conflicts_with = "rev")]
     pub tag: Option<String>,
+    /// Set up the guest with the `std`-compatible facade of `nexus-rt`.
+    #[arg(long)]
+    pub std: bool,
 }
 
 pub fn handle_command(args: HostArgs) -> anyhow::Result<()> {
     let path = args.path;
     let rev = args.rev;
     let mut tag = args.tag;
+    let std = args.std;
 
     if rev.is_none() && tag.is_none() {
         // default to current release
         tag = Some(String::from("0.3.1"));
     }
 
-    setup_crate(path, rev, tag)
+    setup_crate(path, rev, tag, std)
 }
 
-fn setup_crate(host_path: PathBuf, rev: Option<String>, tag: Option<String>) -> anyhow::Result<()> {
+fn setup_crate(
+    host_path: PathBuf,
+    rev: Option<String>,
+    tag: Option<String>,
+    std: bool,
+) -> anyhow::Result<()> {
     assert!(rev.is_some() || tag.is_some());
 
     let host_str = host_path
@@ -86,17 +95,18 @@ fn setup_crate(host_path: PathBuf, rev: Option<String>, tag: Option<String>) ->
     fs::write(host_path.join("rust-toolchain.toml"), RUST_TOOLCHAIN)?;
 
     cargo(None, ["new", guest_str])?;
-    cargo(
-        Some(&guest_path),
-        [
-            "add",
-            "--git",
-            "https://github.com/nexus-xyz/nexus-zkvm.git",
-            arg,
-            &ver,
-            "nexus-rt",
-        ],
-    )?;
+    let mut rt_args = vec![
+        "add",
+        "--git",
+        "https://github.com/nexus-xyz/nexus-zkvm.git",
+        arg,
+        ver.as_str(),
+        "nexus-rt",
+    ];
+    if std {
+        rt_args.extend(["-F", "std-shim"]);
+    }
+    cargo(Some(&guest_path), rt_args)?;
 
     // add postcard because it is used for (de)serializing from/to the input/output tapes
     cargo(
@@ -134,7 +144,12 @@ fn setup_crate(host_path: PathBuf, rev: Option<String>, tag: Option<String>) ->
     )?;
 
     // guest/src/main.rs
-    fs::write(guest_path.join("src/main.rs"), GUEST_TEMPLATE_SRC_MAIN)?;
+    let guest_main = if std {
+        GUEST_TEMPLATE_STD_SRC_MAIN
+    } else {
+        GUEST_TEMPLATE_SRC_MAIN
+    };
+    fs::write(guest_path.join("src/main.rs"), guest_main)?;
     fs::write(guest_path.join("rust-toolchain.toml"), RUST_TOOLCHAIN)?;
 
     Ok(())
@@ -161,6 +176,15 @@ rustflags = [
 runner="nexus-run"
 "#;
 const GUEST_TEMPLATE_SRC_MAIN: &str = include_str!(concat!(guest_examples_dir!(), "/src/main.rs"));
+const GUEST_TEMPLATE_STD_SRC_MAIN: &str = r#"#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+use nexus_rt::shim::io::{self, Write};
+
+#[nexus_rt::main]
+fn main() {
+    writeln!(io::stdout(), "Hello, World!").unwrap();
+}
+"#;
 
 // freeze toolchain that works with all provers
 const RUST_TOOLCHAIN: &str = r#"[toolchain]
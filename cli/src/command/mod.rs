@@ -2,12 +2,15 @@ use clap::Subcommand;
 
 use super::ENV;
 
+pub mod debug;
 pub mod host;
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Create a new host/guest Nexus package at <path>.
     Host(host::HostArgs),
+    /// Debug a guest ELF interactively on the Harvard emulator.
+    Debug(debug::DebugArgs),
 }
 
 pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
@@ -15,5 +18,6 @@ pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
 
     match cmd {
         Command::Host(args) => host::handle_command(args),
+        Command::Debug(args) => debug::handle_command(args),
     }
 }
@@ -0,0 +1,272 @@
+//! Random RV32IM program generation for differential testing.
+
+use nexus_common::riscv::register::NUM_REGISTERS;
+use nexus_vm::riscv::{BuiltinOpcode, Instruction, Opcode};
+
+use super::{TestCase, BASE_REGISTER, SCRATCH_BASE, SCRATCH_SIZE};
+
+/// Small deterministic PRNG (SplitMix64), so that failures are reproducible from a seed alone.
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a value in `0..bound`.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % bound as u64) as u32
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u32) as usize]
+    }
+}
+
+const R_TYPE: &[BuiltinOpcode] = &[
+    BuiltinOpcode::ADD,
+    BuiltinOpcode::SUB,
+    BuiltinOpcode::SLL,
+    BuiltinOpcode::SLT,
+    BuiltinOpcode::SLTU,
+    BuiltinOpcode::XOR,
+    BuiltinOpcode::SRL,
+    BuiltinOpcode::SRA,
+    BuiltinOpcode::OR,
+    BuiltinOpcode::AND,
+    BuiltinOpcode::MUL,
+    BuiltinOpcode::MULH,
+    BuiltinOpcode::MULHSU,
+    BuiltinOpcode::MULHU,
+    BuiltinOpcode::DIV,
+    BuiltinOpcode::DIVU,
+    BuiltinOpcode::REM,
+    BuiltinOpcode::REMU,
+];
+
+const I_TYPE: &[BuiltinOpcode] = &[
+    BuiltinOpcode::ADDI,
+    BuiltinOpcode::SLTI,
+    BuiltinOpcode::SLTIU,
+    BuiltinOpcode::XORI,
+    BuiltinOpcode::ORI,
+    BuiltinOpcode::ANDI,
+];
+
+const SHAMT_TYPE: &[BuiltinOpcode] = &[
+    BuiltinOpcode::SLLI,
+    BuiltinOpcode::SRLI,
+    BuiltinOpcode::SRAI,
+];
+
+// (opcode, access size in bytes)
+const LOADS: &[(BuiltinOpcode, u32)] = &[
+    (BuiltinOpcode::LB, 1),
+    (BuiltinOpcode::LBU, 1),
+    (BuiltinOpcode::LH, 2),
+    (BuiltinOpcode::LHU, 2),
+    (BuiltinOpcode::LW, 4),
+];
+
+const STORES: &[(BuiltinOpcode, u32)] = &[
+    (BuiltinOpcode::SB, 1),
+    (BuiltinOpcode::SH, 2),
+    (BuiltinOpcode::SW, 4),
+];
+
+const BRANCHES: &[BuiltinOpcode] = &[
+    BuiltinOpcode::BEQ,
+    BuiltinOpcode::BNE,
+    BuiltinOpcode::BLT,
+    BuiltinOpcode::BGE,
+    BuiltinOpcode::BLTU,
+    BuiltinOpcode::BGEU,
+];
+
+/// Register values that tend to hit corner cases of comparisons, shifts and division.
+const INTERESTING_VALUES: &[u32] = &[
+    0,
+    1,
+    2,
+    31,
+    32,
+    0x7fff_ffff,
+    0x8000_0000,
+    0xffff_fffe,
+    0xffff_ffff,
+];
+
+/// Maximum number of instructions skipped by a generated branch or jump.
+const MAX_SKIP: u32 = 4;
+
+/// Generates random straight-line programs with forward-only control flow.
+///
+/// Every generated program terminates: branches and jumps only skip ahead, and the program ends
+/// once the pc leaves it. [`BASE_REGISTER`] is never written and holds the start of the scratch
+/// region, which all loads and stores are relative to.
+pub struct ProgramGenerator {
+    rng: SplitMix64,
+}
+
+impl ProgramGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SplitMix64::new(seed),
+        }
+    }
+
+    fn value(&mut self) -> u32 {
+        if self.rng.below(4) == 0 {
+            self.rng.pick(INTERESTING_VALUES)
+        } else {
+            self.rng.next_u32()
+        }
+    }
+
+    /// Any register, including `x0` and [`BASE_REGISTER`].
+    fn source(&mut self) -> u8 {
+        self.rng.below(NUM_REGISTERS as u32) as u8
+    }
+
+    /// Any register except [`BASE_REGISTER`], including `x0`.
+    fn destination(&mut self) -> u8 {
+        self.rng.below(BASE_REGISTER as u32) as u8
+    }
+
+    fn imm12(&mut self) -> u32 {
+        (((self.value() << 20) as i32) >> 20) as u32
+    }
+
+    fn skip(&mut self, remaining: usize) -> u32 {
+        1 + self.rng.below(MAX_SKIP.min(remaining as u32))
+    }
+
+    /// Generates a fresh test case of `len` instructions with random initial state.
+    pub fn generate(&mut self, len: usize) -> TestCase {
+        let mut registers = [0; NUM_REGISTERS];
+        for register in registers.iter_mut().skip(1) {
+            *register = self.value();
+        }
+
+        let memory = (0..SCRATCH_SIZE)
+            .map(|_| self.rng.next_u32() as u8)
+            .collect();
+
+        let mut program = Vec::with_capacity(len);
+        while program.len() < len {
+            program.extend(self.instruction(len - program.len()));
+        }
+
+        registers[BASE_REGISTER as usize] = SCRATCH_BASE;
+
+        TestCase {
+            registers,
+            memory,
+            program,
+        }
+    }
+
+    /// Generates one instruction, or a short sequence that has to stay together, fitting in
+    /// `remaining` slots.
+    fn instruction(&mut self, remaining: usize) -> Vec<Instruction> {
+        let ir = |opcode: BuiltinOpcode, op_a: u8, op_b: u8, op_c: u32| {
+            Instruction::new_ir(Opcode::from(opcode), op_a, op_b, op_c)
+        };
+
+        match self.rng.below(10) {
+            0..=2 => {
+                let opcode = self.rng.pick(R_TYPE);
+                let (rd, rs1, rs2) = (self.destination(), self.source(), self.source());
+                vec![ir(opcode, rd, rs1, rs2 as u32)]
+            }
+            3 => {
+                let opcode = self.rng.pick(I_TYPE);
+                let (rd, rs1, imm) = (self.destination(), self.source(), self.imm12());
+                vec![ir(opcode, rd, rs1, imm)]
+            }
+            4 => {
+                let opcode = self.rng.pick(SHAMT_TYPE);
+                let (rd, rs1, shamt) = (self.destination(), self.source(), self.rng.below(32));
+                vec![ir(opcode, rd, rs1, shamt)]
+            }
+            5 => {
+                let opcode = self.rng.pick(&[BuiltinOpcode::LUI, BuiltinOpcode::AUIPC]);
+                let (rd, imm) = (self.destination(), self.value() >> 12);
+                vec![ir(opcode, rd, 0, imm)]
+            }
+            6 => {
+                let (opcode, size) = self.rng.pick(LOADS);
+                let offset = self.rng.below(SCRATCH_SIZE / size) * size;
+                let rd = self.destination();
+                vec![ir(opcode, rd, BASE_REGISTER as u8, offset)]
+            }
+            7 => {
+                let (opcode, size) = self.rng.pick(STORES);
+                let offset = self.rng.below(SCRATCH_SIZE / size) * size;
+                let rs2 = self.source();
+                vec![ir(opcode, BASE_REGISTER as u8, rs2, offset)]
+            }
+            8 => {
+                let opcode = self.rng.pick(BRANCHES);
+                let (rs1, rs2) = (self.source(), self.source());
+                let offset = self.skip(remaining) * 4;
+                vec![ir(opcode, rs1, rs2, offset)]
+            }
+            _ if remaining >= 2 && self.rng.below(2) == 0 => {
+                // auipc t, 0; jalr rd, offset(t), landing past the pair.
+                let (rd, t) = (self.destination(), 1 + self.rng.below(30) as u8);
+                let offset = (1 + self.skip(remaining - 1)) * 4;
+                vec![
+                    ir(BuiltinOpcode::AUIPC, t, 0, 0),
+                    ir(BuiltinOpcode::JALR, rd, t, offset),
+                ]
+            }
+            _ => {
+                let rd = self.destination();
+                let offset = self.skip(remaining) * 4;
+                vec![ir(BuiltinOpcode::JAL, rd, 0, offset)]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::riscv::InstructionType;
+
+    #[test]
+    fn test_generator_is_deterministic() {
+        let a = ProgramGenerator::new(7).generate(32);
+        let b = ProgramGenerator::new(7).generate(32);
+        assert_eq!(a, b);
+        assert_eq!(a.program.len(), 32);
+        assert_ne!(a, ProgramGenerator::new(8).generate(32));
+    }
+
+    #[test]
+    fn test_generator_preserves_base_register() {
+        let case = ProgramGenerator::new(3).generate(256);
+        assert!(case
+            .program
+            .iter()
+            .filter(|ins| !matches!(
+                ins.ins_type,
+                InstructionType::SType | InstructionType::BType
+            ))
+            .all(|ins| ins.op_a != BASE_REGISTER));
+    }
+}
@@ -0,0 +1,232 @@
+//! A minimal RV32IM interpreter used as the reference for differential testing.
+//!
+//! The model is deliberately written against the raw instruction bits and does not share any code
+//! with `nexus_vm` (decoder, instruction executors or memory), so that a bug in the emulator cannot
+//! silently be mirrored here.
+
+use std::collections::BTreeMap;
+
+use nexus_common::riscv::register::NUM_REGISTERS;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldenError {
+    /// The word does not encode an RV32IM instruction supported by the model.
+    IllegalInstruction(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenModel {
+    pub registers: [u32; NUM_REGISTERS],
+    pub pc: u32,
+    /// Sparse byte-addressed memory, absent bytes read as zero.
+    pub memory: BTreeMap<u32, u8>,
+}
+
+impl GoldenModel {
+    pub fn new(pc: u32, registers: [u32; NUM_REGISTERS]) -> Self {
+        let mut registers = registers;
+        registers[0] = 0;
+
+        Self {
+            registers,
+            pc,
+            memory: BTreeMap::new(),
+        }
+    }
+
+    pub fn read_byte(&self, address: u32) -> u8 {
+        self.memory.get(&address).copied().unwrap_or_default()
+    }
+
+    pub fn write_byte(&mut self, address: u32, value: u8) {
+        self.memory.insert(address, value);
+    }
+
+    fn load(&self, address: u32, size: u32) -> u32 {
+        (0..size).fold(0, |acc, i| {
+            acc | ((self.read_byte(address.wrapping_add(i)) as u32) << (8 * i))
+        })
+    }
+
+    fn store(&mut self, address: u32, size: u32, value: u32) {
+        for i in 0..size {
+            self.write_byte(address.wrapping_add(i), (value >> (8 * i)) as u8);
+        }
+    }
+
+    fn set(&mut self, rd: usize, value: u32) {
+        if rd != 0 {
+            self.registers[rd] = value;
+        }
+    }
+
+    /// Executes a single 32-bit instruction word at the current pc.
+    pub fn step(&mut self, word: u32) -> Result<(), GoldenError> {
+        let illegal = Err(GoldenError::IllegalInstruction(word));
+
+        let opcode = word & 0x7f;
+        let rd = ((word >> 7) & 0x1f) as usize;
+        let funct3 = (word >> 12) & 0x7;
+        let rs1 = ((word >> 15) & 0x1f) as usize;
+        let rs2 = ((word >> 20) & 0x1f) as usize;
+        let funct7 = word >> 25;
+
+        let imm_i = (word as i32 >> 20) as u32;
+        let imm_s = ((word as i32 >> 25) << 5) as u32 | ((word >> 7) & 0x1f);
+        let imm_b = ((word as i32 >> 31) << 12) as u32
+            | ((word & 0x80) << 4)
+            | ((word >> 20) & 0x7e0)
+            | ((word >> 7) & 0x1e);
+        let imm_u = word & 0xffff_f000;
+        let imm_j = ((word as i32 >> 31) << 20) as u32
+            | (word & 0xf_f000)
+            | ((word >> 9) & 0x800)
+            | ((word >> 20) & 0x7fe);
+
+        let a = self.registers[rs1];
+        let b = self.registers[rs2];
+        let mut next_pc = self.pc.wrapping_add(4);
+
+        match opcode {
+            // OP
+            0b011_0011 => {
+                let value = match (funct7, funct3) {
+                    (0b000_0000, 0b000) => a.wrapping_add(b),
+                    (0b010_0000, 0b000) => a.wrapping_sub(b),
+                    (0b000_0000, 0b001) => a << (b & 0x1f),
+                    (0b000_0000, 0b010) => ((a as i32) < (b as i32)) as u32,
+                    (0b000_0000, 0b011) => (a < b) as u32,
+                    (0b000_0000, 0b100) => a ^ b,
+                    (0b000_0000, 0b101) => a >> (b & 0x1f),
+                    (0b010_0000, 0b101) => ((a as i32) >> (b & 0x1f)) as u32,
+                    (0b000_0000, 0b110) => a | b,
+                    (0b000_0000, 0b111) => a & b,
+                    (0b000_0001, 0b000) => a.wrapping_mul(b),
+                    (0b000_0001, 0b001) => ((a as i32 as i64 * b as i32 as i64) >> 32) as u32,
+                    (0b000_0001, 0b010) => ((a as i32 as i64 * b as i64) >> 32) as u32,
+                    (0b000_0001, 0b011) => ((a as u64 * b as u64) >> 32) as u32,
+                    (0b000_0001, 0b100) => match (a as i32, b as i32) {
+                        (_, 0) => u32::MAX,
+                        (i32::MIN, -1) => a,
+                        (x, y) => (x / y) as u32,
+                    },
+                    (0b000_0001, 0b101) => a.checked_div(b).unwrap_or(u32::MAX),
+                    (0b000_0001, 0b110) => match (a as i32, b as i32) {
+                        (_, 0) => a,
+                        (i32::MIN, -1) => 0,
+                        (x, y) => (x % y) as u32,
+                    },
+                    (0b000_0001, 0b111) => a.checked_rem(b).unwrap_or(a),
+                    _ => return illegal,
+                };
+                self.set(rd, value);
+            }
+            // OP-IMM
+            0b001_0011 => {
+                let shamt = rs2 as u32;
+                let value = match (funct3, funct7) {
+                    (0b000, _) => a.wrapping_add(imm_i),
+                    (0b010, _) => ((a as i32) < (imm_i as i32)) as u32,
+                    (0b011, _) => (a < imm_i) as u32,
+                    (0b100, _) => a ^ imm_i,
+                    (0b110, _) => a | imm_i,
+                    (0b111, _) => a & imm_i,
+                    (0b001, 0b000_0000) => a << shamt,
+                    (0b101, 0b000_0000) => a >> shamt,
+                    (0b101, 0b010_0000) => ((a as i32) >> shamt) as u32,
+                    _ => return illegal,
+                };
+                self.set(rd, value);
+            }
+            // LOAD
+            0b000_0011 => {
+                let address = a.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0b000 => self.load(address, 1) as i8 as i32 as u32,
+                    0b001 => self.load(address, 2) as i16 as i32 as u32,
+                    0b010 => self.load(address, 4),
+                    0b100 => self.load(address, 1),
+                    0b101 => self.load(address, 2),
+                    _ => return illegal,
+                };
+                self.set(rd, value);
+            }
+            // STORE
+            0b010_0011 => {
+                let address = a.wrapping_add(imm_s);
+                match funct3 {
+                    0b000 => self.store(address, 1, b),
+                    0b001 => self.store(address, 2, b),
+                    0b010 => self.store(address, 4, b),
+                    _ => return illegal,
+                }
+            }
+            // BRANCH
+            0b110_0011 => {
+                let taken = match funct3 {
+                    0b000 => a == b,
+                    0b001 => a != b,
+                    0b100 => (a as i32) < (b as i32),
+                    0b101 => (a as i32) >= (b as i32),
+                    0b110 => a < b,
+                    0b111 => a >= b,
+                    _ => return illegal,
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            // LUI
+            0b011_0111 => self.set(rd, imm_u),
+            // AUIPC
+            0b001_0111 => self.set(rd, self.pc.wrapping_add(imm_u)),
+            // JAL
+            0b110_1111 => {
+                self.set(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j);
+            }
+            // JALR
+            0b110_0111 if funct3 == 0 => {
+                self.set(rd, next_pc);
+                next_pc = a.wrapping_add(imm_i) & !1;
+            }
+            _ => return illegal,
+        }
+
+        self.pc = next_pc;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_model_basic_encodings() {
+        let mut model = GoldenModel::new(0x1000, [0; NUM_REGISTERS]);
+
+        // addi a0, zero, -1
+        model.step(0xfff0_0513).unwrap();
+        assert_eq!(model.registers[10], u32::MAX);
+
+        // sw a0, 8(zero); lbu a1, 9(zero)
+        model.step(0x00a0_2423).unwrap();
+        model.step(0x0090_4583).unwrap();
+        assert_eq!(model.registers[11], 0xff);
+
+        // div a2, a0, zero
+        model.step(0x0205_4633).unwrap();
+        assert_eq!(model.registers[12], u32::MAX);
+
+        // jal ra, -8
+        model.step(0xff9f_f0ef).unwrap();
+        assert_eq!(model.registers[1], 0x1014);
+        assert_eq!(model.pc, 0x1008);
+
+        assert_eq!(
+            model.step(0x0000_0073),
+            Err(GoldenError::IllegalInstruction(0x0000_0073))
+        );
+    }
+}
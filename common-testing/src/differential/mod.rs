@@ -0,0 +1,372 @@
+//! Differential testing of the emulator against an independent golden model.
+//!
+//! Random RV32IM programs are produced by [`ProgramGenerator`], encoded with the common encoder and
+//! executed step by step on both [`HarvardEmulator`] and [`GoldenModel`]. After every instruction
+//! the pc, all registers and the touched memory are compared. On the first divergence the test
+//! case is shrunk to a minimal reproducer, which is what gets reported.
+//!
+//! ```ignore
+//! if let Err(reproducer) = nexus_common_testing::differential::fuzz(0x5eed, 100, 64) {
+//!     panic!("{reproducer}");
+//! }
+//! ```
+
+mod generator;
+mod golden;
+
+pub use generator::{ProgramGenerator, SplitMix64};
+pub use golden::{GoldenError, GoldenModel};
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use nexus_common::constants::{ELF_TEXT_START, WORD_SIZE};
+use nexus_common::cpu::Registers;
+use nexus_common::riscv::register::NUM_REGISTERS;
+use nexus_vm::emulator::{Emulator, HarvardEmulator};
+use nexus_vm::memory::{LoadOp, MemAccessSize, MemoryProcessor, MemoryRecord, MemoryRecords};
+use nexus_vm::riscv::{BasicBlock, Instruction, Register};
+
+/// Register holding [`SCRATCH_BASE`], which generated programs never overwrite.
+pub const BASE_REGISTER: Register = Register::X31;
+
+/// Start of the data region accessed by generated loads and stores.
+pub const SCRATCH_BASE: u32 = 0x0010_0000;
+
+/// Size in bytes of the data region accessed by generated loads and stores.
+pub const SCRATCH_SIZE: u32 = 0x100;
+
+/// A program together with the initial machine state it runs from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub registers: [u32; NUM_REGISTERS],
+    /// Initial contents of the scratch region.
+    pub memory: Vec<u8>,
+    pub program: Vec<Instruction>,
+}
+
+impl TestCase {
+    fn words(&self) -> Vec<u32> {
+        self.program.iter().map(Instruction::encode).collect()
+    }
+
+    fn golden_model(&self) -> GoldenModel {
+        let mut model = GoldenModel::new(ELF_TEXT_START, self.registers);
+        for (address, &byte) in (SCRATCH_BASE..).zip(&self.memory) {
+            model.write_byte(address, byte);
+        }
+        model
+    }
+
+    fn emulator(&self) -> HarvardEmulator {
+        let mut emulator =
+            HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(self.program.clone())]);
+        for (i, &value) in self.registers.iter().enumerate() {
+            emulator
+                .executor
+                .cpu
+                .registers
+                .write(Register::from(i as u8), value);
+        }
+        for (address, &byte) in (SCRATCH_BASE..).zip(&self.memory) {
+            emulator
+                .data_memory
+                .write(address, MemAccessSize::Byte, byte as u32)
+                .expect("scratch region must be writable");
+        }
+        emulator
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    Pc {
+        emulator: u32,
+        golden: u32,
+    },
+    Register {
+        register: Register,
+        emulator: u32,
+        golden: u32,
+    },
+    Memory {
+        address: u32,
+        emulator: u8,
+        golden: u8,
+    },
+    Emulator(String),
+    Golden(GoldenError),
+}
+
+/// The first point at which the emulator and the golden model disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Number of instructions retired before the diverging one.
+    pub step: usize,
+    pub pc: u32,
+    pub instruction: Instruction,
+    pub mismatch: Mismatch,
+}
+
+/// A minimized failing test case, along with the seed it was generated from.
+#[derive(Debug, Clone)]
+pub struct Reproducer {
+    pub seed: u64,
+    pub case: TestCase,
+    pub divergence: Divergence,
+}
+
+impl Display for Reproducer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Divergence {
+            step,
+            pc,
+            instruction,
+            mismatch,
+        } = &self.divergence;
+        writeln!(
+            f,
+            "divergence at step {step}, pc {pc:#010x} (`{instruction}`): {mismatch:?}"
+        )?;
+        writeln!(f, "seed: {:#x}", self.seed)?;
+
+        writeln!(f, "initial registers:")?;
+        for (i, value) in self.case.registers.iter().enumerate().skip(1) {
+            if *value != 0 {
+                writeln!(f, "  {} = {value:#010x}", Register::from(i as u8))?;
+            }
+        }
+        if self.case.memory.iter().any(|&byte| byte != 0) {
+            writeln!(f, "initial scratch memory at {SCRATCH_BASE:#010x}:")?;
+            for (i, chunk) in self.case.memory.chunks(16).enumerate() {
+                let bytes: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+                writeln!(f, "  +{:#05x}: {}", i * 16, bytes.join(" "))?;
+            }
+        }
+
+        writeln!(f, "program:")?;
+        for (i, (instruction, word)) in self.case.program.iter().zip(self.case.words()).enumerate()
+        {
+            let address = ELF_TEXT_START + (i * WORD_SIZE) as u32;
+            writeln!(f, "  {address:#010x}: {word:08x}  {instruction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Executes one instruction on the emulator, returning its memory records.
+fn step_emulator(emulator: &mut HarvardEmulator) -> nexus_vm::error::Result<MemoryRecords> {
+    let pc = emulator.executor.cpu.pc.value;
+    let (entry, index) = emulator.fetch_block_at(pc)?;
+    let (_, records) = emulator.execute_instruction(&entry.block.0[index], false)?;
+
+    Ok(records)
+}
+
+fn compare(
+    emulator: &HarvardEmulator,
+    golden: &GoldenModel,
+    records: &MemoryRecords,
+) -> Result<(), Mismatch> {
+    let cpu = &emulator.executor.cpu;
+    if cpu.pc.value != golden.pc {
+        return Err(Mismatch::Pc {
+            emulator: cpu.pc.value,
+            golden: golden.pc,
+        });
+    }
+
+    for (i, &expected) in golden.registers.iter().enumerate() {
+        let register = Register::from(i as u8);
+        let actual = cpu.registers.read(register);
+        if actual != expected {
+            return Err(Mismatch::Register {
+                register,
+                emulator: actual,
+                golden: expected,
+            });
+        }
+    }
+
+    // Check every byte the golden model knows about, plus whatever the emulator claims to have
+    // stored, which catches writes landing outside of the expected locations.
+    let stored = records.iter().flat_map(|record| match record {
+        MemoryRecord::StoreRecord((size, address, _, _), _) => {
+            (*address..*address + *size as u32).collect::<Vec<_>>()
+        }
+        MemoryRecord::LoadRecord(..) => Vec::new(),
+    });
+    let addresses: BTreeSet<u32> = golden.memory.keys().copied().chain(stored).collect();
+    for address in addresses {
+        let LoadOp::Op(_, _, actual) = emulator
+            .data_memory
+            .read(address, MemAccessSize::Byte)
+            .map_err(|e| Mismatch::Emulator(e.to_string()))?;
+        let expected = golden.read_byte(address);
+        if actual != expected as u32 {
+            return Err(Mismatch::Memory {
+                address,
+                emulator: actual as u8,
+                golden: expected,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `case` on both machines in lockstep until the program is left, returning the number of
+/// executed instructions or the first divergence.
+pub fn run(case: &TestCase) -> Result<usize, Divergence> {
+    let words = case.words();
+    let end = ELF_TEXT_START + (words.len() * WORD_SIZE) as u32;
+
+    let mut golden = case.golden_model();
+    let mut emulator = case.emulator();
+
+    let mut step = 0;
+    while (ELF_TEXT_START..end).contains(&golden.pc) && golden.pc % WORD_SIZE as u32 == 0 {
+        let pc = golden.pc;
+        let index = (pc - ELF_TEXT_START) as usize / WORD_SIZE;
+        let instruction = case.program[index].clone();
+        let diverge = move |mismatch| Divergence {
+            step,
+            pc,
+            instruction: instruction.clone(),
+            mismatch,
+        };
+
+        golden
+            .step(words[index])
+            .map_err(|e| diverge(Mismatch::Golden(e)))?;
+        let records =
+            step_emulator(&mut emulator).map_err(|e| diverge(Mismatch::Emulator(e.to_string())))?;
+        compare(&emulator, &golden, &records).map_err(diverge)?;
+
+        step += 1;
+    }
+
+    Ok(step)
+}
+
+/// Shrinks `case` while `fails` keeps holding: the program is truncated, instructions are
+/// replaced by `nop`s, and initial registers and memory are zeroed wherever possible.
+///
+/// Instructions are never removed from the middle of the program, as that would change the
+/// targets of the surrounding branches and jumps.
+pub fn minimize(case: &TestCase, fails: impl Fn(&TestCase) -> bool) -> TestCase {
+    let mut best = case.clone();
+
+    if let Some(len) = (1..best.program.len()).find(|&len| {
+        let mut candidate = best.clone();
+        candidate.program.truncate(len);
+        fails(&candidate)
+    }) {
+        best.program.truncate(len);
+    }
+
+    for i in 0..best.program.len() {
+        if best.program[i] == Instruction::nop() {
+            continue;
+        }
+        let mut candidate = best.clone();
+        candidate.program[i] = Instruction::nop();
+        if fails(&candidate) {
+            best = candidate;
+        }
+    }
+
+    for i in 1..NUM_REGISTERS {
+        if i == BASE_REGISTER as usize || best.registers[i] == 0 {
+            continue;
+        }
+        let mut candidate = best.clone();
+        candidate.registers[i] = 0;
+        if fails(&candidate) {
+            best = candidate;
+        }
+    }
+
+    let mut candidate = best.clone();
+    candidate.memory.fill(0);
+    if fails(&candidate) {
+        best = candidate;
+    }
+
+    best
+}
+
+/// Runs `cases` random programs of `len` instructions, derived from `seed`, and returns a
+/// minimized reproducer for the first divergence found.
+pub fn fuzz(seed: u64, cases: usize, len: usize) -> Result<(), Reproducer> {
+    let mut seeds = SplitMix64::new(seed);
+
+    for _ in 0..cases {
+        let seed = seeds.next_u64();
+        let case = ProgramGenerator::new(seed).generate(len);
+        if run(&case).is_err() {
+            let case = minimize(&case, |candidate| run(candidate).is_err());
+            let divergence = run(&case).expect_err("minimized case must still diverge");
+            return Err(Reproducer {
+                seed,
+                case,
+                divergence,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::riscv::{BuiltinOpcode, Opcode};
+
+    #[test]
+    fn test_emulator_matches_golden_model() {
+        if let Err(reproducer) = fuzz(0x5eed, 200, 64) {
+            panic!("{reproducer}");
+        }
+    }
+
+    #[test]
+    fn test_run_executes_whole_program() {
+        // addi a0, a0, 1; jal zero, 8; addi a0, a0, 1; sw a0, 0(t6)
+        let program = vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 31, 10, 0),
+        ];
+        let mut registers = [0; NUM_REGISTERS];
+        registers[BASE_REGISTER as usize] = SCRATCH_BASE;
+        let case = TestCase {
+            registers,
+            memory: vec![0; SCRATCH_SIZE as usize],
+            program,
+        };
+
+        assert_eq!(run(&case), Ok(3));
+    }
+
+    #[test]
+    fn test_minimize_keeps_only_the_culprit() {
+        let mut case = ProgramGenerator::new(1).generate(32);
+        let culprit = Instruction::new_ir(Opcode::from(BuiltinOpcode::MULHU), 5, 6, 7);
+        case.program[20] = culprit.clone();
+
+        let has_culprit = |candidate: &TestCase| candidate.program.contains(&culprit);
+        let minimized = minimize(&case, has_culprit);
+
+        assert!(minimized.program.len() <= 21);
+        assert_eq!(minimized.program.last(), Some(&culprit));
+        assert!(minimized.program[..minimized.program.len() - 1]
+            .iter()
+            .all(|ins| *ins == Instruction::nop()));
+        assert!(minimized.registers[1..BASE_REGISTER as usize]
+            .iter()
+            .all(|&value| value == 0));
+        assert!(minimized.memory.iter().all(|&byte| byte == 0));
+    }
+}
@@ -6,7 +6,7 @@ use nexus_vm::emulator::MemoryTranscript;
 use nexus_vm::emulator::{Emulator, InternalView};
 use nexus_vm::emulator::{HarvardEmulator, LinearEmulator, LinearMemoryLayout};
 use nexus_vm::error::Result;
-use postcard::from_bytes_cobs;
+use postcard::{from_bytes_cobs, to_allocvec_cobs};
 use serde::{de::DeserializeOwned, Serialize};
 
 use std::{path::PathBuf, process::Command};
@@ -89,14 +89,25 @@ pub fn parse_output<T: DeserializeOwned>(
     if output.len() == 0 {
         Ok((exit_code, None))
     } else {
-        // Deserialize the rest as the output.
-        let output: T = from_bytes_cobs(&mut output).expect("Deserialization failed");
+        // Deserialize the rest as the output, skipping the segment tag.
+        let (_, output): (u8, T) = from_bytes_cobs(&mut output).expect("Deserialization failed");
         Ok((exit_code, Some(output)))
     }
 }
 
+/// Serialize an output as the runtime writes it to the public output, in a single unnamed segment.
+pub fn serialize_output<T: Serialize>(output: &T) -> Vec<u8> {
+    const UNNAMED_SEGMENT: u8 = 0;
+    to_allocvec_cobs(&(UNNAMED_SEGMENT, output)).expect("Failed to serialize output")
+}
+
 /// Create a temporary directory with a new Cargo project that has nexus_rt as a local dependency.
 pub fn setup_guest_project(runtime_path: &PathBuf) -> TempDir {
+    setup_guest_project_with_features(runtime_path, &[])
+}
+
+/// Same as [`setup_guest_project`], enabling the given features of nexus_rt.
+pub fn setup_guest_project_with_features(runtime_path: &PathBuf, features: &[&str]) -> TempDir {
     // Create a temporary directory.
     let tmp_dir = tempdir().expect("Failed to create temporary directory");
     let tmp_dir_path = tmp_dir.path().join("integration");
@@ -113,20 +124,43 @@ pub fn setup_guest_project(runtime_path: &PathBuf) -> TempDir {
 
     // Add the nexus_rt dependency to the `Cargo.toml` file.
     let runtime_dir = std::env::current_dir().unwrap().join(runtime_path);
-    output = Command::new("cargo")
+    let mut command = Command::new("cargo");
+    command
         .current_dir(tmp_dir_str)
         .arg("add")
         .arg("nexus-rt")
         .arg("--path")
-        .arg(runtime_dir)
-        .output()
-        .expect("Failed to add nexus_rt dependency");
+        .arg(runtime_dir);
+    if !features.is_empty() {
+        command.arg("--features").arg(features.join(","));
+    }
+    output = command.output().expect("Failed to add nexus_rt dependency");
 
     assert!(output.status.success());
 
     tmp_dir
 }
 
+/// Add crates.io dependencies to a guest project created by [`setup_guest_project`].
+///
+/// Each entry is passed to `cargo add` as whitespace-separated arguments, e.g.
+/// `"serde_json --no-default-features --features alloc"`.
+pub fn add_guest_dependencies(tmp_project_path: &PathBuf, dependencies: &[&str]) {
+    for dependency in dependencies {
+        let output = Command::new("cargo")
+            .current_dir(tmp_project_path)
+            .arg("add")
+            .args(dependency.split_whitespace())
+            .output()
+            .expect("Failed to add guest dependency");
+
+        if !output.status.success() {
+            eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
+            panic!("cargo add failed for {dependency}");
+        }
+    }
+}
+
 /// Setup project.
 pub fn write_guest_source_code(tmp_project_path: &PathBuf, test_path: &str) {
     // Overwrite the main.rs file with the test file.
@@ -187,10 +221,23 @@ pub fn compile_multi(
     test_name: &str,
     compile_flags: &[&str],
     home_path_relative: &str,
+) -> Vec<ElfFile> {
+    compile_multi_with_features(test_name, &[], compile_flags, home_path_relative)
+}
+
+/// Same as [`compile_multi`], building the guest with the given features of nexus_rt.
+pub fn compile_multi_with_features(
+    test_name: &str,
+    runtime_features: &[&str],
+    compile_flags: &[&str],
+    home_path_relative: &str,
 ) -> Vec<ElfFile> {
     let mut elves = Vec::new();
     // Set up the temporary directories for intermediate project setup.
-    let tmp_dir = &setup_guest_project(&PathBuf::from(home_path_relative).join("runtime"));
+    let tmp_dir = &setup_guest_project_with_features(
+        &PathBuf::from(home_path_relative).join("runtime"),
+        runtime_features,
+    );
     let tmp_project_path = tmp_dir.path().join("integration");
 
     for flag_set in compile_flags {
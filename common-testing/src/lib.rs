@@ -1,3 +1,4 @@
+pub mod differential;
 pub mod emulator;
 
 use nexus_vm::riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode};
@@ -9,6 +9,11 @@ impl PC {
         self.value = self.value.wrapping_add(4);
     }
 
+    // Increment PC by the given instruction length (2 bytes for compressed instructions)
+    pub fn step_by(&mut self, size: u32) {
+        self.value = self.value.wrapping_add(size);
+    }
+
     // Branch: Add immediate value to PC
     pub fn branch(&mut self, imm: u32) {
         self.value = self.value.wrapping_add(sign_extension_branch(imm));
@@ -1,5 +1,47 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use thiserror::Error;
 
+/// Direction of a memory access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+impl Display for AccessKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Read => f.write_str("read"),
+            Self::Write => f.write_str("write"),
+        }
+    }
+}
+
+/// Reason for a memory access to be stopped by a watchpoint or by strict mode checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessViolation {
+    /// The access touched a watched address range.
+    Watchpoint,
+    /// The access fell into the unmapped gap between heap and stack.
+    HeapStackGap,
+    /// The access fell below the bottom of the stack while the stack pointer was there too.
+    StackOverflow,
+    /// The access wrote to a read-only region.
+    ReadOnlyWrite,
+}
+
+impl Display for AccessViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Watchpoint => f.write_str("watchpoint"),
+            Self::HeapStackGap => f.write_str("access into the heap-stack gap"),
+            Self::StackOverflow => f.write_str("stack overflow"),
+            Self::ReadOnlyWrite => f.write_str("write to read-only memory"),
+        }
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum MemoryError {
     // Cannot write unaligned memory
@@ -47,4 +89,8 @@ pub enum MemoryError {
     // Invalid memory segment
     #[error("Invalid memory segment")]
     InvalidMemorySegment,
+
+    // Access stopped by a watchpoint or strict mode checks
+    #[error("Memory access violation: {2} on {1} of address 0x{0:08X}")]
+    AccessViolation(u32, AccessKind, AccessViolation),
 }
@@ -1,5 +1,5 @@
 mod memory;
 mod opcode;
 
-pub use memory::MemoryError;
+pub use memory::{AccessKind, AccessViolation, MemoryError};
 pub use opcode::OpcodeError;
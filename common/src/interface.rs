@@ -0,0 +1,16 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The I/O signature of a guest program, shared between the guest and its host.
+///
+/// Implement this trait on a marker type in a crate that both the guest and the host depend on, then pass
+/// it to `#[nexus_rt::main(interface = ...)]` in the guest and to `nexus_sdk::Typed` in the host. Both
+/// sides are then checked against the same types at compile time. The trait is re-exported as
+/// `nexus_rt::Interface` and `nexus_sdk::Interface`.
+pub trait Interface {
+    /// The private input of the guest program, read off the private input tape.
+    type PrivateInput: Serialize + DeserializeOwned;
+    /// The public input of the guest program, read from the public input segment.
+    type PublicInput: Serialize + DeserializeOwned;
+    /// The public output of the guest program, written to the public output segment.
+    type PublicOutput: Serialize + DeserializeOwned;
+}
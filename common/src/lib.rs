@@ -1,5 +1,13 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 pub mod constants;
+#[cfg(feature = "std")]
 pub mod cpu;
+#[cfg(feature = "std")]
 pub mod error;
+pub mod interface;
+#[cfg(feature = "std")]
 pub mod memory;
+#[cfg(feature = "std")]
 pub mod riscv;
+pub mod schema;
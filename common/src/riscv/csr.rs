@@ -0,0 +1,106 @@
+//! Counter CSRs from the Zicsr/Zicntr extensions.
+//!
+//! Only the read-only user counters are supported, i.e. the `csrrs rd, csr, x0` forms emitted by
+//! the `rdcycle`, `rdtime` and `rdinstret` pseudo-instructions (and their `h` variants, which
+//! return the upper 32 bits). Every other CSR access decodes to `unimpl`.
+//!
+//! All three counters are backed by the emulator's global clock: the VM retires exactly one
+//! instruction per cycle and has no notion of wall-clock time, so `cycle`, `time` and `instret`
+//! always hold the same value, namely the timestamp of the instruction reading them.
+//!
+//! # Provability
+//!
+//! Counter reads are deterministic: both emulation passes execute the same instruction sequence,
+//! so a guest observes identical values in each of them. The values are, however, **not
+//! constrained by the prover**. Programs reading counter CSRs can be executed and profiled, but
+//! proving their traces is rejected. Use the `CycleCount` syscall when cycle measurements need to
+//! coexist with proving.
+
+/// A counter CSR readable through `csrrs rd, csr, x0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CounterCsr {
+    Cycle,
+    Time,
+    Instret,
+    CycleH,
+    TimeH,
+    InstretH,
+}
+
+impl CounterCsr {
+    /// Returns the counter for the given 12-bit CSR address, if it is supported.
+    pub fn from_address(address: u32) -> Option<Self> {
+        match address {
+            0xC00 => Some(Self::Cycle),
+            0xC01 => Some(Self::Time),
+            0xC02 => Some(Self::Instret),
+            0xC80 => Some(Self::CycleH),
+            0xC81 => Some(Self::TimeH),
+            0xC82 => Some(Self::InstretH),
+            _ => None,
+        }
+    }
+
+    /// Returns the 12-bit CSR address of the counter.
+    pub fn address(&self) -> u32 {
+        match self {
+            Self::Cycle => 0xC00,
+            Self::Time => 0xC01,
+            Self::Instret => 0xC02,
+            Self::CycleH => 0xC80,
+            Self::TimeH => 0xC81,
+            Self::InstretH => 0xC82,
+        }
+    }
+
+    /// Returns the assembler name of the counter.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cycle => "cycle",
+            Self::Time => "time",
+            Self::Instret => "instret",
+            Self::CycleH => "cycleh",
+            Self::TimeH => "timeh",
+            Self::InstretH => "instreth",
+        }
+    }
+
+    /// Returns the 32-bit view of the 64-bit counter `value` exposed by this CSR.
+    pub fn read(&self, value: u64) -> u32 {
+        match self {
+            Self::Cycle | Self::Time | Self::Instret => value as u32,
+            Self::CycleH | Self::TimeH | Self::InstretH => (value >> 32) as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_csr_addresses_roundtrip() {
+        for csr in [
+            CounterCsr::Cycle,
+            CounterCsr::Time,
+            CounterCsr::Instret,
+            CounterCsr::CycleH,
+            CounterCsr::TimeH,
+            CounterCsr::InstretH,
+        ] {
+            assert_eq!(CounterCsr::from_address(csr.address()), Some(csr));
+        }
+
+        // mcycle and friends are machine-mode CSRs and stay unsupported.
+        assert_eq!(CounterCsr::from_address(0xB00), None);
+        assert_eq!(CounterCsr::from_address(0x300), None);
+    }
+
+    #[test]
+    fn test_counter_csr_read_halves() {
+        let value = 0x0000_0001_0000_0002;
+        assert_eq!(CounterCsr::Cycle.read(value), 2);
+        assert_eq!(CounterCsr::CycleH.read(value), 1);
+        assert_eq!(CounterCsr::InstretH.read(value), 1);
+    }
+}
@@ -104,6 +104,8 @@ fn encode_j_type(instruction: &Instruction) -> u32 {
 }
 
 /// Encodes an instruction into its binary representation to little-endian format.
+///
+/// Compressed instructions are encoded as their 32-bit expansion.
 pub fn encode_instruction(instruction: &Instruction) -> u32 {
     if instruction.opcode.is_builtin() {
         match instruction.ins_type {
@@ -139,6 +141,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 3.into(),
             op_c: 1,
+            compressed: false,
         };
         let encoded_r = r_instruction.encode();
         assert_eq!(encoded_r, 0x118133);
@@ -150,6 +153,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 3.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_i = i_instruction.encode();
         assert_eq!(encoded_i, 0xA18113);
@@ -161,6 +165,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 3.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_s = s_instruction.encode();
         assert_eq!(encoded_s, 0x312523);
@@ -172,6 +177,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 3.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_b = b_instruction.encode();
         assert_eq!(encoded_b, 0x310563);
@@ -183,6 +189,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 0.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_u = u_instruction.encode();
         assert_eq!(encoded_u, 0xA137);
@@ -194,6 +201,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 0.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_j = j_instruction.encode();
         assert_eq!(encoded_j, 0xA0016F);
@@ -205,6 +213,7 @@ mod tests {
             op_a: 2.into(),
             op_b: 3.into(),
             op_c: 10,
+            compressed: false,
         };
         let encoded_i_shamt = i_shamt_instruction.encode();
         assert_eq!(encoded_i_shamt, 0x40A1D113);
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
-use crate::riscv::{encode_instruction, opcode::BuiltinOpcode};
+use crate::constants::{WORD_SIZE, WORD_SIZE_HALVED};
+use crate::riscv::{csr::CounterCsr, encode_instruction, opcode::BuiltinOpcode};
 
 use super::{register::Register, Opcode};
 
@@ -32,6 +33,9 @@ pub struct Instruction {
     // Op_c can be either 12-bit immediate, 20-bit immediate, or a register index 5 bits wide.
     pub op_c: u32,
     pub ins_type: InstructionType,
+    // Whether the instruction was expanded from a 16-bit RVC encoding.
+    #[serde(default)]
+    pub compressed: bool,
 }
 
 impl Instruction {
@@ -52,6 +56,24 @@ impl Instruction {
             op_b,
             op_c,
             ins_type,
+            compressed: false,
+        }
+    }
+
+    /// Marks the instruction as expanded from a 16-bit compressed (RVC) encoding.
+    pub fn into_compressed(self) -> Self {
+        Self {
+            compressed: true,
+            ..self
+        }
+    }
+
+    /// Returns the size in bytes of the encoding the instruction was decoded from.
+    pub fn size(&self) -> usize {
+        if self.compressed {
+            WORD_SIZE_HALVED
+        } else {
+            WORD_SIZE
         }
     }
 
@@ -232,6 +254,10 @@ impl Instruction {
             | BuiltinOpcode::LHU => {
                 format!("{} {}, {}({})", opcode, rd, imm12, rs1)
             }
+            BuiltinOpcode::CSRRS => match (CounterCsr::from_address(self.op_c), rs1) {
+                (Some(csr), Register::X0) => format!("rd{} {}", csr.name(), rd),
+                _ => format!("{} {}, {:#x}, {}", opcode, rd, self.op_c, rs1),
+            },
             _ => format!("{} {}, {}, {}", opcode, rd, rs1, imm12),
         }
     }
@@ -1,3 +1,4 @@
+pub mod csr;
 pub mod encoder;
 pub mod instruction;
 pub mod opcode;
@@ -142,6 +142,7 @@ impl Opcode {
                 | OpcodeIdentifier::Builtin(BuiltinOpcode::ECALL)
                 | OpcodeIdentifier::Builtin(BuiltinOpcode::EBREAK)
                 | OpcodeIdentifier::Builtin(BuiltinOpcode::FENCE)
+                | OpcodeIdentifier::Builtin(BuiltinOpcode::CSRRS)
                 | OpcodeIdentifier::Custom(_)
         )
     }
@@ -310,6 +311,7 @@ pub enum BuiltinOpcode {
     ECALL,  // Environment call
     EBREAK, // Environment break       UNSUPPORTED
     FENCE,  // Fence (memory ordering) UNSUPPORTED
+    CSRRS,  // Atomic read and set bits in CSR, only counter reads are supported
 
     // S-type instructions
     SB, // Store byte
@@ -335,7 +337,7 @@ pub enum BuiltinOpcode {
     // UNIMPL instruction is used to represent instructions that are not yet implemented
     // or are intentionally left unimplemented in the current implementation.
     // In the RISC-V specification, this is similar to the UNIMP (unimplemented instruction) concept.
-    // Note: This may be updated or replaced if CSR (Control and Status Register) instruction support is added in the future.
+    // Note: CSR (Control and Status Register) instructions other than counter reads also decode to UNIMPL.
     #[default]
     UNIMPL,
 }
@@ -345,8 +347,8 @@ impl BuiltinOpcode {
         "add", "sub", "sll", "slt", "sltu", "xor", "srl", "sra", "or", "and", "mul", "mulh",
         "mulhsu", "mulhu", "div", "divu", "rem", "remu", "addi", "slli", "slti", "sltiu", "xori",
         "srli", "srai", "ori", "andi", "lb", "lh", "lw", "lbu", "lhu", "jalr", "ecall", "ebreak",
-        "fence", "sb", "sh", "sw", "beq", "bne", "blt", "bge", "bltu", "bgeu", "lui", "auipc",
-        "jal", "unimpl",
+        "fence", "csrrs", "sb", "sh", "sw", "beq", "bne", "blt", "bge", "bltu", "bgeu", "lui",
+        "auipc", "jal", "unimpl",
     ];
 
     fn mnemonic(&self) -> &'static str {
@@ -395,6 +397,7 @@ impl BuiltinOpcode {
             BuiltinOpcode::ECALL => 0b1110011,
             BuiltinOpcode::EBREAK => 0b1110011,
             BuiltinOpcode::FENCE => 0b0001111,
+            BuiltinOpcode::CSRRS => 0b1110011,
 
             BuiltinOpcode::SB => 0b0100011,
             BuiltinOpcode::SH => 0b0100011,
@@ -474,6 +477,8 @@ impl BuiltinOpcode {
 
             BuiltinOpcode::FENCE => SubByte::<3>::new_set(0b000),
 
+            BuiltinOpcode::CSRRS => SubByte::<3>::new_set(0b010),
+
             // Placeholder for unimplemented instructions should not have a known funct3
             BuiltinOpcode::UNIMPL => SubByte::<3>::new_unset(),
         }
@@ -548,6 +553,9 @@ impl BuiltinOpcode {
 
             BuiltinOpcode::FENCE => SubByte::<7>::new_unset(),
 
+            // The CSR address occupies the immediate field.
+            BuiltinOpcode::CSRRS => SubByte::<7>::new_unset(),
+
             BuiltinOpcode::UNIMPL => SubByte::<7>::new_unset(),
         }
     }
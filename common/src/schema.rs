@@ -0,0 +1,251 @@
+//! Guest I/O Type Schema
+//!
+//! The runtime macros record the structure of the types read and written by a guest program in the
+//! `.note.nexus-io-schema` section of its ELF file, so that the host can check its inputs before
+//! running the guest. A schema describes the postcard encoding of a type as a tree of tags in
+//! prefix order, where composite tags are followed by their lengths and the schemas of their parts:
+//!
+//! - [`tag::OPTION`] and [`tag::SEQ`] are followed by the schema of their element,
+//! - [`tag::MAP`] by the schemas of the key and the value,
+//! - [`tag::ARRAY`] by its length and the schema of its element,
+//! - [`tag::TUPLE`] and [`tag::ENUM`] by the number of fields or variants and the schema of each.
+//!
+//! Lengths are unsigned LEB128 varints. Structs are encoded as the tuple of their fields, a tuple with a
+//! single field as the field itself, and the variants of an enum as the tuple of their fields.
+//!
+//! Each entry of the section is the kind of I/O and the name of its type, both NUL-terminated,
+//! followed by the schema of the type. Schemas are built in constant evaluation, see [`Encoded`].
+
+/// The tags of the schema encoding.
+pub mod tag {
+    pub const UNIT: u8 = 0;
+    pub const BOOL: u8 = 1;
+    pub const U8: u8 = 2;
+    pub const U16: u8 = 3;
+    pub const U32: u8 = 4;
+    pub const U64: u8 = 5;
+    pub const U128: u8 = 6;
+    pub const I8: u8 = 7;
+    pub const I16: u8 = 8;
+    pub const I32: u8 = 9;
+    pub const I64: u8 = 10;
+    pub const I128: u8 = 11;
+    pub const F32: u8 = 12;
+    pub const F64: u8 = 13;
+    pub const CHAR: u8 = 14;
+    pub const STR: u8 = 15;
+    pub const OPTION: u8 = 16;
+    pub const SEQ: u8 = 17;
+    pub const MAP: u8 = 18;
+    pub const TUPLE: u8 = 19;
+    pub const ARRAY: u8 = 20;
+    pub const ENUM: u8 = 21;
+    /// A type whose structure is unknown, which matches any value.
+    pub const OPAQUE: u8 = 22;
+}
+
+/// The maximum length of an encoded schema, larger schemas are recorded as [`tag::OPAQUE`].
+pub const MAX_ENCODED_LEN: usize = 512;
+
+/// A schema encoded in constant evaluation.
+#[derive(Clone, Copy)]
+pub struct Encoded {
+    bytes: [u8; MAX_ENCODED_LEN],
+    len: usize,
+}
+
+impl Encoded {
+    /// The schema of a type whose structure is unknown.
+    pub const OPAQUE: Self = {
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        bytes[0] = tag::OPAQUE;
+        Self { bytes, len: 1 }
+    };
+
+    /// The schema of a primitive type, given by its tag.
+    pub const fn leaf(tag: u8) -> Self {
+        Builder::new().push(tag).finish()
+    }
+
+    pub const fn option(inner: &Encoded) -> Self {
+        Builder::new().push(tag::OPTION).append(inner).finish()
+    }
+
+    pub const fn seq(elem: &Encoded) -> Self {
+        Builder::new().push(tag::SEQ).append(elem).finish()
+    }
+
+    pub const fn map(key: &Encoded, value: &Encoded) -> Self {
+        Builder::new()
+            .push(tag::MAP)
+            .append(key)
+            .append(value)
+            .finish()
+    }
+
+    pub const fn array(len: usize, elem: &Encoded) -> Self {
+        match len {
+            0 => Self::leaf(tag::UNIT),
+            1 => *elem,
+            _ => Builder::new()
+                .push(tag::ARRAY)
+                .push_len(len)
+                .append(elem)
+                .finish(),
+        }
+    }
+
+    /// The schema of a tuple or a struct with the given fields.
+    pub const fn tuple(fields: &[Encoded]) -> Self {
+        match fields {
+            [] => Self::leaf(tag::UNIT),
+            [field] => *field,
+            _ => Builder::new()
+                .push(tag::TUPLE)
+                .push_len(fields.len())
+                .append_all(fields)
+                .finish(),
+        }
+    }
+
+    /// The schema of an enum, given the schemas of its variants in order.
+    pub const fn enumeration(variants: &[Encoded]) -> Self {
+        Builder::new()
+            .push(tag::ENUM)
+            .push_len(variants.len())
+            .append_all(variants)
+            .finish()
+    }
+
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.bytes.split_at(self.len).0
+    }
+}
+
+/// The length of an entry of the I/O schema section, see [`entry`].
+pub const fn entry_len(header: &[u8], schema: &Encoded) -> usize {
+    header.len() + schema.len
+}
+
+/// An entry of the I/O schema section, where `header` holds the NUL-terminated kind of I/O and type name.
+///
+/// `N` must be [`entry_len`] of the same arguments.
+pub const fn entry<const N: usize>(header: &[u8], schema: &Encoded) -> [u8; N] {
+    assert!(N == entry_len(header, schema));
+
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < header.len() {
+        out[i] = header[i];
+        i += 1;
+    }
+    while i < N {
+        out[i] = schema.bytes[i - header.len()];
+        i += 1;
+    }
+    out
+}
+
+// Builds an encoded schema, falling back to an opaque schema if it does not fit.
+struct Builder {
+    bytes: [u8; MAX_ENCODED_LEN],
+    len: usize,
+    overflow: bool,
+}
+
+impl Builder {
+    const fn new() -> Self {
+        Self {
+            bytes: [0; MAX_ENCODED_LEN],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    const fn push(mut self, byte: u8) -> Self {
+        if self.len == MAX_ENCODED_LEN {
+            self.overflow = true;
+        } else {
+            self.bytes[self.len] = byte;
+            self.len += 1;
+        }
+        self
+    }
+
+    const fn push_len(mut self, mut len: usize) -> Self {
+        while len >= 0x80 {
+            self = self.push((len as u8 & 0x7f) | 0x80);
+            len >>= 7;
+        }
+        self.push(len as u8)
+    }
+
+    const fn append(mut self, other: &Encoded) -> Self {
+        let mut i = 0;
+        while i < other.len {
+            self = self.push(other.bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    const fn append_all(mut self, others: &[Encoded]) -> Self {
+        let mut i = 0;
+        while i < others.len() {
+            self = self.append(&others[i]);
+            i += 1;
+        }
+        self
+    }
+
+    const fn finish(self) -> Encoded {
+        if self.overflow {
+            return Encoded::OPAQUE;
+        }
+        Encoded {
+            bytes: self.bytes,
+            len: self.len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const U32: Encoded = Encoded::leaf(tag::U32);
+
+    #[test]
+    fn test_encode_composites() {
+        let point = Encoded::tuple(&[U32, Encoded::leaf(tag::STR)]);
+        assert_eq!(point.as_bytes(), [tag::TUPLE, 2, tag::U32, tag::STR]);
+        assert_eq!(Encoded::tuple(&[U32]).as_bytes(), [tag::U32]);
+        assert_eq!(Encoded::tuple(&[]).as_bytes(), [tag::UNIT]);
+        assert_eq!(
+            Encoded::seq(&Encoded::option(&point)).as_bytes(),
+            [tag::SEQ, tag::OPTION, tag::TUPLE, 2, tag::U32, tag::STR]
+        );
+        assert_eq!(
+            Encoded::array(300, &U32).as_bytes(),
+            [tag::ARRAY, 0xac, 0x02, tag::U32]
+        );
+        assert_eq!(
+            Encoded::enumeration(&[Encoded::leaf(tag::UNIT), point]).as_bytes(),
+            [tag::ENUM, 2, tag::UNIT, tag::TUPLE, 2, tag::U32, tag::STR]
+        );
+    }
+
+    #[test]
+    fn test_encode_overflow() {
+        let schema = Encoded::tuple(&[U32; MAX_ENCODED_LEN]);
+        assert_eq!(schema.as_bytes(), [tag::OPAQUE]);
+        assert_eq!(Encoded::seq(&schema).as_bytes(), [tag::SEQ, tag::OPAQUE]);
+    }
+
+    #[test]
+    fn test_entry() {
+        const HEADER: &[u8] = b"public_input\0u32\0";
+        const ENTRY: [u8; entry_len(HEADER, &U32)] = entry(HEADER, &U32);
+        assert_eq!(ENTRY, *b"public_input\0u32\0\x04");
+    }
+}
@@ -4,21 +4,26 @@
 /// RISC-V processing
 pub mod nvm {
     pub use nexus_vm::{
-        elf::{ElfError, ElfFile},
-        emulator::View,
+        elf::{
+            normalize_type_name, ElfError, ElfFile, IoSchema, SchemaMismatch, Shape, TypeSchema,
+        },
+        emulator::{LayoutOverrides, MemoryReport, View},
         error::VMError,
-        trace::{bb_trace, k_trace, BBTrace, UniformTrace},
+        trace::{bb_trace, k_trace, k_trace_with_overrides, BBTrace, Trace, UniformTrace},
     };
     pub mod internals {
         pub use nexus_vm::emulator::{
-            convert_instruction, elf_into_program_info, io_entries_into_vec, map_into_io_entries,
-            slice_into_io_entries, LinearEmulator, LinearMemoryLayout, MemoryInitializationEntry,
-            ProgramInfo, PublicOutputEntry,
+            convert_instruction, convert_instructions, elf_into_program_info, io_entries_into_vec,
+            map_into_io_entries, slice_into_io_entries, InternalView, LinearEmulator,
+            LinearMemoryLayout, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry,
         };
     }
 }
 
 /// Stwo proving
 pub mod stwo {
-    pub use nexus_vm_prover::{prove, verify, Proof, ProvingError, VerificationError};
+    pub use nexus_vm_prover::{
+        format, prove, prove_with_report, report, verify, Proof, ProverReport, ProvingError,
+        VerificationError,
+    };
 }
@@ -180,6 +180,9 @@ impl MachineChip for CpuChip {
             Some(BuiltinOpcode::EBREAK) => {
                 traces.fill_columns(row_idx, true, IsEbreak);
             }
+            Some(BuiltinOpcode::CSRRS) => {
+                unreachable!("counter CSR reads are rejected before trace generation");
+            }
             _ => {
                 panic!(
                     "Unsupported opcode: {:?}",
@@ -30,7 +30,7 @@ use crate::{
 pub struct BitOpChip;
 
 const LOOKUP_TUPLE_SIZE: usize = 4; // op_flag, b, c, a
-stwo_prover::relation!(BitOpLookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(BitOpLookupElements, LOOKUP_TUPLE_SIZE);
 
 /// Unit-enum indicating which bitwise operation is executed by the chip.
 ///
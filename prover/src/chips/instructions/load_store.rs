@@ -63,7 +63,7 @@ impl VirtualColumnForSum for Ram3_4Accessed {
 pub struct LoadStoreChip;
 
 const LOOKUP_TUPLE_SIZE: usize = 2 * WORD_SIZE + 1;
-stwo_prover::relation!(LoadStoreLookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(LoadStoreLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for LoadStoreChip {
     fn draw_lookup_elements(
@@ -29,7 +29,7 @@ use crate::{
 pub struct ProgramMemCheckChip;
 
 const LOOKUP_TUPLE_SIZE: usize = 3 * WORD_SIZE;
-stwo_prover::relation!(ProgramCheckLookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(ProgramCheckLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for ProgramMemCheckChip {
     fn draw_lookup_elements(
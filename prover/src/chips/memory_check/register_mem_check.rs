@@ -37,7 +37,7 @@ impl RegisterMemCheckChip {
     const TUPLE_SIZE: usize = LOOKUP_TUPLE_SIZE;
 }
 
-stwo_prover::relation!(RegisterCheckLookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(RegisterCheckLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for RegisterMemCheckChip {
     fn draw_lookup_elements(
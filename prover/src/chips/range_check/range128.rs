@@ -31,7 +31,7 @@ use crate::column::Column::{self, Helper2, Helper3, IsBge, IsBlt, IsSlt};
 pub struct Range128Chip;
 
 const LOOKUP_TUPLE_SIZE: usize = 1;
-stwo_prover::relation!(Range128LookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(Range128LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for Range128Chip {
     fn draw_lookup_elements(
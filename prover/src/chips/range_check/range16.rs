@@ -28,7 +28,7 @@ use crate::{
 pub struct Range16Chip;
 
 const LOOKUP_TUPLE_SIZE: usize = 1;
-stwo_prover::relation!(Range16LookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(Range16LookupElements, LOOKUP_TUPLE_SIZE);
 
 const TYPE_R_CHECKED: [Column; 3] = [OpC0_3, OpA1_4, OpB1_4];
 const TYPE_U_CHECKED: [Column; 2] = [OpC12_15, OpA1_4];
@@ -33,7 +33,7 @@ use crate::{
 pub struct Range256Chip;
 
 const LOOKUP_TUPLE_SIZE: usize = 1;
-stwo_prover::relation!(Range256LookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(Range256LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl Range256Chip {
     const CHECKED_WORDS: [Column; 31] = [
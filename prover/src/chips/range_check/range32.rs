@@ -24,7 +24,7 @@ use crate::{
 pub struct Range32Chip;
 
 const LOOKUP_TUPLE_SIZE: usize = 1;
-stwo_prover::relation!(Range32LookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(Range32LookupElements, LOOKUP_TUPLE_SIZE);
 
 const CHECKED: [Column; 5] = [OpA, OpB, Reg1Address, Reg2Address, Reg3Address];
 
@@ -40,7 +40,7 @@ impl VirtualColumnForSum for Helper1MsbChecked {
 pub struct Range8Chip;
 
 const LOOKUP_TUPLE_SIZE: usize = 1;
-stwo_prover::relation!(Range8LookupElements, LOOKUP_TUPLE_SIZE);
+crate::components::relation!(Range8LookupElements, LOOKUP_TUPLE_SIZE);
 
 const TYPE_I_NO_SHIFT_CHECKED: [Column; 1] = [OpC8_10];
 const TYPE_J_CHECKED: [Column; 2] = [OpC1_3, OpC8_10];
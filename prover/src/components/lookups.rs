@@ -1,16 +1,23 @@
 //! Utilities for stwo lookups.
 //!
 //! Adding a chip with a lookup requires doing the following:
-//!     1. Declare a relation with a [`stwo_prover::relation`] macro.
+//!     1. Declare a relation with a [`relation`] macro, a wrapper around [`stwo_prover::relation`] which allows
+//!        lookup elements to be sent along with the proof.
 //!     2. Add this type to [`register_relation`] enum.
 //!     3. Implement [`MachineChip::draw_lookup_elements`] and [`MachineChip::fill_interaction_trace`] for the chip.
 //!
 //! Internally, [`AllLookupElements`] is a hashmap storing a set of generated alphas and z (=lookup elements) for each
 //! type. Since [`stwo_prover::constraint_framework::Relation`] is not object safe and cannot be boxed, the only way
 //! to store it is by using an enum.
+//!
+//! Lookup elements are serialized as a list of relation names and raw values, sorted by name. Deserialized elements
+//! are not trusted: the verifier draws its own and compares them against the received ones.
 
 use std::{any::TypeId, collections::HashMap};
 
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use stwo_prover::core::fields::qm31::SecureField;
+
 use crate::chips::{
     instructions::{bit_op::BitOpLookupElements, load_store::LoadStoreLookupElements},
     memory_check::{
@@ -43,6 +50,53 @@ register_relation! {
     pub(crate) trait RegisteredLookupBound {}
 }
 
+/// Raw values of [`stwo_prover::constraint_framework::logup::LookupElements`] of a single relation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RawLookupElements {
+    pub z: SecureField,
+    pub alpha: SecureField,
+    pub alpha_powers: Vec<SecureField>,
+}
+
+/// Conversion of a relation into its raw lookup elements, implemented by the [`relation`] macro.
+pub(crate) trait RelationRaw: Sized {
+    fn to_raw(&self) -> RawLookupElements;
+
+    /// Returns `None` if the number of alpha powers doesn't match the size of the relation.
+    fn from_raw(raw: RawLookupElements) -> Option<Self>;
+}
+
+/// Declares a relation with [`stwo_prover::relation`] and implements [`RelationRaw`] for it.
+///
+/// The inner lookup elements of the relation are private to the module invoking the macro, hence the conversion
+/// must be expanded there.
+macro_rules! relation {
+    ($name:ident, $size:tt) => {
+        stwo_prover::relation!($name, $size);
+
+        impl $crate::components::RelationRaw for $name {
+            fn to_raw(&self) -> $crate::components::RawLookupElements {
+                $crate::components::RawLookupElements {
+                    z: self.0.z,
+                    alpha: self.0.alpha,
+                    alpha_powers: self.0.alpha_powers.to_vec(),
+                }
+            }
+
+            fn from_raw(raw: $crate::components::RawLookupElements) -> Option<Self> {
+                Some(Self(
+                    stwo_prover::constraint_framework::logup::LookupElements {
+                        z: raw.z,
+                        alpha: raw.alpha,
+                        alpha_powers: raw.alpha_powers.try_into().ok()?,
+                    },
+                ))
+            }
+        }
+    };
+}
+pub(crate) use relation;
+
 #[derive(Default, Debug, Clone)]
 pub struct AllLookupElements(HashMap<TypeId, RelationVariant>);
 
@@ -60,6 +114,64 @@ impl AllLookupElements {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Returns whether both sets contain the same relations, regardless of the values of their lookup elements.
+    pub(crate) fn has_same_relations(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.keys().all(|type_id| other.0.contains_key(type_id))
+    }
+
+    /// Returns the size estimate of lookup elements in bytes, excluding relation names.
+    pub fn size_estimate(&self) -> usize {
+        self.0
+            .values()
+            .map(|variant| {
+                let raw = variant.to_raw();
+                (2 + raw.alpha_powers.len()) * std::mem::size_of::<SecureField>()
+            })
+            .sum()
+    }
+
+    /// Returns raw lookup elements of each relation, sorted by relation name.
+    fn to_raw(&self) -> Vec<(String, RawLookupElements)> {
+        let mut raw: Vec<_> = self
+            .0
+            .values()
+            .map(|variant| (variant.name().to_string(), variant.to_raw()))
+            .collect();
+        raw.sort_by(|(a, _), (b, _)| a.cmp(b));
+        raw
+    }
+}
+
+impl PartialEq for AllLookupElements {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_raw() == other.to_raw()
+    }
+}
+
+impl Eq for AllLookupElements {}
+
+impl Serialize for AllLookupElements {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_raw().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AllLookupElements {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Vec::<(String, RawLookupElements)>::deserialize(deserializer)?;
+        let mut all_elements = Self::default();
+        for (name, raw) in raw {
+            let (type_id, variant) = RelationVariant::from_raw(&name, raw)
+                .ok_or_else(|| D::Error::custom(format!("invalid lookup elements for {name}")))?;
+            if all_elements.0.insert(type_id, variant).is_some() {
+                return Err(D::Error::custom(format!(
+                    "duplicate lookup elements for {name}"
+                )));
+            }
+        }
+        Ok(all_elements)
+    }
 }
 
 impl<T: RegisteredLookupBound> AsRef<T> for AllLookupElements {
@@ -80,7 +192,7 @@ macro_rules! register_relation {
             $($name($name),)*
         }
 
-        $_vis trait $_trait: Sync + Clone + 'static {
+        $_vis trait $_trait: $crate::components::RelationRaw + Sync + Clone + 'static {
             type Relation<
                 F: Clone,
                 EF: stwo_prover::constraint_framework::RelationEFTraitBound<F>
@@ -147,6 +259,33 @@ macro_rules! register_relation {
                     )*
                 ]
             }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    $( Self::$name(_) => stringify!($name), )*
+                }
+            }
+
+            fn to_raw(&self) -> $crate::components::RawLookupElements {
+                match self {
+                    $( Self::$name(inner) => $crate::components::RelationRaw::to_raw(inner), )*
+                }
+            }
+
+            fn from_raw(
+                name: &str,
+                raw: $crate::components::RawLookupElements,
+            ) -> Option<(std::any::TypeId, Self)> {
+                match name {
+                    $(
+                        stringify!($name) => {
+                            let inner = <$name as $crate::components::RelationRaw>::from_raw(raw)?;
+                            Some((std::any::TypeId::of::<$name>(), Self::$name(inner)))
+                        }
+                    )*
+                    _ => None,
+                }
+            }
         }
     };
 }
@@ -158,3 +297,35 @@ macro_rules! replace_expr {
     };
 }
 pub(self) use replace_expr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stwo_prover::core::channel::Blake2sChannel;
+
+    #[test]
+    fn test_lookup_elements_serde() {
+        let channel = &mut Blake2sChannel::default();
+        let mut all_elements = AllLookupElements::default();
+        all_elements.insert(Range8LookupElements::draw(channel));
+        all_elements.insert(BitOpLookupElements::draw(channel));
+
+        let bytes = postcard::to_allocvec(&all_elements).unwrap();
+        let deserialized: AllLookupElements = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(deserialized, all_elements);
+        assert_ne!(deserialized, AllLookupElements::dummy());
+
+        let mut other_elements = AllLookupElements::default();
+        other_elements.insert(BitOpLookupElements::draw(channel));
+        other_elements.insert(Range8LookupElements::draw(channel));
+        assert!(all_elements.has_same_relations(&other_elements));
+        assert_ne!(all_elements, other_elements);
+        other_elements.insert(Range16LookupElements::draw(channel));
+        assert!(!all_elements.has_same_relations(&other_elements));
+
+        let mut raw = all_elements.to_raw();
+        raw[0].1.alpha_powers.pop();
+        let bytes = postcard::to_allocvec(&raw).unwrap();
+        assert!(postcard::from_bytes::<AllLookupElements>(&bytes).is_err());
+    }
+}
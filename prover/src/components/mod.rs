@@ -1,14 +1,12 @@
 use std::marker::PhantomData;
 
-use stwo_prover::constraint_framework::{
-    EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator,
-};
+use stwo_prover::constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval};
 
 use super::{trace::eval::TraceEval, traits::MachineChip};
 
 mod lookups;
 pub use lookups::AllLookupElements;
-pub(crate) use lookups::RegisteredLookupBound;
+pub(crate) use lookups::{relation, RawLookupElements, RegisteredLookupBound, RelationRaw};
 
 pub(super) const LOG_CONSTRAINT_DEGREE: u32 = 2;
 
@@ -49,12 +47,3 @@ impl<C: MachineChip> FrameworkEval for MachineEval<C> {
         eval
     }
 }
-
-pub(crate) fn machine_component_info<C: MachineChip>() -> InfoEvaluator {
-    let eval = MachineEval::<C> {
-        log_n_rows: 1,
-        lookup_elements: AllLookupElements::dummy(),
-        _phantom_data: PhantomData,
-    };
-    eval.evaluate(InfoEvaluator::empty())
-}
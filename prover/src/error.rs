@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// Errors that occur while proving an execution.
+#[derive(Debug)]
+pub enum ProvingError {
+    /// The Stwo prover failed, for instance because the trace does not satisfy the constraints.
+    Stwo(stwo_prover::core::prover::ProvingError),
+    /// The trace executes an instruction whose effects are not constrained by the prover.
+    UnprovableInstruction {
+        /// Address of the instruction.
+        pc: u32,
+        /// Why the instruction cannot be proven.
+        reason: &'static str,
+    },
+}
+
+impl From<stwo_prover::core::prover::ProvingError> for ProvingError {
+    fn from(err: stwo_prover::core::prover::ProvingError) -> Self {
+        Self::Stwo(err)
+    }
+}
+
+impl fmt::Display for ProvingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stwo(err) => write!(f, "{err}"),
+            Self::UnprovableInstruction { pc, reason } => {
+                write!(f, "instruction at pc {pc:#x} cannot be proven: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProvingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Stwo(err) => Some(err),
+            Self::UnprovableInstruction { .. } => None,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use stwo_prover::{
     constraint_framework::{
         logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, FrameworkEval,
@@ -20,7 +22,7 @@ use crate::{
     trace::sidenote::SideNote,
 };
 
-use super::{BuiltInExtension, FrameworkEvalExt};
+use super::{table_log_size, BuiltInExtension, FrameworkEvalExt};
 
 /// A component that yields logup sum emitted by the bitwise chip.
 #[derive(Debug, Clone)]
@@ -35,29 +37,22 @@ impl BitOpMultiplicity {
 }
 
 pub(crate) struct BitOpMultiplicityEval {
+    log_size: u32,
     lookup_elements: BitOpLookupElements,
 }
 
-impl Default for BitOpMultiplicityEval {
-    fn default() -> Self {
-        Self {
-            lookup_elements: BitOpLookupElements::dummy(),
-        }
-    }
-}
-
 impl BitOpMultiplicityEval {
     // There are (2 ** 4) ** 2 = 256 combinations for each looked up pair.
-    const LOG_SIZE: u32 = 8;
+    const MAX_LOG_SIZE: u32 = 8;
 }
 
 impl FrameworkEval for BitOpMultiplicityEval {
     fn log_size(&self) -> u32 {
-        Self::LOG_SIZE
+        self.log_size
     }
 
     fn max_constraint_log_degree_bound(&self) -> u32 {
-        Self::LOG_SIZE + 1
+        self.log_size + 1
     }
 
     fn evaluate<E: stwo_prover::constraint_framework::EvalAtRow>(&self, mut eval: E) -> E {
@@ -102,34 +97,58 @@ impl FrameworkEval for BitOpMultiplicityEval {
 }
 
 impl FrameworkEvalExt for BitOpMultiplicityEval {
-    const LOG_SIZE: u32 = BitOpMultiplicityEval::LOG_SIZE;
-
-    fn new(lookup_elements: &AllLookupElements) -> Self {
+    fn new(log_size: u32, lookup_elements: &AllLookupElements) -> Self {
         let lookup_elements: &BitOpLookupElements = lookup_elements.as_ref();
         Self {
+            log_size,
             lookup_elements: lookup_elements.clone(),
         }
     }
+
+    fn dummy(log_size: u32) -> Self {
+        Self {
+            log_size,
+            lookup_elements: BitOpLookupElements::dummy(),
+        }
+    }
 }
 
 impl BuiltInExtension for BitOpMultiplicity {
     type Eval = BitOpMultiplicityEval;
 
+    /// The table is truncated to the rows of the largest looked up `(b, c)` pair.
+    fn compute_log_size(side_note: &SideNote) -> u32 {
+        let bit_op = &side_note.bit_op;
+        table_log_size((0..=255).map(|i| {
+            [
+                &bit_op.multiplicity_and,
+                &bit_op.multiplicity_or,
+                &bit_op.multiplicity_xor,
+            ]
+            .iter()
+            .map(|multiplicity| multiplicity.get(&i).copied().unwrap_or_default())
+            .sum()
+        }))
+    }
+
+    fn log_size_bounds() -> RangeInclusive<u32> {
+        LOG_N_LANES..=BitOpMultiplicityEval::MAX_LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
+        log_size: u32,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::preprocessed_base_columns();
-        let domain = CanonicCoset::new(BitOpMultiplicityEval::LOG_SIZE).circle_domain();
+        let base_cols = Self::preprocessed_base_columns(log_size);
+        let domain = CanonicCoset::new(log_size).circle_domain();
         base_cols
             .into_iter()
             .map(|col| CircleEvaluation::new(domain, col))
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(log_size: u32) -> Vec<u32> {
         // preprocessed column for each of [and, or, xor] with 2 input lookups
-        std::iter::repeat(BitOpMultiplicityEval::LOG_SIZE)
-            .take(5)
-            .collect()
+        std::iter::repeat(log_size).take(5).collect()
     }
 
     /// Contains multiplicity column for each of [and, or, xor]
@@ -138,8 +157,9 @@ impl BuiltInExtension for BitOpMultiplicity {
     fn generate_original_trace(
         side_note: &SideNote,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::base_columns(side_note);
-        let domain = CanonicCoset::new(BitOpMultiplicityEval::LOG_SIZE).circle_domain();
+        let log_size = Self::compute_log_size(side_note);
+        let base_cols = Self::base_columns(log_size, side_note);
+        let domain = CanonicCoset::new(log_size).circle_domain();
         base_cols
             .into_iter()
             .map(|col| CircleEvaluation::new(domain, col))
@@ -154,11 +174,12 @@ impl BuiltInExtension for BitOpMultiplicity {
         SecureField,
     ) {
         let lookup_element: &BitOpLookupElements = lookup_elements.as_ref();
-        let mut logup_trace_gen = LogupTraceGenerator::new(BitOpMultiplicityEval::LOG_SIZE);
+        let log_size = Self::compute_log_size(side_note);
+        let mut logup_trace_gen = LogupTraceGenerator::new(log_size);
 
         // Subtract looked up multiplicities from logup sum
-        let preprocessed_columns = Self::preprocessed_base_columns();
-        let base_columns = Self::base_columns(side_note);
+        let preprocessed_columns = Self::preprocessed_base_columns(log_size);
+        let base_columns = Self::base_columns(log_size, side_note);
 
         let [answer_b, answer_c, answer_a_and, answer_a_or, answer_a_xor] = preprocessed_columns
             .try_into()
@@ -173,7 +194,7 @@ impl BuiltInExtension for BitOpMultiplicity {
             (BitOp::Xor, &answer_a_xor, &mult_xor),
         ] {
             let mut logup_col_gen = logup_trace_gen.new_col();
-            for vec_row in 0..(1 << (BitOpMultiplicityEval::LOG_SIZE - LOG_N_LANES)) {
+            for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
                 let answer_tuple = vec![
                     op_type.to_packed_base_field(),
                     answer_b.data[vec_row],
@@ -192,8 +213,10 @@ impl BuiltInExtension for BitOpMultiplicity {
 }
 
 impl BitOpMultiplicity {
-    fn preprocessed_base_columns() -> Vec<BaseColumn> {
-        let range_iter = (0u8..16).flat_map(|b| (0u8..16).map(move |c| (b, c)));
+    fn preprocessed_base_columns(log_size: u32) -> Vec<BaseColumn> {
+        let range_iter = (0u8..16)
+            .flat_map(|b| (0u8..16).map(move |c| (b, c)))
+            .take(1 << log_size);
         let column_b = BaseColumn::from_iter(range_iter.clone().map(|(b, _)| u32::from(b).into()));
         let column_c = BaseColumn::from_iter(range_iter.clone().map(|(_, c)| u32::from(c).into()));
         let column_and =
@@ -206,19 +229,25 @@ impl BitOpMultiplicity {
         vec![column_b, column_c, column_and, column_or, column_xor]
     }
 
-    fn base_columns(side_note: &SideNote) -> Vec<BaseColumn> {
+    fn base_columns(log_size: u32, side_note: &SideNote) -> Vec<BaseColumn> {
         let multiplicity_and = &side_note.bit_op.multiplicity_and;
         let multiplicity_or = &side_note.bit_op.multiplicity_or;
         let multiplicity_xor = &side_note.bit_op.multiplicity_xor;
 
         let multiplicity_and = BaseColumn::from_iter(
-            (0..=255).map(|i| multiplicity_and.get(&i).copied().unwrap_or_default().into()),
+            (0..=u8::MAX)
+                .take(1 << log_size)
+                .map(|i| multiplicity_and.get(&i).copied().unwrap_or_default().into()),
         );
         let multiplicity_or = BaseColumn::from_iter(
-            (0..=255).map(|i| multiplicity_or.get(&i).copied().unwrap_or_default().into()),
+            (0..=u8::MAX)
+                .take(1 << log_size)
+                .map(|i| multiplicity_or.get(&i).copied().unwrap_or_default().into()),
         );
         let multiplicity_xor = BaseColumn::from_iter(
-            (0..=255).map(|i| multiplicity_xor.get(&i).copied().unwrap_or_default().into()),
+            (0..=u8::MAX)
+                .take(1 << log_size)
+                .map(|i| multiplicity_xor.get(&i).copied().unwrap_or_default().into()),
         );
         vec![multiplicity_and, multiplicity_or, multiplicity_xor]
     }
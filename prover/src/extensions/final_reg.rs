@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use nexus_common::constants::NUM_REGISTERS;
 use nexus_vm::WORD_SIZE;
 use num_traits::{One, Zero};
@@ -123,20 +125,32 @@ impl FrameworkEval for FinalRegEval {
 }
 
 impl FrameworkEvalExt for FinalRegEval {
-    const LOG_SIZE: u32 = Self::LOG_SIZE;
-
-    fn new(lookup_elements: &AllLookupElements) -> Self {
+    // The size is fixed by the number of registers, see [`FinalReg::log_size_bounds`].
+    fn new(_log_size: u32, lookup_elements: &AllLookupElements) -> Self {
         let register_check_lookup_elements: &RegisterCheckLookupElements = lookup_elements.as_ref();
         Self {
             lookup_elements: register_check_lookup_elements.clone(),
         }
     }
+
+    fn dummy(_log_size: u32) -> Self {
+        Self::default()
+    }
 }
 
 impl BuiltInExtension for FinalReg {
     type Eval = FinalRegEval;
 
+    fn compute_log_size(_side_note: &SideNote) -> u32 {
+        FinalRegEval::LOG_SIZE
+    }
+
+    fn log_size_bounds() -> RangeInclusive<u32> {
+        FinalRegEval::LOG_SIZE..=FinalRegEval::LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
+        _log_size: u32,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(FinalRegEval::LOG_SIZE).circle_domain();
@@ -146,7 +160,7 @@ impl BuiltInExtension for FinalReg {
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(_log_size: u32) -> Vec<u32> {
         vec![FinalRegEval::LOG_SIZE]
     }
 
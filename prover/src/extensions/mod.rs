@@ -14,13 +14,15 @@
 //! Some components must always be present, for example [`final_reg::FinalReg`]. They should only be accessible within
 //! the crate to avoid misuse.
 
+use std::ops::RangeInclusive;
+
 use stwo_prover::{
     constraint_framework::{
         FrameworkComponent, FrameworkEval, InfoEvaluator, TraceLocationAllocator,
     },
     core::{
         air::{Component, ComponentProver},
-        backend::simd::SimdBackend,
+        backend::simd::{m31::LOG_N_LANES, SimdBackend},
         fields::{m31::BaseField, qm31::SecureField},
         pcs::TreeVec,
         poly::{circle::CircleEvaluation, BitReversedOrder},
@@ -40,18 +42,28 @@ use multiplicity::{Multiplicity128, Multiplicity16, Multiplicity256, Multiplicit
 mod multiplicity8;
 use multiplicity8::Multiplicity8;
 
-trait FrameworkEvalExt: FrameworkEval + Default + Sync + 'static {
-    // TODO: make it variable, e.g. derived by the component implementation from
-    // the finalized side note.
-    const LOG_SIZE: u32;
+trait FrameworkEvalExt: FrameworkEval + Sync + 'static {
+    fn new(log_size: u32, lookup_elements: &AllLookupElements) -> Self;
 
-    fn new(lookup_elements: &AllLookupElements) -> Self;
+    /// Returns the evaluator with dummy lookup elements, used for retrieving trace dimensions.
+    fn dummy(log_size: u32) -> Self;
 }
 
+/// Built-in extension component.
+///
+/// The log size of a component is chosen by the prover from the finalized [`SideNote`], and recorded in the proof.
+/// The verifier only accepts sizes within [`BuiltInExtension::log_size_bounds`].
 trait BuiltInExtension {
     type Eval: FrameworkEvalExt;
 
+    /// Returns the log size of the component required for proving the execution recorded in `side_note`.
+    fn compute_log_size(side_note: &SideNote) -> u32;
+
+    /// Returns the range of log sizes that are sound for the component.
+    fn log_size_bounds() -> RangeInclusive<u32>;
+
     fn generate_preprocessed_trace(
+        log_size: u32,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
     fn generate_original_trace(
@@ -68,40 +80,56 @@ trait BuiltInExtension {
 
     fn to_component_prover(
         &self,
+        log_size: u32,
         tree_span_provider: &mut TraceLocationAllocator,
         lookup_elements: &AllLookupElements,
         claimed_sum: SecureField,
     ) -> Box<dyn ComponentProver<SimdBackend>> {
         Box::new(FrameworkComponent::new(
             tree_span_provider,
-            Self::Eval::new(lookup_elements),
+            Self::Eval::new(log_size, lookup_elements),
             claimed_sum,
         ))
     }
 
     fn to_component(
         &self,
+        log_size: u32,
         tree_span_provider: &mut TraceLocationAllocator,
         lookup_elements: &AllLookupElements,
         claimed_sum: SecureField,
     ) -> Box<dyn Component> {
         Box::new(FrameworkComponent::new(
             tree_span_provider,
-            Self::Eval::new(lookup_elements),
+            Self::Eval::new(log_size, lookup_elements),
             claimed_sum,
         ))
     }
 
-    fn trace_sizes(&self) -> TreeVec<Vec<u32>> {
-        <Self as BuiltInExtension>::Eval::default()
+    fn trace_sizes(&self, log_size: u32) -> TreeVec<Vec<u32>> {
+        Self::Eval::dummy(log_size)
             .evaluate(InfoEvaluator::empty())
             .mask_offsets
             .as_cols_ref()
-            .map_cols(|_| Self::Eval::LOG_SIZE)
+            .map_cols(|_| log_size)
     }
 
     /// Returns the log_sizes of each preprocessed columns
-    fn preprocessed_trace_sizes() -> Vec<u32>;
+    fn preprocessed_trace_sizes(log_size: u32) -> Vec<u32>;
+}
+
+/// Returns the log size of a lookup table truncated to the rows with non-zero multiplicity.
+///
+/// Every row of a table is a valid entry on its own, hence committing only to the first `2^log_size` rows is sound:
+/// a lookup of any value beyond them cannot be balanced. `multiplicities` must be given in row order.
+fn table_log_size(multiplicities: impl IntoIterator<Item = u32>) -> u32 {
+    let used_rows = multiplicities
+        .into_iter()
+        .enumerate()
+        .filter(|(_, multiplicity)| *multiplicity != 0)
+        .last()
+        .map_or(0, |(row, _)| row + 1);
+    used_rows.next_power_of_two().ilog2().max(LOG_N_LANES)
 }
 
 extension_dispatch! {
@@ -164,11 +192,30 @@ macro_rules! extension_dispatch {
         impl $_enum {
             #![allow(unused)]
 
+            pub(crate) fn name(&self) -> &'static str {
+                match self {
+                    $( $_enum::$name(_) => stringify!($name), )*
+                }
+            }
+
+            pub(crate) fn compute_log_size(&self, side_note: &SideNote) -> u32 {
+                match self {
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::compute_log_size(side_note), )*
+                }
+            }
+
+            pub(crate) fn log_size_bounds(&self) -> RangeInclusive<u32> {
+                match self {
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::log_size_bounds(), )*
+                }
+            }
+
             pub(crate) fn generate_preprocessed_trace(
                 &self,
+                log_size: u32,
             ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(log_size), )*
                 }
             }
 
@@ -196,38 +243,57 @@ macro_rules! extension_dispatch {
 
             pub(crate) fn to_component_prover(
                 &self,
+                log_size: u32,
                 tree_span_provider: &mut TraceLocationAllocator,
                 lookup_elements: &AllLookupElements,
                 claimed_sum: SecureField,
             ) -> Box<dyn ComponentProver<SimdBackend>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component_prover(inner, tree_span_provider, lookup_elements, claimed_sum), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component_prover(inner, log_size, tree_span_provider, lookup_elements, claimed_sum), )*
                 }
             }
 
             pub(crate) fn to_component(
                 &self,
+                log_size: u32,
                 tree_span_provider: &mut TraceLocationAllocator,
                 lookup_elements: &AllLookupElements,
                 claimed_sum: SecureField,
             ) -> Box<dyn Component> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component(inner, tree_span_provider, lookup_elements, claimed_sum), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component(inner, log_size, tree_span_provider, lookup_elements, claimed_sum), )*
                 }
             }
 
-            pub(crate) fn trace_sizes(&self) -> TreeVec<Vec<u32>> {
+            pub(crate) fn trace_sizes(&self, log_size: u32) -> TreeVec<Vec<u32>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::trace_sizes(inner), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::trace_sizes(inner, log_size), )*
                 }
             }
 
-            pub(crate) fn preprocessed_trace_sizes(&self) -> Vec<u32> {
+            pub(crate) fn preprocessed_trace_sizes(&self, log_size: u32) -> Vec<u32> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::preprocessed_trace_sizes(), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::preprocessed_trace_sizes(log_size), )*
                 }
             }
         }
     };
 }
 pub(self) use extension_dispatch;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_log_size() {
+        assert_eq!(table_log_size([0; 256]), LOG_N_LANES);
+        assert_eq!(table_log_size([1, 0, 0, 0]), LOG_N_LANES);
+
+        let mut multiplicities = [0; 256];
+        multiplicities[16] = 1;
+        assert_eq!(table_log_size(multiplicities), 5);
+        multiplicities[255] = 3;
+        assert_eq!(table_log_size(multiplicities), 8);
+    }
+}
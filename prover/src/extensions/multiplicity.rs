@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use stwo_prover::{
     constraint_framework::{
         logup::LogupTraceGenerator, preprocessed_columns::PreProcessedColumnId, FrameworkEval,
@@ -28,7 +30,7 @@ use crate::{
     trace::sidenote::{RangeCheckSideNote, RangeCheckSideNoteGetter, SideNote},
 };
 
-use super::{BuiltInExtension, FrameworkEvalExt};
+use super::{table_log_size, BuiltInExtension, FrameworkEvalExt};
 
 /// A component for range check multiplicity
 ///
@@ -48,25 +50,23 @@ impl<const LEN: usize, L> Multiplicity<LEN, L> {
 }
 
 pub(crate) struct MultiplicityEval<const LEN: usize, L> {
+    log_size: u32,
     lookup_elements: L,
 }
 
 impl<const LEN: usize, L> MultiplicityEval<LEN, L> {
-    const LOG_SIZE: u32 = {
+    /// Log size of the whole table, the component can be truncated to fewer rows.
+    const MAX_LOG_SIZE: u32 = {
         let log_size = LEN.ilog2();
         assert!(1 << log_size == LEN, "LEN must be a power of 2");
+        assert!(
+            log_size >= LOG_N_LANES,
+            "LEN must fill at least one SIMD vector"
+        );
         log_size
     };
 }
 
-impl<L: RegisteredLookupBound, const LEN: usize> Default for MultiplicityEval<LEN, L> {
-    fn default() -> Self {
-        Self {
-            lookup_elements: L::dummy(),
-        }
-    }
-}
-
 /// A column with {0, ..., LEN - 1} when LEN is a power of 2
 #[derive(Debug, Clone)]
 pub struct RangeValues<const LEN: usize>;
@@ -85,16 +85,16 @@ impl<const LEN: usize> RangeValues<LEN> {
 
 impl<const LEN: usize, L: RegisteredLookupBound> FrameworkEval for MultiplicityEval<LEN, L> {
     fn log_size(&self) -> u32 {
-        Self::LOG_SIZE
+        self.log_size
     }
 
     fn max_constraint_log_degree_bound(&self) -> u32 {
-        Self::LOG_SIZE + 1
+        self.log_size + 1
     }
 
     fn evaluate<E: stwo_prover::constraint_framework::EvalAtRow>(&self, mut eval: E) -> E {
         let lookup_elements = <L as RegisteredLookupBound>::as_relation_ref(&self.lookup_elements);
-        let checked_value = RangeValues::<LEN>::new(Self::LOG_SIZE);
+        let checked_value = RangeValues::<LEN>::new(self.log_size);
         let checked_value = eval.get_preprocessed_column(checked_value.id());
         let multiplicity = eval.next_trace_mask();
         eval.add_to_relation(RelationEntry::new(
@@ -108,14 +108,20 @@ impl<const LEN: usize, L: RegisteredLookupBound> FrameworkEval for MultiplicityE
 }
 
 impl<const LEN: usize, L: RegisteredLookupBound> FrameworkEvalExt for MultiplicityEval<LEN, L> {
-    const LOG_SIZE: u32 = Self::LOG_SIZE;
-
-    fn new(lookup_elements: &AllLookupElements) -> Self {
+    fn new(log_size: u32, lookup_elements: &AllLookupElements) -> Self {
         let lookup: &L = lookup_elements.as_ref();
         Self {
+            log_size,
             lookup_elements: lookup.clone(),
         }
     }
+
+    fn dummy(log_size: u32) -> Self {
+        Self {
+            log_size,
+            lookup_elements: L::dummy(),
+        }
+    }
 }
 
 impl<const LEN: usize, L: RegisteredLookupBound> BuiltInExtension for Multiplicity<LEN, L>
@@ -127,18 +133,29 @@ where
 {
     type Eval = MultiplicityEval<LEN, L>;
 
+    /// The table is truncated to the rows of the largest checked value.
+    fn compute_log_size(side_note: &SideNote) -> u32 {
+        let range_check_side_note: &RangeCheckSideNote<LEN> = side_note.get_range_check_side_note();
+        table_log_size(range_check_side_note.multiplicity)
+    }
+
+    fn log_size_bounds() -> RangeInclusive<u32> {
+        LOG_N_LANES..=Self::Eval::MAX_LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
+        log_size: u32,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::preprocessed_base_columns();
-        let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
+        let base_cols = Self::preprocessed_base_columns(log_size);
+        let domain = CanonicCoset::new(log_size).circle_domain();
         base_cols
             .into_iter()
             .map(|col| CircleEvaluation::new(domain, col))
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
-        vec![Self::Eval::LOG_SIZE]
+    fn preprocessed_trace_sizes(log_size: u32) -> Vec<u32> {
+        vec![log_size]
     }
 
     /// Contains only one column, representing the multiplicity
@@ -147,8 +164,9 @@ where
     fn generate_original_trace(
         side_note: &SideNote,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::base_columns(side_note);
-        let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
+        let log_size = Self::compute_log_size(side_note);
+        let base_cols = Self::base_columns(log_size, side_note);
+        let domain = CanonicCoset::new(log_size).circle_domain();
         base_cols
             .into_iter()
             .map(|col| CircleEvaluation::new(domain, col))
@@ -163,13 +181,14 @@ where
         SecureField,
     ) {
         let lookup_element: &L = lookup_elements.as_ref();
-        let values = &Self::preprocessed_base_columns()[0];
-        let base_cols = Self::base_columns(side_note);
-        let mut logup_trace_gen = LogupTraceGenerator::new(Self::Eval::LOG_SIZE);
+        let log_size = Self::compute_log_size(side_note);
+        let values = &Self::preprocessed_base_columns(log_size)[0];
+        let base_cols = Self::base_columns(log_size, side_note);
+        let mut logup_trace_gen = LogupTraceGenerator::new(log_size);
 
         // Subtract looked up values with the multiplicity
         let mut logup_col_gen = logup_trace_gen.new_col();
-        for vec_row in 0..(1 << (Self::Eval::LOG_SIZE - LOG_N_LANES)) {
+        for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
             let value = values.data[vec_row];
             let denom = lookup_element.combine(&[value]);
             let numerator = -base_cols[0].data[vec_row];
@@ -181,11 +200,11 @@ where
 }
 
 impl<const LEN: usize, L> Multiplicity<LEN, L> {
-    fn preprocessed_base_columns() -> Vec<BaseColumn> {
-        let range_values = BaseColumn::from_iter((0..LEN).map(BaseField::from));
+    fn preprocessed_base_columns(log_size: u32) -> Vec<BaseColumn> {
+        let range_values = BaseColumn::from_iter((0..1 << log_size).map(BaseField::from));
         vec![range_values]
     }
-    fn base_columns(side_note: &SideNote) -> Vec<BaseColumn>
+    fn base_columns(log_size: u32, side_note: &SideNote) -> Vec<BaseColumn>
     where
         SideNote: RangeCheckSideNoteGetter<LEN>,
     {
@@ -194,6 +213,7 @@ impl<const LEN: usize, L> Multiplicity<LEN, L> {
             range_check_side_note
                 .multiplicity
                 .into_iter()
+                .take(1 << log_size)
                 .map(BaseField::from),
         );
         vec![multiplicities]
@@ -1,5 +1,7 @@
 // Multiplicity8 extension is a special case because it requires eight padding rows in order to fit the SIMD usage
 
+use std::ops::RangeInclusive;
+
 use num_traits::{CheckedSub, Zero};
 use stwo_prover::{
     constraint_framework::{
@@ -95,20 +97,32 @@ impl FrameworkEval for MultiplicityEval8 {
 }
 
 impl FrameworkEvalExt for MultiplicityEval8 {
-    const LOG_SIZE: u32 = Self::LOG_SIZE;
-
-    fn new(lookup_elements: &AllLookupElements) -> Self {
+    // The size is fixed by SIMD, see [`Multiplicity8::log_size_bounds`].
+    fn new(_log_size: u32, lookup_elements: &AllLookupElements) -> Self {
         let lookup: &Range8LookupElements = lookup_elements.as_ref();
         Self {
             lookup_elements: lookup.clone(),
         }
     }
+
+    fn dummy(_log_size: u32) -> Self {
+        Self::default()
+    }
 }
 
 impl BuiltInExtension for Multiplicity8 {
     type Eval = MultiplicityEval8;
 
+    fn compute_log_size(_side_note: &SideNote) -> u32 {
+        Self::Eval::LOG_SIZE
+    }
+
+    fn log_size_bounds() -> RangeInclusive<u32> {
+        Self::Eval::LOG_SIZE..=Self::Eval::LOG_SIZE
+    }
+
     fn generate_preprocessed_trace(
+        _log_size: u32,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
@@ -118,7 +132,7 @@ impl BuiltInExtension for Multiplicity8 {
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(_log_size: u32) -> Vec<u32> {
         vec![Self::Eval::LOG_SIZE]
     }
 
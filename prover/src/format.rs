@@ -0,0 +1,439 @@
+//! Versioned binary container for proofs.
+//!
+//! Serializing [`Proof`](crate::Proof) with serde alone gives no way to tell apart a proof produced by an
+//! incompatible version of the crate from a corrupted one. The container prepends a fixed header and appends a
+//! checksum. All integers are little-endian:
+//!
+//! | offset | size | field                                                          |
+//! |--------|------|----------------------------------------------------------------|
+//! | 0      | 4    | magic bytes `NXPF`                                             |
+//! | 4      | 2    | format version, currently [`FORMAT_VERSION`]                   |
+//! | 6      | 1    | hash function of the commitment scheme, see [`HashFunction`]   |
+//! | 7      | 1    | reserved, must be zero                                         |
+//! | 8      | 4    | PCS proof of work bits                                         |
+//! | 12     | 4    | FRI log blowup factor                                          |
+//! | 16     | 4    | FRI log last layer degree bound                                |
+//! | 20     | 4    | FRI number of queries                                          |
+//! | 24     | 32   | program ID, see [`ProgramId`]                                  |
+//! | 56     | 1    | length `n` of the prover version string                        |
+//! | 57     | n    | prover version, UTF-8                                          |
+//! | 57 + n | 8    | length `m` of the payload                                      |
+//! | 65 + n | m    | payload, postcard encoding of the proof                        |
+//! | 65+n+m | 32   | Blake2s checksum of all preceding bytes                        |
+//!
+//! Only the magic bytes and the format version are guaranteed to stay at the same position across versions, the
+//! decoder checks them before anything else. Any change to the payload encoding, including new fields of the proof,
+//! requires bumping [`FORMAT_VERSION`].
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+use stwo_prover::core::{fri::FriConfig, pcs::PcsConfig, vcs::blake2_hash::Blake2sHasher};
+
+use nexus_vm::emulator::ProgramInfo;
+
+use crate::Proof;
+
+pub const MAGIC: [u8; 4] = *b"NXPF";
+
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Version of the crate that produced the proof, informational only.
+pub const PROVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const CHECKSUM_LEN: usize = 32;
+
+/// Hash function used by the commitment scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashFunction {
+    Blake2s = 1,
+}
+
+impl HashFunction {
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Blake2s),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters of the polynomial commitment scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcsParameters {
+    pub pow_bits: u32,
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub n_queries: u32,
+}
+
+impl From<PcsConfig> for PcsParameters {
+    fn from(config: PcsConfig) -> Self {
+        Self {
+            pow_bits: config.pow_bits,
+            log_blowup_factor: config.fri_config.log_blowup_factor,
+            log_last_layer_degree_bound: config.fri_config.log_last_layer_degree_bound,
+            n_queries: config.fri_config.n_queries as u32,
+        }
+    }
+}
+
+impl From<PcsParameters> for PcsConfig {
+    fn from(params: PcsParameters) -> Self {
+        Self {
+            pow_bits: params.pow_bits,
+            fri_config: FriConfig::new(
+                params.log_last_layer_degree_bound,
+                params.log_blowup_factor,
+                params.n_queries as usize,
+            ),
+        }
+    }
+}
+
+/// Blake2s hash identifying the proven program: the initial program counter followed by every program memory entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramId(pub [u8; 32]);
+
+impl ProgramId {
+    pub fn new(program_info: &ProgramInfo) -> Self {
+        let mut bytes = Vec::with_capacity(4 + program_info.program.len() * 8);
+        bytes.extend(program_info.initial_pc.to_le_bytes());
+        for entry in &program_info.program {
+            bytes.extend(entry.pc.to_le_bytes());
+            bytes.extend(entry.instruction_word.to_le_bytes());
+        }
+        Self(Blake2sHasher::hash(&bytes).0)
+    }
+}
+
+impl fmt::Display for ProgramId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofHeader {
+    pub format_version: u16,
+    pub prover_version: String,
+    pub hash_function: HashFunction,
+    pub pcs_parameters: PcsParameters,
+    pub program_id: ProgramId,
+}
+
+impl ProofHeader {
+    /// Returns the header of a proof produced by this version of the crate.
+    pub fn current(program_id: ProgramId) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            prover_version: PROVER_VERSION.to_string(),
+            hash_function: HashFunction::Blake2s,
+            pcs_parameters: PcsConfig::default().into(),
+            program_id,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The input doesn't start with [`MAGIC`].
+    BadMagic,
+    /// The input was produced with a format version this crate cannot read.
+    UnsupportedVersion(u16),
+    /// The input ended before the container was complete.
+    Truncated,
+    /// The checksum doesn't match the content.
+    ChecksumMismatch,
+    UnsupportedHashFunction(u8),
+    /// The proof was produced with PCS parameters other than the ones used by this crate.
+    UnsupportedPcsParameters(PcsParameters),
+    InvalidHeader(&'static str),
+    /// The payload couldn't be deserialized.
+    Payload(postcard::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not a proof file: bad magic bytes"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported proof format version {version}, expected {FORMAT_VERSION}"
+            ),
+            Self::Truncated => write!(f, "proof file is truncated"),
+            Self::ChecksumMismatch => write!(f, "proof file checksum mismatch"),
+            Self::UnsupportedHashFunction(id) => write!(f, "unsupported hash function {id}"),
+            Self::UnsupportedPcsParameters(params) => {
+                write!(f, "unsupported PCS parameters {params:?}")
+            }
+            Self::InvalidHeader(reason) => write!(f, "invalid proof header: {reason}"),
+            Self::Payload(err) => write!(f, "invalid proof payload: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Payload(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes `payload` into a container with the [current](ProofHeader::current) header.
+pub fn encode<T: Serialize>(payload: &T, program_id: ProgramId) -> Vec<u8> {
+    let header = ProofHeader::current(program_id);
+    let payload = postcard::to_allocvec(payload).expect("proof serialization must not fail");
+
+    let mut bytes = Vec::with_capacity(65 + header.prover_version.len() + payload.len() + 32);
+    bytes.extend(MAGIC);
+    bytes.extend(header.format_version.to_le_bytes());
+    bytes.push(header.hash_function as u8);
+    bytes.push(0);
+    let params = header.pcs_parameters;
+    for value in [
+        params.pow_bits,
+        params.log_blowup_factor,
+        params.log_last_layer_degree_bound,
+        params.n_queries,
+    ] {
+        bytes.extend(value.to_le_bytes());
+    }
+    bytes.extend(header.program_id.0);
+    bytes.push(
+        header
+            .prover_version
+            .len()
+            .try_into()
+            .expect("prover version is too long"),
+    );
+    bytes.extend(header.prover_version.as_bytes());
+    bytes.extend((payload.len() as u64).to_le_bytes());
+    bytes.extend(payload);
+
+    let checksum = Blake2sHasher::hash(&bytes).0;
+    bytes.extend(checksum);
+    bytes
+}
+
+/// Decodes a container, checking its version, checksum and parameters before deserializing the payload.
+///
+/// The program ID is returned in the header and must be compared by the caller against the expected program.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<(ProofHeader, T), DecodeError> {
+    let mut reader = Reader(bytes);
+    if reader.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+        return Err(DecodeError::BadMagic);
+    }
+    let format_version = u16::from_le_bytes(reader.array()?);
+    if format_version != FORMAT_VERSION {
+        return Err(DecodeError::UnsupportedVersion(format_version));
+    }
+
+    let content_len = bytes
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .ok_or(DecodeError::Truncated)?;
+    let (content, checksum) = bytes.split_at(content_len);
+    if Blake2sHasher::hash(content).0 != checksum {
+        return Err(DecodeError::ChecksumMismatch);
+    }
+    // Skip magic and version, which were already read.
+    let mut reader = Reader(&content[MAGIC.len() + 2..]);
+
+    let [hash_id, reserved] = reader.array()?;
+    let hash_function =
+        HashFunction::from_id(hash_id).ok_or(DecodeError::UnsupportedHashFunction(hash_id))?;
+    if reserved != 0 {
+        return Err(DecodeError::InvalidHeader("reserved byte is not zero"));
+    }
+    let pcs_parameters = PcsParameters {
+        pow_bits: u32::from_le_bytes(reader.array()?),
+        log_blowup_factor: u32::from_le_bytes(reader.array()?),
+        log_last_layer_degree_bound: u32::from_le_bytes(reader.array()?),
+        n_queries: u32::from_le_bytes(reader.array()?),
+    };
+    if pcs_parameters != PcsConfig::default().into() {
+        return Err(DecodeError::UnsupportedPcsParameters(pcs_parameters));
+    }
+    let program_id = ProgramId(reader.array()?);
+    let [version_len] = reader.array()?;
+    let prover_version = std::str::from_utf8(reader.take(version_len as usize)?)
+        .map_err(|_| DecodeError::InvalidHeader("prover version is not UTF-8"))?
+        .to_string();
+
+    let payload_len = u64::from_le_bytes(reader.array()?);
+    let payload_len = usize::try_from(payload_len)
+        .map_err(|_| DecodeError::InvalidHeader("payload is too long"))?;
+    let payload = reader.take(payload_len)?;
+    if !reader.0.is_empty() {
+        return Err(DecodeError::InvalidHeader("trailing bytes after payload"));
+    }
+    let payload = postcard::from_bytes(payload).map_err(DecodeError::Payload)?;
+
+    let header = ProofHeader {
+        format_version,
+        prover_version,
+        hash_function,
+        pcs_parameters,
+        program_id,
+    };
+    Ok((header, payload))
+}
+
+impl Proof {
+    /// Encodes the proof of `program_id` into the versioned container, see [module documentation](self).
+    pub fn to_bytes(&self, program_id: ProgramId) -> Vec<u8> {
+        encode(self, program_id)
+    }
+
+    /// Decodes the proof from the versioned container, see [`decode`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<(ProofHeader, Self), DecodeError> {
+        decode(bytes)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < len {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(N)?.try_into().expect("length is checked"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::machine::{BaseComponent, Machine};
+    use nexus_vm::{
+        emulator::{InternalView, View},
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    /// Set to regenerate the golden proof of the current format version.
+    const BLESS_ENV: &str = "NEXUS_BLESS_PROOFS";
+
+    fn golden_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/proofs")
+    }
+
+    fn prove_add() -> (View, Proof) {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        (view, proof)
+    }
+
+    fn verify(proof: Proof, view: &View) {
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (view, proof) = prove_add();
+        let program_id = ProgramId::new(view.get_program_memory());
+
+        let bytes = proof.to_bytes(program_id);
+        let (header, decoded) = Proof::from_bytes(&bytes).unwrap();
+        assert_eq!(header, ProofHeader::current(program_id));
+        verify(decoded, &view);
+
+        let err = Proof::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(matches!(err, DecodeError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_golden_malformed() {
+        let decode_golden = |name: &str| {
+            let bytes = std::fs::read(golden_dir().join(name)).unwrap();
+            Proof::from_bytes(&bytes).unwrap_err()
+        };
+        assert!(matches!(
+            decode_golden("bad_magic.proof"),
+            DecodeError::BadMagic
+        ));
+        assert!(matches!(
+            decode_golden("unsupported_version.proof"),
+            DecodeError::UnsupportedVersion(2)
+        ));
+        assert!(matches!(
+            decode_golden("corrupted.proof"),
+            DecodeError::ChecksumMismatch
+        ));
+        assert!(matches!(
+            decode_golden("unknown_hash.proof"),
+            DecodeError::UnsupportedHashFunction(255)
+        ));
+    }
+
+    /// Proofs in `v<version>_add.proof` files are proofs of [`prove_add`] produced by older releases. Files of the
+    /// current version must decode and verify, older ones must be rejected with an explicit error.
+    #[test]
+    #[ignore = "v1_add.proof is not blessed yet, bless it with NEXUS_BLESS_PROOFS=1 and --include-ignored, then drop this attribute"]
+    fn test_golden_proofs() {
+        let (view, proof) = prove_add();
+        let program_id = ProgramId::new(view.get_program_memory());
+        if std::env::var_os(BLESS_ENV).is_some() {
+            let path = golden_dir().join(format!("v{FORMAT_VERSION}_add.proof"));
+            std::fs::write(path, proof.to_bytes(program_id)).unwrap();
+        }
+
+        let mut versions = Vec::new();
+        for entry in std::fs::read_dir(golden_dir()).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let Some(version) = name
+                .strip_prefix('v')
+                .and_then(|name| name.strip_suffix("_add.proof"))
+            else {
+                continue;
+            };
+            let version: u16 = version.parse().unwrap();
+            versions.push(version);
+            let bytes = std::fs::read(&path).unwrap();
+
+            match Proof::from_bytes(&bytes) {
+                Ok((header, proof)) => {
+                    assert_eq!(version, FORMAT_VERSION, "{name} must be rejected");
+                    assert_eq!(header.program_id, program_id, "{name}");
+                    verify(proof, &view);
+                }
+                Err(DecodeError::UnsupportedVersion(v)) => {
+                    assert_ne!(version, FORMAT_VERSION, "{name} must decode");
+                    assert_eq!(v, version, "{name}");
+                }
+                Err(err) => panic!("{name}: {err}"),
+            }
+        }
+        assert!(
+            versions.contains(&FORMAT_VERSION),
+            "missing golden proof v{FORMAT_VERSION}_add.proof, run the test with {BLESS_ENV}=1"
+        );
+    }
+}
@@ -4,10 +4,13 @@ pub mod extensions;
 pub mod trace;
 
 pub mod column;
+pub mod error;
+pub mod format;
 pub mod traits;
 pub mod virtual_column;
 
 pub mod machine;
+pub mod report;
 
 #[cfg(test)]
 mod test_utils;
@@ -15,9 +18,11 @@ mod test_utils;
 use nexus_vm::emulator::InternalView;
 pub(crate) use nexus_vm::WORD_SIZE;
 
+pub use error::ProvingError;
 pub use machine::Proof;
+pub use report::{ProverReport, ReportOptions};
 
-pub use stwo_prover::core::prover::{ProvingError, VerificationError};
+pub use stwo_prover::core::prover::VerificationError;
 
 pub fn prove(
     trace: &impl nexus_vm::trace::Trace,
@@ -26,6 +31,21 @@ pub fn prove(
     machine::Machine::<machine::BaseComponent>::prove(trace, view)
 }
 
+/// Same as [`prove`], additionally returns a report of the proving costs.
+///
+/// The report is collected with default [`ReportOptions`], leaving the peak memory tracking of the process intact.
+pub fn prove_with_report(
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+) -> Result<(Proof, ProverReport), ProvingError> {
+    machine::Machine::<machine::BaseComponent>::prove_with_report(
+        &[],
+        trace,
+        view,
+        ReportOptions::default(),
+    )
+}
+
 pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), VerificationError> {
     machine::Machine::<machine::BaseComponent>::verify(
         proof,
@@ -10,7 +10,7 @@ use stwo_prover::{
         fields::qm31::SecureField,
         pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec},
         poly::circle::{CanonicCoset, PolyOps},
-        prover::{prove, verify, ProvingError, StarkProof, VerificationError},
+        prover::{prove, verify, StarkProof, VerificationError},
         vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
     },
 };
@@ -20,9 +20,12 @@ use super::trace::{
     program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
     PreprocessedTraces, TracesBuilder,
 };
+use nexus_common::cpu::Registers;
 use nexus_vm::{
     emulator::{InternalView, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View},
+    riscv::{BuiltinOpcode, Register},
     trace::Trace,
+    SyscallCode,
 };
 
 use super::components::{MachineComponent, MachineEval, LOG_CONSTRAINT_DEGREE};
@@ -34,9 +37,14 @@ use crate::{
         RangeCheckChip, RegisterMemCheckChip, SllChip, SltChip, SltuChip, SraChip, SrlChip,
         SubChip, SyscallChip, TimestampChip,
     },
-    column::{PreprocessedColumn, ProgramColumn},
-    components::{self, AllLookupElements},
+    column::{Column, PreprocessedColumn, ProgramColumn},
+    components::AllLookupElements,
+    error::ProvingError,
     extensions::ExtensionComponent,
+    report::{
+        ChipRecorder, ColumnCounts, ExtensionReport, LogSizeSource, PhaseRecorder, ProverReport,
+        ProvingPhase, ReportOptions,
+    },
     traits::generate_interaction_trace,
 };
 use serde::{Deserialize, Serialize};
@@ -87,6 +95,10 @@ pub struct Proof {
     pub stark_proof: StarkProof<Blake2sMerkleHasher>,
     pub claimed_sum: Vec<SecureField>,
     pub log_size: u32,
+    /// Log sizes of extension components, in the order of base extensions followed by extra ones.
+    pub extension_log_sizes: Vec<u32>,
+    /// Lookup elements drawn by the prover, the verifier draws its own and rejects the proof on mismatch.
+    pub lookup_elements: AllLookupElements,
 }
 
 impl Proof {
@@ -96,10 +108,17 @@ impl Proof {
             stark_proof,
             claimed_sum,
             log_size,
+            extension_log_sizes,
+            lookup_elements,
         } = self;
         stark_proof.size_estimate()
             + claimed_sum.iter().map(std::mem::size_of_val).sum::<usize>()
             + std::mem::size_of_val(log_size)
+            + extension_log_sizes
+                .iter()
+                .map(std::mem::size_of_val)
+                .sum::<usize>()
+            + lookup_elements.size_estimate()
     }
 }
 
@@ -124,34 +143,44 @@ impl<C: MachineChip + Sync> Machine<C> {
         trace: &impl Trace,
         view: &View,
     ) -> Result<Proof, ProvingError> {
+        Self::prove_inner(extensions, trace, view, None).map(|(proof, _)| proof)
+    }
+
+    /// Same as [`Self::prove_with_extensions`], additionally returns a [`ProverReport`] describing the cost of each
+    /// proving phase and chip.
+    ///
+    /// Measuring chips adds an overhead to trace generation.
+    pub fn prove_with_report(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        options: ReportOptions,
+    ) -> Result<(Proof, ProverReport), ProvingError> {
+        Self::prove_inner(extensions, trace, view, Some(options))
+            .map(|(proof, report)| (proof, report.expect("report was requested")))
+    }
+
+    fn prove_inner(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        report_options: Option<ReportOptions>,
+    ) -> Result<(Proof, Option<ProverReport>), ProvingError> {
+        Self::check_provable(trace)?;
+
+        let mut recorder = PhaseRecorder::new(report_options);
+        let mut chip_recorder = report_options.map(|_| ChipRecorder::new::<C>());
+
         let num_steps = trace.get_num_steps();
         let program_len = view.get_program_memory().program.len();
         let tracked_ram_size = view.view_tracked_ram_size();
 
-        let log_size = Self::max_log_size(&[num_steps, program_len, tracked_ram_size])
-            .max(PreprocessedTraces::MIN_LOG_SIZE);
+        let (log_size, log_size_source) =
+            Self::choose_log_size(num_steps, program_len, tracked_ram_size);
 
         let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
 
-        let config = PcsConfig::default();
-        // Precompute twiddles.
-        let twiddles = SimdBackend::precompute_twiddles(
-            CanonicCoset::new(
-                log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
-            )
-            .circle_domain()
-            .half_coset,
-        );
-
-        // Setup protocol.
-        let prover_channel = &mut Blake2sChannel::default();
-        for byte in view.view_associated_data().unwrap_or_default() {
-            prover_channel.mix_u64(byte.into());
-        }
-
-        let mut commitment_scheme =
-            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
-
+        recorder.start(ProvingPhase::TraceGeneration);
         // Fill columns of the preprocessed trace.
         let preprocessed_trace = PreprocessedTraces::new(log_size);
 
@@ -167,17 +196,61 @@ impl<C: MachineChip + Sync> Machine<C> {
         let mut prover_side_note = SideNote::new(&program_traces, view);
         let program_steps = iter_program_steps(trace, prover_traces.num_rows());
         for (row_idx, program_step) in program_steps.enumerate() {
-            C::fill_main_trace(
-                &mut prover_traces,
-                row_idx,
-                &program_step,
-                &mut prover_side_note,
-            );
+            if let Some(chip_recorder) = &mut chip_recorder {
+                chip_recorder.fill_main_trace::<C>(
+                    &mut prover_traces,
+                    row_idx,
+                    &program_step,
+                    &mut prover_side_note,
+                );
+            } else {
+                C::fill_main_trace(
+                    &mut prover_traces,
+                    row_idx,
+                    &program_step,
+                    &mut prover_side_note,
+                );
+            }
         }
 
         let finalized_trace = prover_traces.finalize();
         let finalized_program_trace = program_traces.finalize();
 
+        // Fill columns of extensions.
+        let extension_log_sizes: Vec<u32> = extensions_iter
+            .clone()
+            .map(|ext| ext.compute_log_size(&prover_side_note))
+            .collect();
+        let ext_preprocessed_traces: Vec<_> = extensions_iter
+            .clone()
+            .zip(&extension_log_sizes)
+            .map(|(ext, &ext_log_size)| ext.generate_preprocessed_trace(ext_log_size))
+            .collect();
+        let ext_original_traces: Vec<_> = extensions_iter
+            .clone()
+            .map(|ext| ext.generate_original_trace(&prover_side_note))
+            .collect();
+
+        recorder.start(ProvingPhase::Commitment);
+        let config = PcsConfig::default();
+        // Precompute twiddles.
+        let twiddles = SimdBackend::precompute_twiddles(
+            CanonicCoset::new(
+                log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
+            )
+            .circle_domain()
+            .half_coset,
+        );
+
+        // Setup protocol.
+        let prover_channel = &mut Blake2sChannel::default();
+        for byte in view.view_associated_data().unwrap_or_default() {
+            prover_channel.mix_u64(byte.into());
+        }
+
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+
         let mut tree_builder = commitment_scheme.tree_builder();
         let _preprocessed_trace_location = tree_builder.extend_evals(
             preprocessed_trace
@@ -187,8 +260,8 @@ impl<C: MachineChip + Sync> Machine<C> {
                 .chain(finalized_program_trace.clone().into_circle_evaluation()),
         );
         // Handle extensions for the preprocessed trace
-        for ext in extensions_iter.clone() {
-            tree_builder.extend_evals(ext.generate_preprocessed_trace());
+        for ext_preprocessed_trace in ext_preprocessed_traces {
+            tree_builder.extend_evals(ext_preprocessed_trace);
         }
         tree_builder.commit(prover_channel);
 
@@ -196,33 +269,49 @@ impl<C: MachineChip + Sync> Machine<C> {
         let _main_trace_location =
             tree_builder.extend_evals(finalized_trace.clone().into_circle_evaluation());
         // Handle extensions for the main trace
-        for ext in extensions_iter.clone() {
-            tree_builder.extend_evals(ext.generate_original_trace(&prover_side_note));
+        for ext_original_trace in ext_original_traces {
+            tree_builder.extend_evals(ext_original_trace);
         }
         tree_builder.commit(prover_channel);
 
         let mut lookup_elements = AllLookupElements::default();
         C::draw_lookup_elements(&mut lookup_elements, prover_channel);
 
-        let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
-            &finalized_trace,
-            &preprocessed_trace,
-            &finalized_program_trace,
-            &lookup_elements,
-        );
-
-        let mut tree_builder = commitment_scheme.tree_builder();
-        let _interaction_trace_location = tree_builder.extend_evals(interaction_trace);
+        recorder.start(ProvingPhase::InteractionTrace);
+        let (interaction_trace, claimed_sum) = if let Some(chip_recorder) = &mut chip_recorder {
+            chip_recorder.generate_interaction_trace::<C>(
+                &finalized_trace,
+                &preprocessed_trace,
+                &finalized_program_trace,
+                &lookup_elements,
+            )
+        } else {
+            generate_interaction_trace::<C>(
+                &finalized_trace,
+                &preprocessed_trace,
+                &finalized_program_trace,
+                &lookup_elements,
+            )
+        };
         // Handle extensions for the interaction trace
         let mut all_claimed_sum = vec![claimed_sum];
+        let mut ext_interaction_traces = Vec::new();
         for ext in extensions_iter.clone() {
             let (interaction_trace, claimed_sum) =
                 ext.generate_interaction_trace(&prover_side_note, &lookup_elements);
             all_claimed_sum.push(claimed_sum);
-            tree_builder.extend_evals(interaction_trace);
+            ext_interaction_traces.push(interaction_trace);
+        }
+
+        recorder.start(ProvingPhase::Commitment);
+        let mut tree_builder = commitment_scheme.tree_builder();
+        let _interaction_trace_location = tree_builder.extend_evals(interaction_trace);
+        for ext_interaction_trace in ext_interaction_traces {
+            tree_builder.extend_evals(ext_interaction_trace);
         }
         tree_builder.commit(prover_channel);
 
+        recorder.start(ProvingPhase::Fri);
         let tree_span_provider = &mut TraceLocationAllocator::default();
         let main_component = MachineComponent::new(
             tree_span_provider,
@@ -230,9 +319,16 @@ impl<C: MachineChip + Sync> Machine<C> {
             claimed_sum,
         );
         let ext_components: Vec<Box<dyn ComponentProver<SimdBackend>>> = extensions_iter
+            .clone()
+            .zip(&extension_log_sizes)
             .zip(all_claimed_sum.get(1..).unwrap_or_default())
-            .map(|(ext, claimed_sum)| {
-                ext.to_component_prover(tree_span_provider, &lookup_elements, *claimed_sum)
+            .map(|((ext, &ext_log_size), claimed_sum)| {
+                ext.to_component_prover(
+                    ext_log_size,
+                    tree_span_provider,
+                    &lookup_elements,
+                    *claimed_sum,
+                )
             })
             .collect();
         let mut components_ref: Vec<&dyn ComponentProver<SimdBackend>> =
@@ -244,11 +340,46 @@ impl<C: MachineChip + Sync> Machine<C> {
             commitment_scheme,
         )?;
 
-        Ok(Proof {
-            stark_proof: proof,
-            claimed_sum: all_claimed_sum,
+        let report = chip_recorder.map(|chip_recorder| ProverReport {
             log_size,
-        })
+            log_size_source,
+            num_steps,
+            program_len,
+            tracked_ram_size,
+            columns: ColumnCounts {
+                preprocessed: PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM,
+                main: Column::COLUMNS_NUM,
+                interaction: main_component.trace_log_degree_bounds()[INTERACTION_TRACE_IDX].len(),
+            },
+            chips: chip_recorder.into_reports(),
+            extensions: extensions_iter
+                .zip(&extension_log_sizes)
+                .map(|(ext, &ext_log_size)| {
+                    let sizes = ext.trace_sizes(ext_log_size);
+                    ExtensionReport {
+                        name: ext.name().to_string(),
+                        log_size: ext_log_size,
+                        columns: ColumnCounts {
+                            preprocessed: ext.preprocessed_trace_sizes(ext_log_size).len(),
+                            main: sizes[ORIGINAL_TRACE_IDX].len(),
+                            interaction: sizes[INTERACTION_TRACE_IDX].len(),
+                        },
+                    }
+                })
+                .collect(),
+            phases: recorder.into_phases(),
+        });
+
+        Ok((
+            Proof {
+                stark_proof: proof,
+                claimed_sum: all_claimed_sum,
+                log_size,
+                extension_log_sizes,
+                lookup_elements,
+            },
+            report,
+        ))
     }
 
     pub fn verify(
@@ -283,6 +414,8 @@ impl<C: MachineChip + Sync> Machine<C> {
             stark_proof: proof,
             claimed_sum,
             log_size,
+            extension_log_sizes,
+            lookup_elements: received_lookup_elements,
         } = proof;
 
         if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
@@ -290,12 +423,25 @@ impl<C: MachineChip + Sync> Machine<C> {
                 "claimed sum len mismatch".to_string(),
             ));
         }
+        if extension_log_sizes.len() != extensions.len() + BASE_EXTENSIONS.len() {
+            return Err(VerificationError::InvalidStructure(
+                "extension log sizes len mismatch".to_string(),
+            ));
+        }
         if claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
             return Err(VerificationError::InvalidStructure(
                 "claimed logup sum is not zero".to_string(),
             ));
         }
         let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+        for (ext, ext_log_size) in extensions_iter.clone().zip(&extension_log_sizes) {
+            if !ext.log_size_bounds().contains(ext_log_size) {
+                return Err(VerificationError::InvalidStructure(format!(
+                    "invalid extension log size {ext_log_size}, expected {:?}",
+                    ext.log_size_bounds()
+                )));
+            }
+        }
 
         let config = PcsConfig::default();
         let verifier_channel = &mut Blake2sChannel::default();
@@ -338,8 +484,8 @@ impl<C: MachineChip + Sync> Machine<C> {
                     .chain(program_trace.into_circle_evaluation()),
             );
             // Handle extensions for the preprocessed trace
-            for ext in extensions_iter.clone() {
-                tree_builder.extend_evals(ext.generate_preprocessed_trace());
+            for (ext, &ext_log_size) in extensions_iter.clone().zip(&extension_log_sizes) {
+                tree_builder.extend_evals(ext.generate_preprocessed_trace(ext_log_size));
             }
             tree_builder.commit(verifier_channel);
 
@@ -351,51 +497,66 @@ impl<C: MachineChip + Sync> Machine<C> {
             }
         }
 
-        // Retrieve the expected column sizes in each commitment interaction, from the AIR.
-
-        // Info evaluation can be avoided if the prover sends lookup elements along with the proof, this requires
-        // implementing  [`serde::Serialize`] for all relations and [`AllLookupElements`]. Note that the verifier
-        // should still independently draw elements and match it against received ones.
-        let mut sizes = vec![components::machine_component_info::<C>()
-            .mask_offsets
-            .as_cols_ref()
-            .map_cols(|_| log_size)];
-        for ext in extensions_iter.clone() {
-            sizes.push(ext.trace_sizes());
-        }
-        let mut log_sizes = TreeVec::concat_cols(sizes.into_iter());
-        // use the fact that preprocessed columns are only allowed to have [0] mask
-        log_sizes[PREPROCESSED_TRACE_IDX] = std::iter::repeat(log_size)
-            .take(PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM)
-            .collect();
-        for ext in extensions_iter.clone() {
-            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
-            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes());
-        }
-
-        for idx in [PREPROCESSED_TRACE_IDX, ORIGINAL_TRACE_IDX] {
-            commitment_scheme.commit(proof.commitments[idx], &log_sizes[idx], verifier_channel);
+        // Build components from the lookup elements received from the prover, and retrieve the expected column sizes
+        // in each commitment interaction from them. The elements are checked against independently drawn ones before
+        // running the verifier, until then only make sure that none of the relations is missing.
+        let mut expected_relations = AllLookupElements::default();
+        C::draw_lookup_elements(&mut expected_relations, &mut Blake2sChannel::default());
+        if !received_lookup_elements.has_same_relations(&expected_relations) {
+            return Err(VerificationError::InvalidStructure(
+                "lookup elements mismatch".to_string(),
+            ));
         }
 
-        let mut lookup_elements = AllLookupElements::default();
-        C::draw_lookup_elements(&mut lookup_elements, verifier_channel);
-
         let tree_span_provider = &mut TraceLocationAllocator::default();
         let main_component = MachineComponent::new(
             tree_span_provider,
-            MachineEval::<C>::new(log_size, lookup_elements.clone()),
+            MachineEval::<C>::new(log_size, received_lookup_elements.clone()),
             claimed_sum[0],
         );
 
         let ext_components: Vec<Box<dyn Component>> = extensions_iter
+            .clone()
+            .zip(&extension_log_sizes)
             .zip(claimed_sum.get(1..).unwrap_or_default())
-            .map(|(ext, claimed_sum)| {
-                ext.to_component(tree_span_provider, &lookup_elements, *claimed_sum)
+            .map(|((ext, &ext_log_size), claimed_sum)| {
+                ext.to_component(
+                    ext_log_size,
+                    tree_span_provider,
+                    &received_lookup_elements,
+                    *claimed_sum,
+                )
             })
             .collect();
         let mut components_ref: Vec<&dyn Component> = ext_components.iter().map(|c| &**c).collect();
         components_ref.insert(0, &main_component);
 
+        let mut log_sizes = TreeVec::concat_cols(
+            components_ref
+                .iter()
+                .map(|component| component.trace_log_degree_bounds()),
+        );
+        // use the fact that preprocessed columns are only allowed to have [0] mask
+        log_sizes[PREPROCESSED_TRACE_IDX] = std::iter::repeat(log_size)
+            .take(PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM)
+            .collect();
+        for (ext, &ext_log_size) in extensions_iter.zip(&extension_log_sizes) {
+            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
+            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes(ext_log_size));
+        }
+
+        for idx in [PREPROCESSED_TRACE_IDX, ORIGINAL_TRACE_IDX] {
+            commitment_scheme.commit(proof.commitments[idx], &log_sizes[idx], verifier_channel);
+        }
+
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(&mut lookup_elements, verifier_channel);
+        if lookup_elements != received_lookup_elements {
+            return Err(VerificationError::InvalidStructure(
+                "lookup elements mismatch".to_string(),
+            ));
+        }
+
         commitment_scheme.commit(
             proof.commitments[INTERACTION_TRACE_IDX],
             &log_sizes[INTERACTION_TRACE_IDX],
@@ -413,11 +574,81 @@ impl<C: MachineChip + Sync> Machine<C> {
             .max()
             .expect("sizes is empty")
     }
+
+    /// Rejects traces executing instructions that the chips do not constrain.
+    fn check_provable(trace: &impl Trace) -> Result<(), ProvingError> {
+        for block in trace.get_blocks_iter() {
+            // The registers are recorded before the block, replay the writes of its steps to know the
+            // syscall code of each ECALL.
+            let mut regs = block.regs;
+            for step in &block.steps {
+                // The chips assume 4-byte instructions when constraining the program counter.
+                if step.instruction.compressed {
+                    return Err(ProvingError::UnprovableInstruction {
+                        pc: step.pc,
+                        reason: "compressed instructions are not constrained, build the guest without the C extension",
+                    });
+                }
+
+                let reason = match step.instruction.opcode.builtin() {
+                    Some(BuiltinOpcode::CSRRS) => Some("counter CSR reads are not constrained"),
+                    Some(BuiltinOpcode::ECALL)
+                        if regs.read(Register::X17)
+                            == u32::from(SyscallCode::ReadFromPrivateInputBulk) =>
+                    {
+                        Some("bulk private input reads (0x405) are not constrained, build the guest without the `bulk-private-input` feature of nexus-rt")
+                    }
+                    _ => None,
+                };
+                if let Some(reason) = reason {
+                    return Err(ProvingError::UnprovableInstruction {
+                        pc: step.pc,
+                        reason,
+                    });
+                }
+
+                // Syscalls write their result to a0 or sp rather than to their first operand, neither
+                // of which holds the syscall code.
+                if let Some(value) = step.result {
+                    if step.instruction.opcode.builtin() != Some(BuiltinOpcode::ECALL) {
+                        regs.write(step.instruction.op_a, value);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the log size of the main trace and the requirement that determined it.
+    fn choose_log_size(
+        num_steps: usize,
+        program_len: usize,
+        tracked_ram_size: usize,
+    ) -> (u32, LogSizeSource) {
+        [
+            (num_steps, LogSizeSource::NumSteps),
+            (program_len, LogSizeSource::ProgramLength),
+            (tracked_ram_size, LogSizeSource::TrackedRam),
+        ]
+        .into_iter()
+        .map(|(size, source)| (Self::max_log_size(&[size]), source))
+        .fold(
+            (PreprocessedTraces::MIN_LOG_SIZE, LogSizeSource::Minimum),
+            |chosen, candidate| {
+                if candidate.0 > chosen.0 {
+                    candidate
+                } else {
+                    chosen
+                }
+            },
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::components::RawLookupElements;
     use nexus_vm::{
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
         trace::k_trace_direct,
@@ -447,4 +678,228 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn prove_with_report() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let (proof, report) = Machine::<BaseComponent>::prove_with_report(
+            &[],
+            &program_trace,
+            &view,
+            ReportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(report.log_size, proof.log_size);
+        assert_eq!(report.log_size_source, LogSizeSource::Minimum);
+        assert_eq!(report.columns.main, Column::COLUMNS_NUM);
+        assert!(report.chips.iter().any(|chip| chip.name == "AddChip"));
+        assert_eq!(
+            report
+                .extensions
+                .iter()
+                .map(|ext| ext.log_size)
+                .collect::<Vec<_>>(),
+            proof.extension_log_sizes
+        );
+        assert_eq!(
+            report.phases.iter().map(|p| p.phase).collect::<Vec<_>>(),
+            [
+                ProvingPhase::TraceGeneration,
+                ProvingPhase::Commitment,
+                ProvingPhase::InteractionTrace,
+                ProvingPhase::Fri,
+            ]
+        );
+
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn reject_counter_csr_read() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::CSRRS), 2, 0, 0xC00),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let err = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap_err();
+        let ProvingError::UnprovableInstruction { pc, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(pc, program_trace.blocks[1].steps[0].pc);
+    }
+
+    #[test]
+    fn reject_bulk_private_input_read() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                17,
+                0,
+                SyscallCode::ReadFromPrivateInputBulk as u32,
+            ),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let err = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap_err();
+        let ProvingError::UnprovableInstruction { pc, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(pc, program_trace.blocks[1].steps[0].pc);
+    }
+
+    #[test]
+    fn reject_bulk_private_input_read_within_block() {
+        // With k = 2 the syscall code is set by the step preceding the ECALL in the same block.
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                17,
+                0,
+                SyscallCode::ReadFromPrivateInputBulk as u32,
+            ),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 2).expect("error generating trace");
+        let block = program_trace
+            .blocks
+            .iter()
+            .find(|block| block.steps.len() == 2)
+            .expect("no block holds both steps");
+        assert_eq!(block.regs.read(Register::X17), 0);
+        let expected_pc = block.steps[1].pc;
+
+        let err = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap_err();
+        let ProvingError::UnprovableInstruction { pc, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(pc, expected_pc);
+    }
+
+    #[test]
+    fn reject_compressed_instruction() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, mut program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        // Blocks are encoded to 32-bit instructions before execution, so mark a step as compressed.
+        let step = &mut program_trace.blocks[1].steps[0];
+        step.instruction = step.instruction.clone().into_compressed();
+        let expected_pc = step.pc;
+
+        let err = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap_err();
+        let ProvingError::UnprovableInstruction { pc, .. } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(pc, expected_pc);
+    }
+
+    #[test]
+    fn reject_invalid_extension_log_size() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let mut proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(proof.extension_log_sizes.len(), BASE_EXTENSIONS.len());
+
+        // final register state is the first base extension and always covers all 32 registers
+        proof.extension_log_sizes[0] += 1;
+        let err = Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, VerificationError::InvalidStructure(_)));
+    }
+
+    #[test]
+    fn reject_mismatched_lookup_elements() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let verify = |proof| {
+            Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+
+        let bytes = postcard::to_allocvec(&proof).unwrap();
+        let deserialized: Proof = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(deserialized.lookup_elements, proof.lookup_elements);
+        verify(deserialized).unwrap();
+
+        let mut proof = proof;
+        proof.lookup_elements = AllLookupElements::dummy();
+        let err = verify(proof).unwrap_err();
+        assert!(matches!(err, VerificationError::InvalidStructure(_)));
+    }
+
+    #[test]
+    fn reject_missing_lookup_elements() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let mut proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        let bytes = postcard::to_allocvec(&proof.lookup_elements).unwrap();
+        let mut raw: Vec<(String, RawLookupElements)> = postcard::from_bytes(&bytes).unwrap();
+        let len = raw.len();
+        raw.retain(|(name, _)| name != "BitOpLookupElements");
+        assert_eq!(raw.len(), len - 1);
+        let bytes = postcard::to_allocvec(&raw).unwrap();
+        proof.lookup_elements = postcard::from_bytes(&bytes).unwrap();
+
+        let err = Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, VerificationError::InvalidStructure(_)));
+    }
 }
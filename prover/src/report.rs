@@ -0,0 +1,540 @@
+//! Proving cost report.
+//!
+//! [`ProverReport`] is returned by [`Machine::prove_with_report`](crate::machine::Machine::prove_with_report) and
+//! breaks down the cost of a proof by phase and by chip. All chips of the base component share the main trace, so
+//! the per-chip cost is given as the time spent by the chip in trace generation, and the number of main trace rows
+//! and columns the chip wrote to.
+//!
+//! Peak memory is only available on Linux. By default it is the resident set high-water mark of the process at the
+//! end of each phase, which includes everything allocated before proving started. Setting
+//! [`ReportOptions::reset_peak_memory`] measures each phase separately instead.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use stwo_prover::{
+    constraint_framework::logup::LogupTraceGenerator,
+    core::{
+        backend::simd::SimdBackend,
+        fields::{m31::BaseField, qm31::SecureField},
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        ColumnVec,
+    },
+};
+
+use crate::{
+    column::Column,
+    components::AllLookupElements,
+    trace::{
+        program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces, PreprocessedTraces,
+        ProgramStep, TracesBuilder,
+    },
+    traits::{ChipVisitor, MachineChip},
+};
+
+/// The requirement that determined the log size of the main trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogSizeSource {
+    /// Number of executed instructions.
+    NumSteps,
+    /// Number of instructions in the program.
+    ProgramLength,
+    /// Number of RAM bytes tracked by memory checking.
+    TrackedRam,
+    /// The minimum log size supported by the preprocessed trace.
+    Minimum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProvingPhase {
+    /// Filling the preprocessed, program and main traces, including extensions.
+    TraceGeneration,
+    /// Generating logup interaction traces, including extensions.
+    InteractionTrace,
+    /// Twiddle precomputation, interpolation and Merkle commitment of all trace trees.
+    Commitment,
+    /// Composition polynomial, FRI and decommitment, i.e. the rest of the STARK protocol.
+    Fri,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnCounts {
+    pub preprocessed: usize,
+    pub main: usize,
+    pub interaction: usize,
+}
+
+impl ColumnCounts {
+    pub fn total(&self) -> usize {
+        self.preprocessed + self.main + self.interaction
+    }
+}
+
+/// Options for collecting a [`ProverReport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Reset the resident set high-water mark of the process at the start of each phase, so that the peak memory of
+    /// a phase excludes earlier allocations.
+    ///
+    /// The reset is done by writing to `/proc/self/clear_refs` and affects the whole process, including the
+    /// `ru_maxrss` reported by `getrusage`. Only enable it if nothing else in the process tracks peak memory.
+    pub reset_peak_memory: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseReport {
+    pub phase: ProvingPhase,
+    pub duration: Duration,
+    /// Peak resident memory in bytes, if it could be measured.
+    ///
+    /// This is the peak of the phase if [`ReportOptions::reset_peak_memory`] is set, and the peak of the process
+    /// up to the end of the phase otherwise.
+    pub peak_memory: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChipReport {
+    pub name: String,
+    /// Number of main trace rows the chip wrote to.
+    pub rows: usize,
+    /// Number of main trace columns the chip wrote to.
+    ///
+    /// Writes that leave a cell unchanged, e.g. zeroes, are not detected.
+    pub columns: usize,
+    pub main_trace_duration: Duration,
+    pub interaction_trace_duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionReport {
+    pub name: String,
+    pub log_size: u32,
+    pub columns: ColumnCounts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverReport {
+    pub log_size: u32,
+    pub log_size_source: LogSizeSource,
+    pub num_steps: usize,
+    pub program_len: usize,
+    pub tracked_ram_size: usize,
+    /// Columns of the base component, shared by all chips.
+    pub columns: ColumnCounts,
+    pub chips: Vec<ChipReport>,
+    pub extensions: Vec<ExtensionReport>,
+    pub phases: Vec<PhaseReport>,
+}
+
+impl ProverReport {
+    pub fn total_duration(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.duration).sum()
+    }
+}
+
+fn fmt_memory(peak_memory: Option<u64>) -> String {
+    peak_memory.map_or_else(
+        || "n/a".to_string(),
+        |bytes| format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64),
+    )
+}
+
+impl fmt::Display for ProverReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "log size: {} (chosen by {:?}; steps: {}, program length: {}, tracked RAM: {})",
+            self.log_size,
+            self.log_size_source,
+            self.num_steps,
+            self.program_len,
+            self.tracked_ram_size
+        )?;
+        writeln!(
+            f,
+            "columns: {} preprocessed, {} main, {} interaction",
+            self.columns.preprocessed, self.columns.main, self.columns.interaction
+        )?;
+
+        writeln!(f, "\n{:<20} {:>12} {:>12}", "phase", "time", "peak memory")?;
+        for phase in &self.phases {
+            writeln!(
+                f,
+                "{:<20} {:>12.3?} {:>12}",
+                format!("{:?}", phase.phase),
+                phase.duration,
+                fmt_memory(phase.peak_memory)
+            )?;
+        }
+        writeln!(f, "{:<20} {:>12.3?}", "total", self.total_duration())?;
+
+        writeln!(
+            f,
+            "\n{:<24} {:>8} {:>8} {:>12} {:>12}",
+            "chip", "rows", "columns", "main", "interaction"
+        )?;
+        for chip in &self.chips {
+            writeln!(
+                f,
+                "{:<24} {:>8} {:>8} {:>12.3?} {:>12.3?}",
+                chip.name,
+                chip.rows,
+                chip.columns,
+                chip.main_trace_duration,
+                chip.interaction_trace_duration
+            )?;
+        }
+
+        writeln!(
+            f,
+            "\n{:<24} {:>8} {:>8}",
+            "extension", "log size", "columns"
+        )?;
+        for ext in &self.extensions {
+            writeln!(
+                f,
+                "{:<24} {:>8} {:>8}",
+                ext.name,
+                ext.log_size,
+                ext.columns.total()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod memory {
+    /// Resets the peak resident set size of the process, returns `false` if not permitted.
+    pub(super) fn reset_peak() -> bool {
+        std::fs::write("/proc/self/clear_refs", "5").is_ok()
+    }
+
+    /// Returns the peak resident set size of the process in bytes.
+    pub(super) fn peak() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let kib: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .ok()?;
+        Some(kib * 1024)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod memory {
+    pub(super) fn reset_peak() -> bool {
+        false
+    }
+
+    pub(super) fn peak() -> Option<u64> {
+        None
+    }
+}
+
+/// Accumulates time and peak memory per phase, does nothing unless enabled.
+pub(crate) struct PhaseRecorder {
+    options: Option<ReportOptions>,
+    phases: Vec<PhaseReport>,
+    current: Option<(ProvingPhase, Instant, bool)>,
+}
+
+impl PhaseRecorder {
+    /// Returns a recorder that is enabled if `options` are given.
+    pub(crate) fn new(options: Option<ReportOptions>) -> Self {
+        Self {
+            options,
+            phases: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Finishes the current phase, if any, and starts recording `phase`.
+    pub(crate) fn start(&mut self, phase: ProvingPhase) {
+        let Some(options) = self.options else {
+            return;
+        };
+        self.finish();
+        // If a requested reset fails, the high-water mark would not belong to the phase and is not reported.
+        let measure_peak = !options.reset_peak_memory || memory::reset_peak();
+        self.current = Some((phase, Instant::now(), measure_peak));
+    }
+
+    pub(crate) fn finish(&mut self) {
+        let Some((phase, start, measure_peak)) = self.current.take() else {
+            return;
+        };
+        let duration = start.elapsed();
+        let peak_memory = measure_peak.then(memory::peak).flatten();
+
+        match self.phases.iter_mut().find(|report| report.phase == phase) {
+            Some(report) => {
+                report.duration += duration;
+                report.peak_memory = report.peak_memory.max(peak_memory);
+            }
+            None => self.phases.push(PhaseReport {
+                phase,
+                duration,
+                peak_memory,
+            }),
+        }
+    }
+
+    pub(crate) fn into_phases(mut self) -> Vec<PhaseReport> {
+        self.finish();
+        self.phases
+    }
+}
+
+/// Returns the name of a chip type without its module path.
+fn chip_name<C>() -> String {
+    let name = std::any::type_name::<C>();
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+/// Accumulates the cost of each chip of a component.
+pub(crate) struct ChipRecorder {
+    chips: Vec<ChipReport>,
+    /// Main trace columns written by each chip.
+    written_columns: Vec<Vec<bool>>,
+    /// Values of the current row, used for detecting writes.
+    row: Vec<BaseField>,
+}
+
+impl ChipRecorder {
+    /// Creates an empty report for each chip of `C`.
+    pub(crate) fn new<C: MachineChip>() -> Self {
+        struct Names(Vec<ChipReport>);
+
+        impl ChipVisitor for Names {
+            fn visit<C: MachineChip>(&mut self) {
+                self.0.push(ChipReport {
+                    name: chip_name::<C>(),
+                    rows: 0,
+                    columns: 0,
+                    main_trace_duration: Duration::ZERO,
+                    interaction_trace_duration: Duration::ZERO,
+                });
+            }
+        }
+
+        let mut names = Names(Vec::new());
+        C::visit_chips(&mut names);
+        let chips = names.0;
+        Self {
+            written_columns: vec![vec![false; Column::COLUMNS_NUM]; chips.len()],
+            chips,
+            row: Vec::with_capacity(Column::COLUMNS_NUM),
+        }
+    }
+
+    pub(crate) fn into_reports(self) -> Vec<ChipReport> {
+        self.chips
+            .into_iter()
+            .zip(self.written_columns)
+            .map(|(chip, written)| ChipReport {
+                columns: written.into_iter().filter(|&written| written).count(),
+                ..chip
+            })
+            .collect()
+    }
+
+    /// Same as [`MachineChip::fill_main_trace`], measuring the time spent by each chip and the cells it writes to.
+    ///
+    /// Only writes to the row `row_idx` are attributed to chips.
+    pub(crate) fn fill_main_trace<C: MachineChip>(
+        &mut self,
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        side_note: &mut SideNote,
+    ) {
+        struct FillMainTrace<'a> {
+            traces: &'a mut TracesBuilder,
+            row_idx: usize,
+            vm_step: &'a Option<ProgramStep>,
+            side_note: &'a mut SideNote,
+            row: &'a mut [BaseField],
+            chips: std::iter::Zip<
+                std::slice::IterMut<'a, ChipReport>,
+                std::slice::IterMut<'a, Vec<bool>>,
+            >,
+        }
+
+        impl ChipVisitor for FillMainTrace<'_> {
+            fn visit<C: MachineChip>(&mut self) {
+                let start = Instant::now();
+                C::fill_main_trace(self.traces, self.row_idx, self.vm_step, self.side_note);
+                let duration = start.elapsed();
+
+                let (chip, written_columns) = self.chips.next().expect("chip count mismatch");
+                chip.main_trace_duration += duration;
+
+                let mut written_row = false;
+                for ((col, value), written) in self
+                    .traces
+                    .cols
+                    .iter()
+                    .zip(&mut *self.row)
+                    .zip(written_columns)
+                {
+                    let new_value = col[self.row_idx];
+                    if new_value != *value {
+                        *value = new_value;
+                        *written = true;
+                        written_row = true;
+                    }
+                }
+                if written_row {
+                    chip.rows += 1;
+                }
+            }
+        }
+
+        self.row.clear();
+        self.row.extend(traces.cols.iter().map(|col| col[row_idx]));
+        C::visit_chips(&mut FillMainTrace {
+            traces,
+            row_idx,
+            vm_step,
+            side_note,
+            row: &mut self.row,
+            chips: self.chips.iter_mut().zip(self.written_columns.iter_mut()),
+        });
+    }
+
+    /// Same as [`generate_interaction_trace`](crate::traits::generate_interaction_trace), measuring the time spent
+    /// by each chip.
+    pub(crate) fn generate_interaction_trace<C: MachineChip>(
+        &mut self,
+        original_traces: &FinalizedTraces,
+        preprocessed_trace: &PreprocessedTraces,
+        program_traces: &ProgramTraces,
+        lookup_elements: &AllLookupElements,
+    ) -> (
+        ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+        SecureField,
+    ) {
+        struct FillInteractionTrace<'a> {
+            logup_trace_gen: LogupTraceGenerator,
+            original_traces: &'a FinalizedTraces,
+            preprocessed_trace: &'a PreprocessedTraces,
+            program_traces: &'a ProgramTraces,
+            lookup_elements: &'a AllLookupElements,
+            chips: std::slice::IterMut<'a, ChipReport>,
+        }
+
+        impl ChipVisitor for FillInteractionTrace<'_> {
+            fn visit<C: MachineChip>(&mut self) {
+                let start = Instant::now();
+                C::fill_interaction_trace(
+                    &mut self.logup_trace_gen,
+                    self.original_traces,
+                    self.preprocessed_trace,
+                    self.program_traces,
+                    self.lookup_elements,
+                );
+                let chip = self.chips.next().expect("chip count mismatch");
+                chip.interaction_trace_duration += start.elapsed();
+            }
+        }
+
+        if lookup_elements.is_empty() {
+            return (ColumnVec::new(), SecureField::zero());
+        }
+        let mut visitor = FillInteractionTrace {
+            logup_trace_gen: LogupTraceGenerator::new(original_traces.log_size()),
+            original_traces,
+            preprocessed_trace,
+            program_traces,
+            lookup_elements,
+            chips: self.chips.iter_mut(),
+        };
+        C::visit_chips(&mut visitor);
+        visitor.logup_trace_gen.finalize_last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chips::{AddChip, CpuChip, RangeCheckChip},
+        trace::{program::iter_program_steps, program_trace::ProgramTracesBuilder},
+    };
+
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    #[test]
+    fn test_chip_reports_flatten_tuples() {
+        let names: Vec<String> = ChipRecorder::new::<(CpuChip, (AddChip,), RangeCheckChip)>()
+            .into_reports()
+            .into_iter()
+            .map(|chip| chip.name)
+            .collect();
+        assert_eq!(names, ["CpuChip", "AddChip", "RangeCheckChip"]);
+    }
+
+    #[test]
+    fn test_chip_recorder_counts_writes() {
+        type Chips = (CpuChip, AddChip);
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 3, 2, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let log_size = PreprocessedTraces::MIN_LOG_SIZE;
+        let mut traces = TracesBuilder::new(log_size);
+        let program_traces =
+            ProgramTracesBuilder::new_with_empty_memory(log_size, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_traces, &view);
+        let mut recorder = ChipRecorder::new::<Chips>();
+        for (row_idx, program_step) in
+            iter_program_steps(&program_trace, traces.num_rows()).enumerate()
+        {
+            recorder.fill_main_trace::<Chips>(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+
+        let [cpu, add] = recorder
+            .into_reports()
+            .try_into()
+            .expect("two chips were recorded");
+        // The CPU chip fills every row, including padding.
+        assert_eq!(cpu.rows, traces.num_rows());
+        // ADDI and ADD, but not SUB.
+        assert_eq!(add.rows, 2);
+        assert!(add.columns > 0 && add.columns < cpu.columns);
+    }
+
+    #[test]
+    fn test_phase_recorder_accumulates() {
+        let mut recorder = PhaseRecorder::new(Some(ReportOptions::default()));
+        recorder.start(ProvingPhase::Commitment);
+        recorder.start(ProvingPhase::Fri);
+        recorder.start(ProvingPhase::Commitment);
+        let phases = recorder.into_phases();
+        assert_eq!(
+            phases.iter().map(|p| p.phase).collect::<Vec<_>>(),
+            [ProvingPhase::Commitment, ProvingPhase::Fri]
+        );
+
+        let mut recorder = PhaseRecorder::new(None);
+        recorder.start(ProvingPhase::Fri);
+        assert!(recorder.into_phases().is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+pub(crate) mod soundness;
+
 use stwo_prover::{
     constraint_framework::{assert_constraints, EvalAtRow},
     core::{
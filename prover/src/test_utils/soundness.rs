@@ -0,0 +1,410 @@
+//! Constraint-level soundness fuzzing.
+//!
+//! Honest traces satisfying the constraints only shows completeness. This module takes a valid
+//! trace, tampers with one cell of the main trace at a time and re-evaluates every constraint of
+//! the given chips. A mutation that is still accepted points to an under-constrained column.
+//!
+//! After each mutation the interaction trace is regenerated with the same lookup elements, so that
+//! logup columns are consistent with the tampered trace; the mutation is then only accepted if
+//! the claimed sum is left unchanged, since the extension components balancing it are unaware of
+//! the mutation.
+
+use std::{
+    cell::Cell,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use num_traits::One;
+use stwo_prover::{
+    constraint_framework::{assert_constraints, EvalAtRow},
+    core::{
+        backend::{simd::SimdBackend, Column as _},
+        fields::{m31::BaseField, qm31::SecureField},
+        pcs::TreeVec,
+        poly::{
+            circle::{CanonicCoset, CircleEvaluation, CirclePoly},
+            BitReversedOrder,
+        },
+        utils::{bit_reverse_index, coset_index_to_circle_domain_index},
+    },
+};
+
+use super::{commit_traces, test_params, CommittedTraces};
+use crate::{
+    column::Column,
+    components::AllLookupElements,
+    trace::{eval::TraceEval, program_trace::ProgramTraces, FinalizedTraces, PreprocessedTraces},
+    traits::{generate_interaction_trace, MachineChip},
+};
+
+/// Tampering applied to a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mutation {
+    /// `x -> 1 - x`, turns boolean flags on and off.
+    Flip,
+    /// `x -> x + 1`
+    Increment,
+    /// `x -> x - 1`
+    Decrement,
+}
+
+impl Mutation {
+    pub(crate) const ALL: [Self; 3] = [Self::Flip, Self::Increment, Self::Decrement];
+
+    fn apply(self, value: BaseField) -> BaseField {
+        match self {
+            Self::Flip => BaseField::one() - value,
+            Self::Increment => value + BaseField::one(),
+            Self::Decrement => value - BaseField::one(),
+        }
+    }
+}
+
+/// A mutation of the main trace that still satisfies every constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SurvivingMutation {
+    pub column: Column,
+    /// Index of the raw column within `column`, i.e. `0..column.size()`.
+    pub limb: usize,
+    /// Row in original (coset) order, as used by [`crate::trace::TracesBuilder`].
+    pub row: usize,
+    pub mutation: Mutation,
+}
+
+impl fmt::Display for SurvivingMutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}[{}] at row {}: {:?}",
+            self.column, self.limb, self.row, self.mutation
+        )
+    }
+}
+
+thread_local! {
+    static SILENCE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f`, catching a panic without printing it. `assert_constraints` reports violations by
+/// panicking, which is the expected outcome for almost every mutation.
+fn catch_silently<R>(f: impl FnOnce() -> R) -> std::thread::Result<R> {
+    static INSTALL_HOOK: Once = Once::new();
+    INSTALL_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCE_PANICS.with(Cell::get) {
+                default_hook(info)
+            }
+        }));
+    });
+
+    SILENCE_PANICS.with(|silent| silent.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    SILENCE_PANICS.with(|silent| silent.set(false));
+    result
+}
+
+/// Returns `true` if the traces satisfy all constraints of `C` with the given claimed sum.
+fn constraints_hold<C: MachineChip>(
+    trace_polys: &TreeVec<Vec<CirclePoly<SimdBackend>>>,
+    log_size: u32,
+    lookup_elements: &AllLookupElements,
+    claimed_sum: SecureField,
+) -> bool {
+    catch_silently(|| {
+        assert_constraints(
+            trace_polys,
+            CanonicCoset::new(log_size),
+            |mut eval| {
+                let trace_eval = TraceEval::new(&mut eval);
+                C::add_constraints(&mut eval, &trace_eval, lookup_elements);
+
+                if !lookup_elements.is_empty() {
+                    eval.finalize_logup();
+                }
+            },
+            claimed_sum,
+        )
+    })
+    .is_ok()
+}
+
+/// Applies every [`Mutation`] to each cell of `columns` at `rows` of a valid `traces`, and returns
+/// those accepted by the constraints of `C`.
+///
+/// Panics if the unmodified traces don't satisfy the constraints.
+pub(crate) fn surviving_mutations<C: MachineChip>(
+    traces: &FinalizedTraces,
+    program_trace: Option<ProgramTraces>,
+    columns: &[Column],
+    rows: &[usize],
+) -> Vec<SurvivingMutation> {
+    let log_size = traces.log_size();
+    let (config, twiddles) = test_params(log_size);
+    let domain = CanonicCoset::new(log_size).circle_domain();
+
+    let CommittedTraces {
+        lookup_elements,
+        preprocessed_trace,
+        interaction_trace,
+        claimed_sum,
+        program_trace,
+        ..
+    } = commit_traces::<C>(config, &twiddles, traces, program_trace);
+
+    let interpolate = |evals: Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>| {
+        evals
+            .into_iter()
+            .map(|c| c.interpolate())
+            .collect::<Vec<_>>()
+    };
+    let mut trace_polys = TreeVec::new(vec![
+        interpolate(
+            [
+                preprocessed_trace.clone().into_circle_evaluation(),
+                program_trace.clone().into_circle_evaluation(),
+            ]
+            .concat(),
+        ),
+        interpolate(traces.clone().into_circle_evaluation()),
+        interpolate(interaction_trace),
+    ]);
+    assert!(
+        constraints_hold::<C>(&trace_polys, log_size, &lookup_elements, claimed_sum),
+        "unmodified traces must satisfy the constraints"
+    );
+    let interaction_polys = trace_polys[2].clone();
+
+    // Finalized columns are stored in bit reversed circle domain order.
+    let mut positions = vec![0; 1 << log_size];
+    for (position, row) in (0..1 << log_size)
+        .map(|i| bit_reverse_index(coset_index_to_circle_domain_index(i, log_size), log_size))
+        .enumerate()
+    {
+        positions[row] = position;
+    }
+
+    let mut tampered = traces.clone();
+    let mut survivors = Vec::new();
+    for &column in columns {
+        for limb in 0..column.size() {
+            let idx = column.offset() + limb;
+            let original_poly = trace_polys[1][idx].clone();
+
+            for &row in rows {
+                let position = positions[row];
+                let original = tampered.cols_mut()[idx].at(position);
+
+                for mutation in Mutation::ALL {
+                    let value = mutation.apply(original);
+                    if value == original {
+                        continue;
+                    }
+                    tampered.cols_mut()[idx].set(position, value);
+                    trace_polys[1][idx] =
+                        CircleEvaluation::new(domain, tampered.cols_mut()[idx].clone())
+                            .interpolate();
+
+                    let accepted = catch_silently(|| {
+                        generate_interaction_trace::<C>(
+                            &tampered,
+                            &preprocessed_trace,
+                            &program_trace,
+                            &lookup_elements,
+                        )
+                    })
+                    .is_ok_and(|(interaction_trace, tampered_sum)| {
+                        trace_polys[2] = interpolate(interaction_trace);
+                        tampered_sum == claimed_sum
+                            && constraints_hold::<C>(
+                                &trace_polys,
+                                log_size,
+                                &lookup_elements,
+                                tampered_sum,
+                            )
+                    });
+                    if accepted {
+                        survivors.push(SurvivingMutation {
+                            column,
+                            limb,
+                            row,
+                            mutation,
+                        });
+                    }
+                }
+
+                tampered.cols_mut()[idx].set(position, original);
+            }
+            trace_polys[1][idx] = original_poly;
+            trace_polys[2] = interaction_polys.clone();
+        }
+    }
+    survivors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, TimestampChip,
+        },
+        machine::BaseComponent,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+            TracesBuilder,
+        },
+    };
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+        SyscallCode,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    type Chips = (
+        CpuChip,
+        DecodingCheckChip,
+        AddChip,
+        RegisterMemCheckChip,
+        ProgramMemCheckChip,
+        TimestampChip,
+        RangeCheckChip,
+    );
+    const ADD_COLUMNS: &[Column] = &[Column::ValueA, Column::CarryFlag];
+
+    /// Returns the traces of `basic_block` filled by the chips of `C`, along with the number of
+    /// executed steps.
+    fn fill_traces<C: MachineChip>(
+        basic_block: &Vec<BasicBlock>,
+    ) -> (FinalizedTraces, ProgramTraces, usize) {
+        let (view, vm_traces) = k_trace_direct(basic_block, 1).expect("Failed to create trace");
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_trace =
+            ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, view.get_program_memory());
+        let mut side_note = SideNote::new(&program_trace, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            C::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        let num_steps = vm_traces.blocks.iter().map(|block| block.steps.len()).sum();
+        (traces.finalize(), program_trace.finalize(), num_steps)
+    }
+
+    fn add_traces() -> (FinalizedTraces, ProgramTraces) {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0xff),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (traces, program_trace, _) = fill_traces::<Chips>(&basic_block);
+        (traces, program_trace)
+    }
+
+    /// A program executing every instruction family constrained by [`BaseComponent`]. The jumps
+    /// and taken branches skip over unimplemented instructions.
+    #[rustfmt::skip]
+    fn representative_program() -> Vec<BasicBlock> {
+        vec![BasicBlock::new(vec![
+            // x20 = 0x80000 + x2, a writable address.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 20, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 128),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 20, 3, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LB), 6, 20, 0),
+            // x7 = 128 - 0x80000 is negative.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 7, 3, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLTU), 8, 7, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLT), 9, 7, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::XOR), 10, 7, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SRLI), 11, 7, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SRAI), 12, 7, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 13, 0, 0xABCDE),
+            // JALR to three instructions past the AUIPC.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 14, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 15, 14, 12),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 16, 0, 8),
+            Instruction::unimpl(),
+            // Only BNE is not taken.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 3, 3, 0xff),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 3, 3, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BLT), 7, 3, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BLTU), 3, 7, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BGE), 3, 7, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BGEU), 7, 3, 8),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::OverwriteHeapPointer as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])]
+    }
+
+    /// Mutations of these columns are known to survive the constraints of [`BaseComponent`] on
+    /// [`representative_program`]. Each entry should come with the reason it is sound.
+    const ALLOWED_SURVIVORS: &[Column] = &[];
+
+    #[test]
+    fn test_add_result_is_constrained() {
+        let (traces, program_trace) = add_traces();
+        let survivors =
+            surviving_mutations::<Chips>(&traces, Some(program_trace), ADD_COLUMNS, &[0, 1]);
+        assert!(
+            survivors.is_empty(),
+            "under-constrained cells: {}",
+            survivors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    #[test]
+    fn test_base_component_is_constrained() {
+        let (traces, program_trace, num_steps) =
+            fill_traces::<BaseComponent>(&representative_program());
+        // Every executed row, and the first padding row.
+        let rows: Vec<usize> = (0..=num_steps).collect();
+        let survivors = surviving_mutations::<BaseComponent>(
+            &traces,
+            Some(program_trace),
+            Column::ALL_VARIANTS,
+            &rows,
+        );
+        let unexpected: Vec<String> = survivors
+            .iter()
+            .filter(|survivor| !ALLOWED_SURVIVORS.contains(&survivor.column))
+            .map(ToString::to_string)
+            .collect();
+        assert!(
+            unexpected.is_empty(),
+            "under-constrained cells: {}",
+            unexpected.join(", ")
+        );
+    }
+
+    #[test]
+    fn test_missing_chip_is_detected() {
+        // Without AddChip nothing relates the result of an addition to its operands.
+        let (traces, program_trace) = add_traces();
+        let survivors =
+            surviving_mutations::<CpuChip>(&traces, Some(program_trace), ADD_COLUMNS, &[1]);
+        assert_eq!(survivors.len(), 6 * Mutation::ALL.len());
+        assert!(survivors.contains(&SurvivingMutation {
+            column: Column::CarryFlag,
+            limb: 0,
+            row: 1,
+            mutation: Mutation::Flip,
+        }));
+    }
+}
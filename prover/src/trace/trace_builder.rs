@@ -155,6 +155,12 @@ impl FinalizedTraces {
         std::array::from_fn(|i| &self.cols[col.offset() + i])
     }
 
+    /// Returns mutable access to all raw columns, used for tampering with a valid trace in tests.
+    #[cfg(test)]
+    pub(crate) fn cols_mut(&mut self) -> &mut [BaseColumn] {
+        &mut self.cols
+    }
+
     pub fn into_circle_evaluation(
         self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
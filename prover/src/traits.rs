@@ -64,8 +64,8 @@ pub trait MachineChip {
     /// # Example
     ///
     /// ```ignore
-    /// stwo_prover::relation!(LookupElements, 2);
-    /// stwo_prover::relation!(AdditionalLookupElements, 5);
+    /// crate::components::relation!(LookupElements, 2);
+    /// crate::components::relation!(AdditionalLookupElements, 5);
     ///
     /// fn draw_lookup_elements(all_elements: &mut AllLookupElements, channel: &mut impl Channel) {
     ///     all_elements.insert(LookupElements::draw(channel));
@@ -73,6 +73,19 @@ pub trait MachineChip {
     /// }
     /// ```
     fn draw_lookup_elements(_: &mut AllLookupElements, _: &mut impl Channel) {}
+
+    /// Calls `visitor` on every chip in order, tuples of chips are flattened.
+    fn visit_chips<V: ChipVisitor>(visitor: &mut V)
+    where
+        Self: Sized,
+    {
+        visitor.visit::<Self>();
+    }
+}
+
+/// Visitor over individual chips of a component, see [`MachineChip::visit_chips`].
+pub trait ChipVisitor {
+    fn visit<C: MachineChip>(&mut self);
 }
 
 #[impl_for_tuples(1, 26)]
@@ -107,6 +120,10 @@ impl MachineChip for Tuple {
     fn draw_lookup_elements(all_elements: &mut AllLookupElements, channel: &mut impl Channel) {
         for_tuples!( #( Tuple::draw_lookup_elements(all_elements, channel); )* );
     }
+
+    fn visit_chips<V: ChipVisitor>(visitor: &mut V) {
+        for_tuples!( #( Tuple::visit_chips(visitor); )* );
+    }
 }
 
 pub fn generate_interaction_trace<C: MachineChip>(
@@ -47,6 +47,9 @@ SECTIONS
     *(.debug*)
   }
 
+  /* Types of the guest program I/O, read by the host but not loaded into memory */
+  .note.nexus-io-schema (INFO) : { KEEP(*(.note.nexus-io-schema)) }
+
   /* Stack unwinding is not supported, but we will keep these for now */
   .eh_frame (INFO) : { KEEP(*(.eh_frame)) }
   .eh_frame_hdr (INFO) : { *(.eh_frame_hdr) }
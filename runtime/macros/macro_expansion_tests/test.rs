@@ -92,7 +92,9 @@ mod test {
             "private-input",
             "public-input",
             "public-output",
+            "public-output-named",
             "combination",
+            "interface",
         ];
         let tmp_dir = &create_tmp_dir();
         let tmp_project_path = tmp_dir.path().join("expansion");
@@ -34,6 +34,18 @@ fn moo() {
         output_handler(&out).expect("Failed to write output");
     }
 }
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Probe::<u32>::SCHEMA;
+    const HEADER: &[u8] = b"public_input\x00u32\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
 fn hello() -> u32 {
     let (x): (u32) = nexus_rt::read_public_input::<(u32)>()
         .expect("Failed to read public input");
@@ -46,6 +58,18 @@ fn hello() -> u32 {
         }
     }
 }
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Probe::<u32>::SCHEMA;
+    const HEADER: &[u8] = b"public_output\x00u32\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
 const _: fn() = main;
 #[no_mangle]
 #[allow(unused)]
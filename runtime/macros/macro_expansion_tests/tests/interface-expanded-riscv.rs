@@ -0,0 +1,31 @@
+#![feature(prelude_import)]
+#![no_std]
+#![no_main]
+#[prelude_import]
+use core::prelude::rust_2024::*;
+#[macro_use]
+extern crate core;
+extern crate compiler_builtins as _;
+use nexus_rt::Interface;
+pub struct Multiply;
+impl Interface for Multiply {
+    type PrivateInput = u32;
+    type PublicInput = u32;
+    type PublicOutput = u32;
+}
+fn __nexus_interface_main(x: u32, y: u32) -> u32 {
+    x * y
+}
+const _: fn() = main;
+#[no_mangle]
+#[allow(unused)]
+fn main() {
+    let out: <Multiply as nexus_rt::Interface>::PublicOutput = __nexus_interface_main(
+        nexus_rt::interface::read_private_input::<Multiply>()
+            .expect("Failed to read private input"),
+        nexus_rt::interface::read_public_input::<Multiply>()
+            .expect("Failed to read public input"),
+    );
+    nexus_rt::interface::write_public_output::<Multiply>(&out)
+        .expect("Failed to write output");
+}
@@ -0,0 +1,29 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+use nexus_rt::Interface;
+
+pub struct Multiply;
+
+impl Interface for Multiply {
+    type PrivateInput = u32;
+    type PublicInput = u32;
+    type PublicOutput = u32;
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+fn input_handler()-> (u32,u32) {
+    (1, 2)
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+fn output_handler(result:u32) {
+    println!("Output: {}", result);
+}
+
+#[cfg_attr(target_arch = "riscv32", nexus_rt::main(interface = Multiply))]
+#[cfg_attr(not(target_arch = "riscv32"), nexus_rt::main)]
+#[cfg_attr(not(target_arch = "riscv32"), nexus_rt::custom_input((x,y),input_handler))]
+#[cfg_attr(not(target_arch = "riscv32"), nexus_rt::custom_output(output_handler))]
+fn main(x: u32, y:u32) -> u32{
+    x * y
+}
\ No newline at end of file
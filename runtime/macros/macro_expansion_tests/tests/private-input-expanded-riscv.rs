@@ -6,6 +6,42 @@ use core::prelude::rust_2024::*;
 #[macro_use]
 extern crate core;
 extern crate compiler_builtins as _;
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Probe::<u32>::SCHEMA;
+    const HEADER: &[u8] = b"private_input\x00u32\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Probe::<u32>::SCHEMA;
+    const HEADER: &[u8] = b"private_input\x00u32\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Probe::<u32>::SCHEMA;
+    const HEADER: &[u8] = b"public_output\x00u32\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
 const _: fn() = main;
 #[no_mangle]
 #[allow(unused)]
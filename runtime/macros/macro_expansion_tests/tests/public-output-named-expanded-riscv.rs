@@ -0,0 +1,44 @@
+#![feature(prelude_import)]
+#![no_std]
+#![no_main]
+#[prelude_import]
+use core::prelude::rust_2024::*;
+#[macro_use]
+extern crate core;
+extern crate compiler_builtins as _;
+fn foo(x: u32, y: u32) {
+    let out = (|| { { (x + y, x * y) } })();
+    nexus_rt::write_public_output_named::<u32>("sum", &out.0)
+        .expect("Failed to write output");
+    nexus_rt::write_public_output_named::<u32>("product", &out.1)
+        .expect("Failed to write output");
+}
+const _: () = {
+    #[allow(unused_imports)]
+    use nexus_rt::schema::OpaqueSchema as _;
+    const SCHEMA: nexus_rt::schema::Encoded = nexus_rt::schema::Encoded::tuple(
+        &[
+            nexus_rt::schema::Probe::<u32>::SCHEMA,
+            nexus_rt::schema::Probe::<u32>::SCHEMA,
+        ],
+    );
+    const HEADER: &[u8] = b"private_input\x00(u32, u32)\x00";
+    #[used]
+    #[link_section = ".note.nexus-io-schema"]
+    static IO_SCHEMA: [u8; nexus_rt::schema::entry_len(HEADER, &SCHEMA)] = nexus_rt::schema::entry(
+        HEADER,
+        &SCHEMA,
+    );
+};
+const _: fn() = main;
+#[no_mangle]
+#[allow(unused)]
+fn main() {
+    let (x, y): (u32, u32) = nexus_rt::read_private_input::<(u32, u32)>()
+        .expect("Failed to read public input");
+    {
+        {
+            foo(x, y);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, ItemFn, Token, Type};
+
+use crate::get_nexus_rt_ident;
+
+/// The arguments of `#[nexus_rt::main(interface = Type)]`.
+pub(crate) struct InterfaceArgs {
+    interface: Type,
+}
+
+impl Parse for InterfaceArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        if key != "interface" {
+            return Err(Error::new_spanned(
+                key,
+                "Invalid attribute arguments. Ex: To use the I/O signature of `Foo` write `#[nexus_rt::main(interface = Foo)]`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let interface = input.parse()?;
+        // Allow a trailing comma.
+        let _ = input.parse::<Option<Token![,]>>()?;
+        Ok(Self { interface })
+    }
+}
+
+/// Generate the entry point of a guest program whose I/O is described by an `Interface`.
+///
+/// The main function takes the private input and the public input of the interface, in that order, and
+/// returns its public output. Trailing arguments can be omitted if the corresponding input is `()`.
+pub(crate) fn main(args: InterfaceArgs, input: TokenStream) -> Result<TokenStream, Error> {
+    let InterfaceArgs { interface } = args;
+    let ItemFn {
+        attrs,
+        vis,
+        mut sig,
+        block,
+    } = syn::parse2::<ItemFn>(input)?;
+
+    if sig.ident != "main" {
+        return Err(Error::new_spanned(
+            &sig.ident,
+            "function name must be `main`",
+        ));
+    }
+    if sig.inputs.len() > 2 {
+        return Err(Error::new_spanned(
+            &sig.inputs,
+            "`main` function takes at most two arguments, the private and public inputs of the interface",
+        ));
+    }
+
+    // The user function is kept as is under another name, so that it is type checked against the interface
+    // at the call site below.
+    let inner = format_ident!("__nexus_interface_main");
+    sig.ident = inner.clone();
+
+    let nexus_rt = get_nexus_rt_ident();
+    let iface = quote! { <#interface as #nexus_rt::Interface> };
+    let private_input = quote! {
+        #nexus_rt::interface::read_private_input::<#interface>().expect("Failed to read private input")
+    };
+    let public_input = quote! {
+        #nexus_rt::interface::read_public_input::<#interface>().expect("Failed to read public input")
+    };
+    let (args, unit_checks) = match sig.inputs.len() {
+        0 => (
+            quote! {},
+            quote! {
+                let _: #iface::PrivateInput = ();
+                let _: #iface::PublicInput = ();
+            },
+        ),
+        1 => (
+            quote! { #private_input },
+            quote! {
+                let _: #iface::PublicInput = ();
+            },
+        ),
+        _ => (quote! { #private_input, #public_input }, quote! {}),
+    };
+
+    Ok(quote! {
+        #[cfg(not(target_arch = "riscv32"))]
+        compile_error!("NexusVM interfaces are not available for native builds, use custom input and output handlers instead.");
+
+        #(#attrs)*
+        #vis #sig #block
+
+        #[#nexus_rt::main]
+        fn main() {
+            #unit_checks
+            let out: #iface::PublicOutput = #inner(#args);
+            #nexus_rt::interface::write_public_output::<#interface>(&out).expect("Failed to write output");
+        }
+    })
+}
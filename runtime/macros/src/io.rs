@@ -24,6 +24,49 @@ pub(crate) enum OutputType {
     Public,
 }
 
+// Record the type of an input or output in the I/O schema section of the ELF file, see
+// `nexus_common::schema`. Each entry is the kind of I/O and the type name, both NUL-terminated,
+// followed by the schema of the type.
+fn io_schema_entry(kind: &str, types: &[Type]) -> TokenStream2 {
+    let nexus_rt = get_nexus_rt_ident();
+
+    // Inputs are read as a tuple unless there is a single one. Each type is probed on its own, so
+    // that a type without a schema does not hide the schemas of the others.
+    let (name, schema) = match types {
+        [ty] => (
+            ty.to_token_stream().to_string(),
+            quote! { #nexus_rt::schema::Probe::<#ty>::SCHEMA },
+        ),
+        types => (
+            format!(
+                "({})",
+                types
+                    .iter()
+                    .map(|ty| ty.to_token_stream().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            quote! {
+                #nexus_rt::schema::Encoded::tuple(&[#(#nexus_rt::schema::Probe::<#types>::SCHEMA),*])
+            },
+        ),
+    };
+    let header = proc_macro2::Literal::byte_string(format!("{kind}\0{name}\0").as_bytes());
+
+    quote! {
+        const _: () = {
+            #[allow(unused_imports)]
+            use #nexus_rt::schema::OpaqueSchema as _;
+            const SCHEMA: #nexus_rt::schema::Encoded = #schema;
+            const HEADER: &[u8] = #header;
+            #[used]
+            #[link_section = ".note.nexus-io-schema"]
+            static IO_SCHEMA: [u8; #nexus_rt::schema::entry_len(HEADER, &SCHEMA)] =
+                #nexus_rt::schema::entry(HEADER, &SCHEMA);
+        };
+    }
+}
+
 pub(crate) fn handle_output(
     args: TokenStream,
     item: TokenStream,
@@ -41,8 +84,8 @@ pub(crate) fn handle_output(
         );
     };
 
-    // Parse the function name for custom output.
-    let custom_fn_name: Option<Ident> = match output_type {
+    // Parse the function name for custom output, or the segment names for public output.
+    let (custom_fn_name, names): (Option<Ident>, Vec<String>) = match output_type {
         OutputType::Custom => {
             let arg_num_error_msg = "Invalid attribute arguments. Ex: To specify that output should be handled by `foo` write `#[nexus_rt::custom_output(foo)]`";
             // This error checking allows us to safely unwrap below.
@@ -54,16 +97,31 @@ pub(crate) fn handle_output(
                 if path.path.segments.len() != 1 {
                     return stream_error(path, arg_num_error_msg);
                 }
-                Some(path.path.segments.get(0).unwrap().ident.clone())
+                (
+                    Some(path.path.segments.get(0).unwrap().ident.clone()),
+                    Vec::new(),
+                )
             } else {
                 return stream_error(&attr_args, arg_num_error_msg);
             }
         }
         OutputType::Public => {
-            if !attr_args.is_empty() {
-                return stream_error(&attr_args, "Invalid attribute arguments. `nexus_rt::public_output` does not take any arguments.");
+            let invalid_name_err = "Expected an identifier. Ex: To write the outputs of a function returning a pair as the segments `sum` and `product` write `#[nexus_rt::public_output(sum, product)]`";
+            let mut names: Vec<String> = Vec::new();
+            for arg in attr_args.iter() {
+                let Path(path) = arg else {
+                    return stream_error(arg, invalid_name_err);
+                };
+                if path.path.segments.len() != 1 {
+                    return stream_error(path, invalid_name_err);
+                }
+                let name = path.path.segments.get(0).unwrap().ident.to_string();
+                if names.contains(&name) {
+                    return stream_error(arg, format!("Duplicate public output: {:?}.", name));
+                }
+                names.push(name);
             }
-            None
+            (None, names)
         }
     };
 
@@ -80,14 +138,40 @@ pub(crate) fn handle_output(
         }
     };
 
-    // Generate the output handler name.
+    // Generate the output handler calls.
     let nexus_rt = get_nexus_rt_ident();
-    let output_fn_full = match output_type {
-        OutputType::Public => quote! {
-            #nexus_rt::write_public_output::<#output>
+    let write_output = match output_type {
+        OutputType::Public if names.is_empty() => quote! {
+            #nexus_rt::write_public_output::<#output>(&out).expect("Failed to write output");
         },
+        // A single name applies to the whole output.
+        OutputType::Public if names.len() == 1 => {
+            let name = &names[0];
+            quote! {
+                #nexus_rt::write_public_output_named::<#output>(#name, &out).expect("Failed to write output");
+            }
+        }
+        // Otherwise each element of the returned tuple is written as its own segment.
+        OutputType::Public => {
+            let types = match &output {
+                Type::Tuple(tuple) if tuple.elems.len() == names.len() => tuple.elems.iter(),
+                _ => {
+                    return stream_error(
+                        &output,
+                        format!(
+                            "Expected a tuple of {} elements, one for each public output.",
+                            names.len()
+                        ),
+                    )
+                }
+            };
+            let indices = (0..names.len()).map(syn::Index::from);
+            quote! {
+                #(#nexus_rt::write_public_output_named::<#types>(#names, &out.#indices).expect("Failed to write output");)*
+            }
+        }
         OutputType::Custom => quote! {
-            #custom_fn_name
+            #custom_fn_name(&out).expect("Failed to write output");
         },
     };
 
@@ -101,15 +185,24 @@ pub(crate) fn handle_output(
         quote! {}
     };
 
+    // Named segments are decoded individually by the host, so only the type of an unnamed output is recorded.
+    let schema = match output_type {
+        OutputType::Public if names.is_empty() => {
+            io_schema_entry("public_output", std::slice::from_ref(&output))
+        }
+        _ => quote! {},
+    };
+
     // Build the output token stream
     let expanded = quote! {
         #target_check
+        #schema
         #(#attrs)*
         fn #fn_name(#inputs) {
             let out = (|| {
                 #block
             })();
-            #output_fn_full(&out).expect("Failed to write output");
+            #write_output
         }
     };
 
@@ -270,9 +363,16 @@ pub(crate) fn handle_input(
         quote! {}
     };
 
+    let schema = match input_type {
+        InputType::Public => io_schema_entry("public_input", &types),
+        InputType::Private => io_schema_entry("private_input", &types),
+        InputType::Custom => quote! {},
+    };
+
     // Build the output token stream
     let expanded = quote! {
         #target_check
+        #schema
         #(#attrs)*
         fn #fn_name(#input_sig) #output {
             let (#(#inputs),*):(#(#types),*) = #input_handler().expect("Failed to read public input");
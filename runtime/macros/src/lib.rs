@@ -5,11 +5,13 @@ use std::fmt::Display;
 extern crate proc_macro;
 use crate::io::{handle_input, handle_output, InputType, OutputType};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Error, ItemFn};
+use syn::{parse_macro_input, DeriveInput, Error, ItemFn};
 
 mod entry;
+mod interface;
 mod io;
 mod profile;
+mod schema;
 
 // Get the full path to the nexus_rt crate.
 pub(crate) fn get_nexus_rt_ident() -> Ident {
@@ -29,6 +31,15 @@ fn stream_error<T: ToTokens, U: Display>(tokens: T, message: U) -> TokenStream {
 #[proc_macro_attribute]
 pub fn main(args: TokenStream, input1: TokenStream) -> TokenStream {
     let input: proc_macro2::TokenStream = input1.clone().into();
+
+    // If an interface is given, the inputs and output are handled according to it.
+    if !args.is_empty() {
+        return syn::parse::<interface::InterfaceArgs>(args)
+            .and_then(|args| interface::main(args, input))
+            .map(Into::into)
+            .unwrap_or_else(|err| err.into_compile_error().into());
+    }
+
     let ItemFn {
         attrs, sig, block, ..
     } = syn::parse2::<syn::ItemFn>(input.clone()).unwrap();
@@ -128,3 +139,10 @@ pub fn custom_output(_attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn profile(_args: TokenStream, input: TokenStream) -> TokenStream {
     profile::profile(input.into()).into()
 }
+
+#[proc_macro_derive(Schema)]
+pub fn derive_schema(input: TokenStream) -> TokenStream {
+    schema::derive(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
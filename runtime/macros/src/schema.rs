@@ -0,0 +1,48 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Result};
+
+use crate::get_nexus_rt_ident;
+
+// Derive `nexus_rt::Schema`, encoding structs and enum variants as the tuples of their fields.
+pub(crate) fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let nexus_rt = get_nexus_rt_ident();
+    let encoded = quote! { #nexus_rt::schema::Encoded };
+
+    let fields_schema = |fields: &Fields| {
+        let types = fields.iter().map(|field| &field.ty);
+        quote! {
+            #encoded::tuple(&[#(<#types as #nexus_rt::schema::Schema>::SCHEMA),*])
+        }
+    };
+
+    let schema = match &input.data {
+        Data::Struct(data) => fields_schema(&data.fields),
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| fields_schema(&variant.fields));
+            quote! { #encoded::enumeration(&[#(#variants),*]) }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span,
+                "`Schema` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#nexus_rt::schema::Schema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let name = &input.ident;
+
+    Ok(quote! {
+        impl #impl_generics #nexus_rt::schema::Schema for #name #ty_generics #where_clause {
+            const SCHEMA: #encoded = #schema;
+        }
+    })
+}
@@ -0,0 +1,353 @@
+// Copyright 2024 Nexus Laboratories, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! First-fit free-list allocator, enabled by the `free-list` feature.
+//!
+//! Freed blocks are kept in a singly linked list sorted by address, and adjacent blocks are
+//! merged on release. Allocations are served from the first free block that fits, otherwise the
+//! heap is grown through `sys_alloc_aligned`, which keeps the heap/stack collision check.
+//!
+//! Sizes and alignments are rounded up to `BLOCK_ALIGN`, so that whatever remains of a split
+//! block is always large enough to hold a list node.
+//!
+//! The list itself, [`FreeList`], does not depend on the target and is tested on the host over a
+//! byte arena.
+
+use core::alloc::Layout;
+use core::ptr;
+
+// A free block, stored in the freed memory itself.
+struct Block {
+    size: usize,
+    next: *mut Block,
+}
+
+const BLOCK_ALIGN: usize = core::mem::size_of::<Block>();
+
+// Size and alignment of the block backing an allocation.
+fn block_layout(layout: Layout) -> (usize, usize) {
+    let size = layout.size().max(1).next_multiple_of(BLOCK_ALIGN);
+    let align = layout.align().max(BLOCK_ALIGN);
+    (size, align)
+}
+
+/// Free blocks sorted by address.
+///
+/// Every block starts at a multiple of `BLOCK_ALIGN` and its size is a multiple of `BLOCK_ALIGN`.
+pub(crate) struct FreeList {
+    head: *mut Block,
+}
+
+impl FreeList {
+    pub(crate) const fn new() -> Self {
+        Self {
+            head: ptr::null_mut(),
+        }
+    }
+
+    /// Removes `size` bytes aligned to `align` from the first block that fits, returns null if
+    /// there is none. The parts of the block before and after the allocation stay in the list.
+    ///
+    /// # Safety
+    ///
+    /// `size` and `align` must be multiples of `BLOCK_ALIGN`, and `align` a power of two.
+    pub(crate) unsafe fn take(&mut self, size: usize, align: usize) -> *mut u8 {
+        // SAFETY: Every node in the list is a free block written by `release` or split off below.
+        let mut link: *mut *mut Block = ptr::addr_of_mut!(self.head);
+        while !(*link).is_null() {
+            let block = *link;
+            let start = block as usize;
+            let end = start + (*block).size;
+            let aligned = start.next_multiple_of(align);
+
+            if aligned + size <= end {
+                let mut next = (*block).next;
+
+                // Return the part after the allocation to the list.
+                let alloc_end = aligned + size;
+                if alloc_end < end {
+                    let tail = alloc_end as *mut Block;
+                    tail.write(Block {
+                        size: end - alloc_end,
+                        next,
+                    });
+                    next = tail;
+                }
+
+                // The part before the allocation, if any, keeps the block's place in the list.
+                if aligned > start {
+                    (*block).size = aligned - start;
+                    (*block).next = next;
+                } else {
+                    *link = next;
+                }
+
+                return aligned as *mut u8;
+            }
+            link = ptr::addr_of_mut!((*block).next);
+        }
+
+        ptr::null_mut()
+    }
+
+    /// Adds `size` bytes at `ptr` to the list, merging them with adjacent free blocks.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be aligned to `BLOCK_ALIGN` and `size` a multiple of it. The memory must be
+    /// writable, and must not overlap any block in the list.
+    pub(crate) unsafe fn release(&mut self, ptr: *mut u8, size: usize) {
+        let start = ptr as usize;
+
+        // Find the neighbours of the block, keeping the list sorted by address.
+        let mut prev: *mut Block = ptr::null_mut();
+        let mut next = self.head;
+        while !next.is_null() && (next as usize) < start {
+            prev = next;
+            next = (*next).next;
+        }
+
+        let block = ptr as *mut Block;
+        block.write(Block { size, next });
+
+        // Merge with the following block.
+        if !next.is_null() && start + size == next as usize {
+            (*block).size += (*next).size;
+            (*block).next = (*next).next;
+        }
+
+        // Merge with the preceding block, or link the block after it.
+        if prev.is_null() {
+            self.head = block;
+        } else if prev as usize + (*prev).size == start {
+            (*prev).size += (*block).size;
+            (*prev).next = (*block).next;
+        } else {
+            (*prev).next = block;
+        }
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+mod riscv32 {
+    use super::{block_layout, FreeList};
+    use crate::heap::sys_alloc_aligned;
+    use core::alloc::Layout;
+
+    static mut FREE_LIST: FreeList = FreeList::new();
+
+    fn free_list() -> &'static mut FreeList {
+        // SAFETY: Single threaded, and references never outlive the calls below.
+        unsafe { &mut *core::ptr::addr_of_mut!(FREE_LIST) }
+    }
+
+    pub(crate) unsafe fn alloc(layout: Layout) -> *mut u8 {
+        let (size, align) = block_layout(layout);
+
+        let ptr = free_list().take(size, align);
+        if ptr.is_null() {
+            return sys_alloc_aligned(size, align);
+        }
+
+        #[cfg(feature = "alloc-stats")]
+        crate::heap::stats::record_alloc(size, 0);
+        ptr
+    }
+
+    pub(crate) unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+        let (size, _) = block_layout(layout);
+        free_list().release(ptr, size);
+
+        #[cfg(feature = "alloc-stats")]
+        crate::heap::stats::record_dealloc(size);
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+pub(crate) use riscv32::{alloc, dealloc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARENA_SIZE: usize = 1024;
+
+    #[repr(C, align(1024))]
+    struct Arena([u8; ARENA_SIZE]);
+
+    impl Arena {
+        fn new() -> Box<Self> {
+            Box::new(Self([0; ARENA_SIZE]))
+        }
+
+        fn at(&mut self, offset: usize) -> *mut u8 {
+            self.0[offset..].as_mut_ptr()
+        }
+    }
+
+    // Free blocks as (offset from the arena, size) pairs, in list order.
+    fn blocks(list: &FreeList, arena: &mut Arena) -> Vec<(usize, usize)> {
+        let base = arena.at(0) as usize;
+        let mut blocks = Vec::new();
+        let mut block = list.head;
+        while !block.is_null() {
+            unsafe {
+                blocks.push((block as usize - base, (*block).size));
+                block = (*block).next;
+            }
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_split() {
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(0), 8 * BLOCK_ALIGN);
+
+            let first = list.take(2 * BLOCK_ALIGN, BLOCK_ALIGN);
+            assert_eq!(first, arena.at(0));
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [(2 * BLOCK_ALIGN, 6 * BLOCK_ALIGN)]
+            );
+
+            let second = list.take(BLOCK_ALIGN, BLOCK_ALIGN);
+            assert_eq!(second, arena.at(2 * BLOCK_ALIGN));
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [(3 * BLOCK_ALIGN, 5 * BLOCK_ALIGN)]
+            );
+
+            // An exact fit removes the block from the list.
+            let third = list.take(5 * BLOCK_ALIGN, BLOCK_ALIGN);
+            assert_eq!(third, arena.at(3 * BLOCK_ALIGN));
+            assert!(blocks(&list, &mut arena).is_empty());
+            assert!(list.take(BLOCK_ALIGN, BLOCK_ALIGN).is_null());
+        }
+    }
+
+    #[test]
+    fn test_first_fit() {
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(0), BLOCK_ALIGN);
+            list.release(arena.at(2 * BLOCK_ALIGN), 4 * BLOCK_ALIGN);
+            list.release(arena.at(8 * BLOCK_ALIGN), 2 * BLOCK_ALIGN);
+
+            // The first block is too small, the second one is used even though the third fits exactly.
+            let ptr = list.take(2 * BLOCK_ALIGN, BLOCK_ALIGN);
+            assert_eq!(ptr, arena.at(2 * BLOCK_ALIGN));
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [
+                    (0, BLOCK_ALIGN),
+                    (4 * BLOCK_ALIGN, 2 * BLOCK_ALIGN),
+                    (8 * BLOCK_ALIGN, 2 * BLOCK_ALIGN)
+                ]
+            );
+            assert!(list.take(4 * BLOCK_ALIGN, BLOCK_ALIGN).is_null());
+        }
+    }
+
+    #[test]
+    fn test_merge_with_previous() {
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(0), 2 * BLOCK_ALIGN);
+            list.release(arena.at(2 * BLOCK_ALIGN), BLOCK_ALIGN);
+            assert_eq!(blocks(&list, &mut arena), [(0, 3 * BLOCK_ALIGN)]);
+        }
+    }
+
+    #[test]
+    fn test_merge_with_next() {
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(2 * BLOCK_ALIGN), 2 * BLOCK_ALIGN);
+            list.release(arena.at(BLOCK_ALIGN), BLOCK_ALIGN);
+            assert_eq!(blocks(&list, &mut arena), [(BLOCK_ALIGN, 3 * BLOCK_ALIGN)]);
+        }
+    }
+
+    #[test]
+    fn test_merge_with_both() {
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(0), BLOCK_ALIGN);
+            list.release(arena.at(2 * BLOCK_ALIGN), BLOCK_ALIGN);
+            list.release(arena.at(6 * BLOCK_ALIGN), BLOCK_ALIGN);
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [
+                    (0, BLOCK_ALIGN),
+                    (2 * BLOCK_ALIGN, BLOCK_ALIGN),
+                    (6 * BLOCK_ALIGN, BLOCK_ALIGN)
+                ]
+            );
+
+            list.release(arena.at(BLOCK_ALIGN), BLOCK_ALIGN);
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [(0, 3 * BLOCK_ALIGN), (6 * BLOCK_ALIGN, BLOCK_ALIGN)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_alignment() {
+        let align = 8 * BLOCK_ALIGN;
+        assert!(align > 8);
+
+        let mut arena = Arena::new();
+        let mut list = FreeList::new();
+        unsafe {
+            list.release(arena.at(BLOCK_ALIGN), 31 * BLOCK_ALIGN);
+
+            // The block starts right after the arena start, so the allocation is placed at the
+            // next multiple of the alignment and the block is split in three.
+            let ptr = list.take(2 * BLOCK_ALIGN, align);
+            assert_eq!(ptr, arena.at(align));
+            assert_eq!(ptr as usize % align, 0);
+            assert_eq!(
+                blocks(&list, &mut arena),
+                [
+                    (BLOCK_ALIGN, align - BLOCK_ALIGN),
+                    (align + 2 * BLOCK_ALIGN, 30 * BLOCK_ALIGN - align)
+                ]
+            );
+
+            // Releasing the allocation merges all three parts back.
+            list.release(ptr, 2 * BLOCK_ALIGN);
+            assert_eq!(blocks(&list, &mut arena), [(BLOCK_ALIGN, 31 * BLOCK_ALIGN)]);
+        }
+    }
+
+    #[test]
+    fn test_block_layout() {
+        let layout = Layout::from_size_align(1, 1).unwrap();
+        assert_eq!(block_layout(layout), (BLOCK_ALIGN, BLOCK_ALIGN));
+
+        let layout = Layout::from_size_align(BLOCK_ALIGN + 1, 4 * BLOCK_ALIGN).unwrap();
+        assert_eq!(block_layout(layout), (2 * BLOCK_ALIGN, 4 * BLOCK_ALIGN));
+
+        let layout = Layout::from_size_align(0, 1).unwrap();
+        assert_eq!(block_layout(layout), (BLOCK_ALIGN, BLOCK_ALIGN));
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2023 RISC Zero, Inc.
+// Copyright 2024 Nexus Laboratories, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{ecall, SYS_ALLOC_ALIGNED};
+
+// Minimum gap between heap and stack
+const MEMORY_GAP: usize = 0x1000;
+
+// Pointer to next heap address to use, or 0 if the heap has not yet been
+// initialized. Exported so that the VM can size the heap to cover all
+// allocations, including memory that is allocated but never touched.
+#[no_mangle]
+static mut __heap_pos: usize = 0;
+
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8 {
+    extern "C" {
+        // https://lld.llvm.org/ELF/linker_script.html#sections-command
+        static _end: u8;
+    }
+
+    // SAFETY: Single threaded, so nothing else can touch this while we're working.
+    let mut heap_pos = __heap_pos;
+
+    if heap_pos == 0 {
+        // Check to see if vm has a different place we should expect the heap to be.
+        let overwrite = ecall!(SYS_ALLOC_ALIGNED);
+        if overwrite > 0 {
+            heap_pos = overwrite as usize;
+        } else {
+            heap_pos = &_end as *const u8 as usize;
+        }
+        #[cfg(feature = "alloc-stats")]
+        stats::record_heap_start(heap_pos);
+    }
+
+    let offset = heap_pos & (align - 1);
+    if offset != 0 {
+        heap_pos = heap_pos
+            .checked_add(align - offset)
+            .expect("Heap calculation has overflowed");
+    }
+
+    let ptr = heap_pos as *mut u8;
+    heap_pos = heap_pos
+        .checked_add(bytes)
+        .expect("Heap calculation has overflowed");
+
+    // Get the current stack pointer
+    let stack_ptr: usize;
+    unsafe {
+        core::arch::asm!(
+            "mv {}, sp",
+            out(reg) stack_ptr
+        );
+    }
+
+    // Check if the heap is about to clash with the stack
+    let gap_check = heap_pos
+        .checked_add(MEMORY_GAP)
+        .expect("Heap calculation has overflowed");
+    if gap_check > stack_ptr {
+        #[cfg(feature = "alloc-stats")]
+        stats::record_overflow(heap_pos);
+        panic!(
+            "Heap clashing with stack (heap: 0x{:x}, stack: 0x{:x})",
+            heap_pos, stack_ptr
+        );
+    }
+
+    __heap_pos = heap_pos;
+    #[cfg(feature = "alloc-stats")]
+    stats::record_alloc(bytes, heap_pos);
+    ptr
+}
+
+/// Report the allocator statistics to the host, a no-op without the `alloc-stats` feature.
+pub(crate) fn report_stats() {
+    #[cfg(feature = "alloc-stats")]
+    stats::report();
+}
+
+#[cfg(feature = "alloc-stats")]
+pub(crate) mod stats {
+    use crate::{ecall, ALLOC_STATS_FD, SYS_LOG};
+
+    const NUM_LARGEST: usize = 4;
+
+    // The layout must match the encoding expected by `nexus_vm::emulator::AllocStats`. Some
+    // fields are only read by the host, through the raw bytes sent in `report`.
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct AllocStats {
+        allocations: u32,
+        deallocations: u32,
+        allocated_bytes: u32,
+        live_bytes: u32,
+        heap_start: u32,
+        heap_end: u32,
+        heap_overflow: u32,
+        largest_allocations: [u32; NUM_LARGEST],
+    }
+
+    static mut STATS: AllocStats = AllocStats {
+        allocations: 0,
+        deallocations: 0,
+        allocated_bytes: 0,
+        live_bytes: 0,
+        heap_start: 0,
+        heap_end: 0,
+        heap_overflow: 0,
+        largest_allocations: [0; NUM_LARGEST],
+    };
+
+    fn stats() -> &'static mut AllocStats {
+        // SAFETY: Single threaded, and references never outlive the calls below.
+        unsafe { &mut *core::ptr::addr_of_mut!(STATS) }
+    }
+
+    pub(crate) fn record_heap_start(heap_pos: usize) {
+        let stats = stats();
+        stats.heap_start = heap_pos as u32;
+        stats.heap_end = heap_pos as u32;
+    }
+
+    pub(crate) fn record_alloc(bytes: usize, heap_pos: usize) {
+        let stats = stats();
+        let bytes = bytes as u32;
+        stats.allocations = stats.allocations.wrapping_add(1);
+        stats.allocated_bytes = stats.allocated_bytes.wrapping_add(bytes);
+        stats.live_bytes = stats.live_bytes.wrapping_add(bytes);
+        stats.heap_end = stats.heap_end.max(heap_pos as u32);
+
+        // Keep the largest allocations sorted in decreasing order.
+        let largest = &mut stats.largest_allocations;
+        if let Some(idx) = largest.iter().position(|&size| bytes > size) {
+            largest.copy_within(idx..NUM_LARGEST - 1, idx + 1);
+            largest[idx] = bytes;
+        }
+    }
+
+    pub(crate) fn record_dealloc(bytes: usize) {
+        let stats = stats();
+        stats.deallocations = stats.deallocations.wrapping_add(1);
+        stats.live_bytes = stats.live_bytes.wrapping_sub(bytes as u32);
+    }
+
+    pub(crate) fn record_overflow(heap_pos: usize) {
+        let stats = stats();
+        stats.heap_overflow = 1;
+        stats.heap_end = stats.heap_end.max(heap_pos as u32);
+    }
+
+    pub(crate) fn report() {
+        let buf_ptr = core::ptr::addr_of!(STATS) as *const u8;
+        let buf_len = core::mem::size_of::<AllocStats>();
+        let _ = ecall!(SYS_LOG, ALLOC_STATS_FD, ("a1", buf_ptr), ("a2", buf_len));
+    }
+}
@@ -0,0 +1,36 @@
+pub use nexus_common::interface::Interface;
+
+#[cfg(target_arch = "riscv32")]
+mod riscv32 {
+    use super::Interface;
+    use serde::de::DeserializeOwned;
+
+    // The host does not write anything for zero-sized inputs, such as `()`, so decode them from no bytes.
+    fn read_or_empty<T: DeserializeOwned>(
+        read: fn() -> Result<T, postcard::Error>,
+    ) -> Result<T, postcard::Error> {
+        if core::mem::size_of::<T>() == 0 {
+            postcard::from_bytes(&[])
+        } else {
+            read()
+        }
+    }
+
+    /// Read the private input of the interface `I`.
+    pub fn read_private_input<I: Interface>() -> Result<I::PrivateInput, postcard::Error> {
+        read_or_empty(crate::read_private_input::<I::PrivateInput>)
+    }
+
+    /// Read the public input of the interface `I`.
+    pub fn read_public_input<I: Interface>() -> Result<I::PublicInput, postcard::Error> {
+        read_or_empty(crate::read_public_input::<I::PublicInput>)
+    }
+
+    /// Write the public output of the interface `I`.
+    pub fn write_public_output<I: Interface>(val: &I::PublicOutput) -> Result<(), postcard::Error> {
+        crate::write_public_output::<I::PublicOutput>(val)
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+pub use riscv32::*;
@@ -5,17 +5,16 @@ extern crate alloc;
 mod riscv32 {
     extern crate alloc;
     use crate::{
-        ecall, read_input, write_output, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
+        ecall, read_input, write_output, STDOUT_FD, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
         SYS_READ_PRIVATE_INPUT, WORD_SIZE,
     };
     use serde::{de::DeserializeOwned, Serialize};
 
     /// Write a string to the output console (if any).
     pub fn write_log(s: &str) -> Option<u32> {
-        let fd = 1;
         let buf_ptr = s.as_ptr();
         let buf_len = s.len();
-        let out = ecall!(SYS_LOG, fd, ("a1", buf_ptr), ("a2", buf_len));
+        let out = ecall!(SYS_LOG, STDOUT_FD, ("a1", buf_ptr), ("a2", buf_len));
         if out == u32::MAX {
             None
         } else {
@@ -25,6 +24,7 @@ mod riscv32 {
 
     /// Exit the program with the given exit code.
     pub fn exit(exit_code: i32) -> ! {
+        crate::heap::report_stats();
         // Write the exit code to the output.
         let _ = write_output!(0, exit_code);
         // Exit the program.
@@ -38,11 +38,101 @@ mod riscv32 {
     /// Read an object off the private input tape
     ///
     /// exhausts the private input tape, so can only be used once
+    ///
+    /// The tape is always read byte by byte, even with the `bulk-private-input` feature, so that
+    /// programs using only this function remain provable.
     pub fn read_private_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
         let mut bytes: alloc::vec::Vec<u8> = core::iter::from_fn(read_from_private_input).collect();
         postcard::from_bytes_cobs::<T>(bytes.as_mut_slice())
     }
 
+    /// Size of the buffer of [`PrivateInputReader`], in bytes.
+    pub const PRIVATE_INPUT_BUFFER_SIZE: usize = 256;
+
+    /// A buffered reader over the private input tape.
+    ///
+    /// Bytes are pulled from the tape on demand, so large witnesses can be processed in chunks
+    /// instead of being held in memory at once. With the `bulk-private-input` feature, each refill
+    /// copies a whole buffer in a single syscall, otherwise the tape is read byte by byte.
+    pub struct PrivateInputReader {
+        buf: [u8; PRIVATE_INPUT_BUFFER_SIZE],
+        pos: usize,
+        len: usize,
+    }
+
+    impl PrivateInputReader {
+        pub const fn new() -> Self {
+            Self {
+                buf: [0; PRIVATE_INPUT_BUFFER_SIZE],
+                pos: 0,
+                len: 0,
+            }
+        }
+
+        /// Read up to `buf.len()` bytes, returns the number of bytes read, 0 once the tape is
+        /// exhausted.
+        pub fn read(&mut self, buf: &mut [u8]) -> usize {
+            if self.pos == self.len {
+                self.fill();
+            }
+            let len = (self.len - self.pos).min(buf.len());
+            buf[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+            self.pos += len;
+            len
+        }
+
+        /// Fill `buf` entirely, returns `false` if the tape was exhausted first.
+        pub fn read_exact(&mut self, mut buf: &mut [u8]) -> bool {
+            while !buf.is_empty() {
+                match self.read(buf) {
+                    0 => return false,
+                    len => buf = &mut buf[len..],
+                }
+            }
+            true
+        }
+
+        #[cfg(feature = "bulk-private-input")]
+        fn fill(&mut self) {
+            let buf_ptr = self.buf.as_mut_ptr();
+            let len = ecall!(
+                crate::SYS_READ_PRIVATE_INPUT_BULK,
+                buf_ptr,
+                ("a1", PRIVATE_INPUT_BUFFER_SIZE)
+            );
+            self.pos = 0;
+            self.len = len as usize;
+        }
+
+        #[cfg(not(feature = "bulk-private-input"))]
+        fn fill(&mut self) {
+            self.pos = 0;
+            self.len = 0;
+            while self.len < PRIVATE_INPUT_BUFFER_SIZE {
+                match read_from_private_input() {
+                    Some(byte) => self.buf[self.len] = byte,
+                    None => break,
+                }
+                self.len += 1;
+            }
+        }
+    }
+
+    impl Default for PrivateInputReader {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Iterator for PrivateInputReader {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            let mut byte = [0u8];
+            (self.read(&mut byte) == 1).then_some(byte[0])
+        }
+    }
+
     /// Read a byte from the private input tape
     fn read_from_private_input() -> Option<u8> {
         let out = ecall!(SYS_READ_PRIVATE_INPUT);
@@ -72,21 +162,50 @@ mod riscv32 {
         postcard::from_bytes_cobs::<T>(input.as_mut_slice())
     }
 
-    /// Write an object to the public output segment.
+    // Byte offset of the next public output segment, word 0 is reserved for the exit code.
+    static mut OUTPUT_POS: usize = WORD_SIZE;
+
+    /// Append an object to the public output.
+    ///
+    /// Each call appends a new segment, which the host can decode independently by its index.
     pub fn write_public_output<T: Serialize + ?Sized>(val: &T) -> Result<(), postcard::Error> {
-        // Serialize the value into bytes.
-        let mut bytes = postcard::to_allocvec_cobs(&val)?;
+        append_public_output(UNNAMED_SEGMENT, val)
+    }
 
+    /// Append an object to the public output as a segment named `name`.
+    ///
+    /// The host can decode the segment by its name, or by its index.
+    pub fn write_public_output_named<T: Serialize + ?Sized>(
+        name: &str,
+        val: &T,
+    ) -> Result<(), postcard::Error> {
+        append_public_output(NAMED_SEGMENT, &(name, val))
+    }
+
+    // Tags of the public output segments, see `nexus_sdk::Viewable::public_output_segment`.
+    const UNNAMED_SEGMENT: u8 = 0;
+    const NAMED_SEGMENT: u8 = 1;
+
+    // Append a segment, serialized as its tag followed by its contents.
+    fn append_public_output<T: Serialize + ?Sized>(
+        tag: u8,
+        segment: &T,
+    ) -> Result<(), postcard::Error> {
+        let mut bytes = postcard::to_allocvec_cobs(&(tag, segment))?;
         let padded_len = (bytes.len() + 3) & !3;
         assert!(padded_len >= bytes.len());
         bytes.resize(padded_len, 0x00); // cobs ignores 0x00 padding
 
+        // SAFETY: Single threaded, so nothing else can touch this while we're working.
+        let pos = unsafe { OUTPUT_POS };
+
         // Write bytes in word chunks to output memory.
         bytes.chunks(WORD_SIZE).enumerate().for_each(|(i, chunk)| {
             let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-            write_output!((i + 1) * WORD_SIZE, word); // word 0 is reserved for the exit code
+            write_output!(pos + i * WORD_SIZE, word);
         });
 
+        unsafe { OUTPUT_POS = pos + padded_len };
         Ok(())
     }
 
@@ -164,6 +283,13 @@ mod native {
     pub fn write_public_output<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(_val: &T) {
         unimplemented!()
     }
+
+    pub fn write_public_output_named<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(
+        _name: &str,
+        _val: &T,
+    ) {
+        unimplemented!()
+    }
 }
 #[cfg(not(target_arch = "riscv32"))]
 pub use native::*;
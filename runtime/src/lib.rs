@@ -7,8 +7,13 @@ mod runtime;
 #[cfg(target_arch = "riscv32")]
 pub use runtime::*;
 
+extern crate alloc;
+
 #[cfg(target_arch = "riscv32")]
-mod alloc;
+mod heap;
+
+#[cfg(any(test, all(target_arch = "riscv32", feature = "free-list")))]
+mod free_list;
 
 pub use nexus_rt_macros::{
     custom_input, custom_output, main, private_input, profile, public_input, public_output,
@@ -16,6 +21,15 @@ pub use nexus_rt_macros::{
 
 mod io;
 pub use io::*;
+
+pub mod interface;
+pub use interface::Interface;
+
+pub mod schema;
+pub use schema::Schema;
+
+#[cfg(feature = "std-shim")]
+pub mod shim;
 pub use postcard;
 
 // Ecall codes. Allow dead code here because these are only used in the RISC-V runtime, not when
@@ -33,6 +47,8 @@ pub(crate) const SYS_CYCLE_COUNT: u32 = 0x401;
 pub(crate) const SYS_OVERWRITE_SP: u32 = 0x402;
 #[cfg(target_arch = "riscv32")]
 pub(crate) const SYS_ALLOC_ALIGNED: u32 = 0x403;
+#[cfg(all(target_arch = "riscv32", feature = "bulk-private-input"))]
+pub(crate) const SYS_READ_PRIVATE_INPUT_BULK: u32 = 0x405;
 // Error codes.
 #[cfg(target_arch = "riscv32")]
 pub(crate) const EXIT_SUCCESS: u32 = 0;
@@ -41,6 +57,12 @@ pub(crate) const EXIT_PANIC: u32 = 1;
 // Constants.
 #[cfg(target_arch = "riscv32")]
 pub(crate) const WORD_SIZE: usize = 4;
+// File descriptor for the debug log, shared by all text written to the host.
+#[cfg(target_arch = "riscv32")]
+pub(crate) const STDOUT_FD: u32 = 1;
+// File descriptor for the allocator statistics, see `nexus_vm::system::ALLOC_STATS_FD`.
+#[cfg(all(target_arch = "riscv32", feature = "alloc-stats"))]
+pub(crate) const ALLOC_STATS_FD: u32 = 3;
 
 /// Macro for making an ecall with variable number of parameters:
 /// - First parameter: syscall code (placed in a7)
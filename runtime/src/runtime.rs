@@ -1,6 +1,8 @@
 // Nexus VM runtime environment
 // Note: adapted from riscv-rt, which was adapted from cortex-m.
-use crate::alloc::sys_alloc_aligned;
+use crate::heap::report_stats;
+#[cfg(not(feature = "free-list"))]
+use crate::heap::sys_alloc_aligned;
 use crate::{ecall, write_output, EXIT_PANIC, EXIT_SUCCESS, SYS_EXIT};
 use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
@@ -8,6 +10,9 @@ use core::panic::PanicInfo;
 #[inline(never)]
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
+    report_stats();
+    #[cfg(feature = "panic-message")]
+    log_panic(_info);
     // Write the exit code to the output.
     let _ = write_output!(0, EXIT_PANIC);
     // Finish with exit syscall.
@@ -18,6 +23,50 @@ fn panic(_info: &PanicInfo) -> ! {
     }
 }
 
+// Size of the buffer the panic message is formatted into, longer messages are truncated.
+#[cfg(feature = "panic-message")]
+const PANIC_MESSAGE_SIZE: usize = 256;
+
+// Fixed-size buffer for the panic message, as the panic may come from an exhausted heap.
+#[cfg(feature = "panic-message")]
+struct PanicMessage {
+    buf: [u8; PANIC_MESSAGE_SIZE],
+    len: usize,
+}
+
+#[cfg(feature = "panic-message")]
+impl core::fmt::Write for PanicMessage {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let len = s.len().min(PANIC_MESSAGE_SIZE - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+/// Write the panic message and its location to the debug log, as a single log entry.
+#[cfg(feature = "panic-message")]
+fn log_panic(info: &PanicInfo) {
+    use core::fmt::Write;
+
+    let mut message = PanicMessage {
+        buf: [0; PANIC_MESSAGE_SIZE],
+        len: 0,
+    };
+    let _ = match info.location() {
+        Some(location) => writeln!(message, "panicked at {location}:\n{}", info.message()),
+        None => writeln!(message, "panicked:\n{}", info.message()),
+    };
+
+    let buf_ptr = message.buf.as_ptr();
+    let _ = ecall!(
+        crate::SYS_LOG,
+        crate::STDOUT_FD,
+        ("a1", buf_ptr),
+        ("a2", message.len)
+    );
+}
+
 #[export_name = "error: nexus-rt appears more than once"]
 #[doc(hidden)]
 pub static __ONCE__: () = ();
@@ -29,12 +78,27 @@ static HEAP: Heap = Heap;
 
 // This trivial allocate will always expand the heap, and never
 // deallocates. This should be fine for small programs.
-
+#[cfg(not(feature = "free-list"))]
 unsafe impl GlobalAlloc for Heap {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         sys_alloc_aligned(layout.size(), layout.align())
     }
-    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
+        #[cfg(feature = "alloc-stats")]
+        crate::heap::stats::record_dealloc(_layout.size());
+    }
+}
+
+// With the `free-list` feature, freed memory is reused by later allocations, which keeps the
+// heap (and the tracked RAM) small for programs that repeatedly build and drop collections.
+#[cfg(feature = "free-list")]
+unsafe impl GlobalAlloc for Heap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        crate::free_list::alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        crate::free_list::dealloc(ptr, layout)
+    }
 }
 
 /// Rust entry point (_start_rust).
@@ -50,6 +114,7 @@ pub unsafe extern "C" fn start_rust() -> u32 {
     // Run the program.
     main();
 
+    report_stats();
     // Write the exit code to the output.
     let _ = write_output!(0, EXIT_SUCCESS);
     // Finish with exit syscall.
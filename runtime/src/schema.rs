@@ -0,0 +1,165 @@
+//! Schemas of guest I/O types, recorded by the input and output macros for the host to check its
+//! inputs against, see [`nexus_common::schema`].
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    string::String,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+pub use nexus_common::schema::{entry, entry_len, tag, Encoded};
+pub use nexus_rt_macros::Schema;
+
+/// A type whose postcard encoding is described by a schema.
+///
+/// Derive it with `#[derive(nexus_rt::Schema)]` on the structs and enums read or written by the
+/// input and output macros, so that the host can check its inputs against them. Types that do not
+/// implement it are recorded as opaque and match any input.
+pub trait Schema {
+    const SCHEMA: Encoded;
+}
+
+// Resolves to the schema of `T` if it implements `Schema`, and to an opaque schema otherwise, as the
+// inherent constant is only found when its bound holds. Only meaningful for concrete types.
+#[doc(hidden)]
+pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+impl<T: Schema + ?Sized> Probe<T> {
+    pub const SCHEMA: Encoded = T::SCHEMA;
+}
+
+#[doc(hidden)]
+pub trait OpaqueSchema {
+    const SCHEMA: Encoded = Encoded::OPAQUE;
+}
+
+impl<T: ?Sized> OpaqueSchema for Probe<T> {}
+
+macro_rules! impl_leaf {
+    ($($ty:ty => $tag:ident),* $(,)?) => {
+        $(impl Schema for $ty {
+            const SCHEMA: Encoded = Encoded::leaf(tag::$tag);
+        })*
+    };
+}
+
+// `usize` and `isize` are encoded as 64-bit integers regardless of the target.
+impl_leaf!(
+    () => UNIT, bool => BOOL, char => CHAR, str => STR, String => STR,
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64, u128 => U128, usize => U64,
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64, i128 => I128, isize => I64,
+    f32 => F32, f64 => F64,
+);
+
+impl<T: ?Sized> Schema for PhantomData<T> {
+    const SCHEMA: Encoded = Encoded::leaf(tag::UNIT);
+}
+
+impl<T: Schema + ?Sized> Schema for &T {
+    const SCHEMA: Encoded = T::SCHEMA;
+}
+
+impl<T: Schema + ?Sized> Schema for Box<T> {
+    const SCHEMA: Encoded = T::SCHEMA;
+}
+
+impl<T: Schema> Schema for Option<T> {
+    const SCHEMA: Encoded = Encoded::option(&T::SCHEMA);
+}
+
+impl<T: Schema, const N: usize> Schema for [T; N] {
+    const SCHEMA: Encoded = Encoded::array(N, &T::SCHEMA);
+}
+
+macro_rules! impl_seq {
+    ($($ty:ident),*) => {
+        $(impl<T: Schema> Schema for $ty<T> {
+            const SCHEMA: Encoded = Encoded::seq(&T::SCHEMA);
+        })*
+    };
+}
+
+impl_seq!(Vec, VecDeque, BTreeSet);
+
+impl<T: Schema> Schema for [T] {
+    const SCHEMA: Encoded = Encoded::seq(&T::SCHEMA);
+}
+
+impl<K: Schema, V: Schema> Schema for BTreeMap<K, V> {
+    const SCHEMA: Encoded = Encoded::map(&K::SCHEMA, &V::SCHEMA);
+}
+
+macro_rules! impl_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty: Schema),+> Schema for ($($ty,)+) {
+            const SCHEMA: Encoded = Encoded::tuple(&[$($ty::SCHEMA),+]);
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+impl_tuple!(A, B, C, D, E, F, G, H, I);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as nexus_rt;
+
+    #[derive(nexus_rt::Schema)]
+    #[allow(dead_code)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(nexus_rt::Schema)]
+    #[allow(dead_code)]
+    enum Shape<T> {
+        Empty,
+        Circle(T),
+        Polygon(Vec<T>, bool),
+        Rect { corner: T, size: (u32, u32) },
+    }
+
+    struct Unknown;
+
+    #[test]
+    fn test_derive() {
+        assert_eq!(
+            Point::SCHEMA.as_bytes(),
+            [tag::TUPLE, 2, tag::I32, tag::I32]
+        );
+
+        #[rustfmt::skip]
+        let shape = [
+            tag::ENUM, 4,
+            tag::UNIT,
+            tag::TUPLE, 2, tag::I32, tag::I32,
+            tag::TUPLE, 2, tag::SEQ, tag::TUPLE, 2, tag::I32, tag::I32, tag::BOOL,
+            tag::TUPLE, 2, tag::TUPLE, 2, tag::I32, tag::I32, tag::TUPLE, 2, tag::U32, tag::U32,
+        ];
+        assert_eq!(Shape::<Point>::SCHEMA.as_bytes(), shape);
+    }
+
+    #[test]
+    fn test_probe() {
+        assert_eq!(
+            Probe::<(String, [u8; 4])>::SCHEMA.as_bytes(),
+            [tag::TUPLE, 2, tag::STR, tag::ARRAY, 4, tag::U8]
+        );
+        assert_eq!(Probe::<Unknown>::SCHEMA.as_bytes(), [tag::OPAQUE]);
+        assert_eq!(Probe::<Vec<Unknown>>::SCHEMA.as_bytes(), [tag::OPAQUE]);
+    }
+}
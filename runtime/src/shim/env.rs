@@ -0,0 +1,55 @@
+//! Stub `std::env`, the zkVM has no command-line arguments or environment variables.
+
+use alloc::string::String;
+use core::fmt;
+
+/// The error type for `var`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VarError {
+    NotPresent,
+}
+
+impl fmt::Display for VarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("environment variable not found")
+    }
+}
+
+impl core::error::Error for VarError {}
+
+/// An iterator over the arguments of the program, always empty.
+pub struct Args(());
+
+impl Iterator for Args {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// An iterator over the environment variables, always empty.
+pub struct Vars(());
+
+impl Iterator for Vars {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<(String, String)> {
+        None
+    }
+}
+
+/// Return the arguments of the program, which are always empty.
+pub fn args() -> Args {
+    Args(())
+}
+
+/// Return the environment variables, which are always empty.
+pub fn vars() -> Vars {
+    Vars(())
+}
+
+/// Fetch an environment variable, which is never present.
+pub fn var<K: AsRef<str>>(_key: K) -> Result<String, VarError> {
+    Err(VarError::NotPresent)
+}
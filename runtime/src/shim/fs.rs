@@ -0,0 +1,79 @@
+//! Minimal read-only `std::fs`, serving files from the private input tape.
+//!
+//! On first use, the whole private input is deserialized as a `Vec<(String, Vec<u8>)>` of paths
+//! and contents, so the host passes the files as the private input of the execution. Guests that
+//! use this module cannot read their private input in any other way. If the private input is not
+//! such a table, every file operation fails with [`ErrorKind::InvalidData`].
+
+use alloc::{string::String, vec::Vec};
+
+use super::io::{self, Error, ErrorKind, Read};
+
+/// The file table, `None` inside if the private input could not be decoded as one.
+static mut FILES: Option<Option<Vec<(String, Vec<u8>)>>> = None;
+
+fn files() -> io::Result<&'static [(String, Vec<u8>)]> {
+    // SAFETY: Single threaded, and the table is never modified once read.
+    let files = unsafe { &mut *core::ptr::addr_of_mut!(FILES) };
+    files
+        .get_or_insert_with(|| crate::read_private_input().ok())
+        .as_deref()
+        .ok_or(Error::new(
+            ErrorKind::InvalidData,
+            "private input is not a table of files",
+        ))
+}
+
+fn lookup(path: &str) -> io::Result<&'static [u8]> {
+    files()?
+        .iter()
+        .find(|(name, _)| name == path)
+        .map(|(_, contents)| contents.as_slice())
+        .ok_or(Error::new(
+            ErrorKind::NotFound,
+            "no such file in the private input",
+        ))
+}
+
+/// A read-only handle to a file from the private input.
+pub struct File {
+    contents: &'static [u8],
+    pos: usize,
+}
+
+impl File {
+    /// Open the file at `path`.
+    pub fn open<P: AsRef<str>>(path: P) -> io::Result<File> {
+        Ok(File {
+            contents: lookup(path.as_ref())?,
+            pos: 0,
+        })
+    }
+
+    /// Writing files is not supported.
+    pub fn create<P: AsRef<str>>(_path: P) -> io::Result<File> {
+        Err(Error::new(ErrorKind::Unsupported, "files are read-only"))
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.contents[self.pos..];
+        let len = remaining.len().min(buf.len());
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Read the entire contents of a file into a bytes vector.
+pub fn read<P: AsRef<str>>(path: P) -> io::Result<Vec<u8>> {
+    lookup(path.as_ref()).map(<[u8]>::to_vec)
+}
+
+/// Read the entire contents of a file into a string.
+pub fn read_to_string<P: AsRef<str>>(path: P) -> io::Result<String> {
+    let contents = lookup(path.as_ref())?;
+    String::from_utf8(contents.to_vec())
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8"))
+}
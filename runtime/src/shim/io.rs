@@ -0,0 +1,193 @@
+//! Minimal `std::io`, with the standard streams routed to the debug log.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{ecall, STDOUT_FD, SYS_LOG};
+
+/// The kind of an I/O error, a subset of `std::io::ErrorKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    NotFound,
+    InvalidData,
+    UnexpectedEof,
+    WriteZero,
+    Unsupported,
+    Other,
+}
+
+/// The error type for I/O operations.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: &'static str,
+}
+
+impl Error {
+    pub(crate) const fn new(kind: ErrorKind, message: &'static str) -> Self {
+        Self { kind, message }
+    }
+
+    /// Return the kind of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl core::error::Error for Error {}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Byte-oriented reading, as in `std::io::Read`.
+pub trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        let start = buf.len();
+        let mut chunk = [0u8; 64];
+        loop {
+            match self.read(&mut chunk)? {
+                0 => return Ok(buf.len() - start),
+                n => buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        let mut bytes = Vec::new();
+        let len = self.read_to_end(&mut bytes)?;
+        let s = core::str::from_utf8(&bytes).map_err(|_| {
+            Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+        })?;
+        buf.push_str(s);
+        Ok(len)
+    }
+
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                n => buf = &mut buf[n..],
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Byte-oriented writing, as in `std::io::Write`.
+pub trait Write {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+    fn flush(&mut self) -> Result<()>;
+
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf)? {
+                0 => {
+                    return Err(Error::new(
+                        ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                n => buf = &buf[n..],
+            }
+        }
+        Ok(())
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+        // Keep the first I/O error, as `fmt::Error` carries no information.
+        struct Adapter<'a, W: ?Sized> {
+            inner: &'a mut W,
+            error: Result<()>,
+        }
+
+        impl<W: Write + ?Sized> fmt::Write for Adapter<'_, W> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.inner.write_all(s.as_bytes()).map_err(|err| {
+                    self.error = Err(err);
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut adapter = Adapter {
+            inner: self,
+            error: Ok(()),
+        };
+        match fmt::write(&mut adapter, args) {
+            Ok(()) => Ok(()),
+            Err(_) => adapter
+                .error
+                .and(Err(Error::new(ErrorKind::Other, "formatter error"))),
+        }
+    }
+}
+
+// Write the bytes to the debug log, which is shared by stdout and stderr.
+fn write_log_bytes(buf: &[u8]) -> Result<usize> {
+    let buf_ptr = buf.as_ptr();
+    let buf_len = buf.len();
+    let out = ecall!(SYS_LOG, STDOUT_FD, ("a1", buf_ptr), ("a2", buf_len));
+    if out == u32::MAX {
+        Err(Error::new(
+            ErrorKind::Other,
+            "failed to write to the debug log",
+        ))
+    } else {
+        Ok(out as usize)
+    }
+}
+
+/// A handle to the standard output, routed to the debug log.
+pub struct Stdout;
+
+/// A handle to the standard error, routed to the debug log.
+pub struct Stderr;
+
+/// Construct a handle to the standard output.
+pub fn stdout() -> Stdout {
+    Stdout
+}
+
+/// Construct a handle to the standard error.
+pub fn stderr() -> Stderr {
+    Stderr
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        write_log_bytes(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        write_log_bytes(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for crate::PrivateInputReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        Ok(crate::PrivateInputReader::read(self, buf))
+    }
+}
@@ -0,0 +1,26 @@
+//! A `std`-compatible facade for guests, enabled by the `std-shim` feature.
+//!
+//! The RISC-V target has no `std`, so this module provides the subset of its API that guests
+//! commonly need, backed by the zkVM:
+//!
+//! - `io`: `stdout` and `stderr` are routed to the debug log (`SYS_LOG`).
+//! - `process`: `exit` is mapped to `SYS_EXIT`.
+//! - `fs`: read-only files served from the private input tape.
+//! - `env` and `time`: stubs, as the zkVM has neither an environment nor a clock.
+//!
+//! On native targets the modules are re-exports of `std`, so guests can write
+//! `use nexus_rt::shim::{fs, io}` and build for both targets.
+
+#[cfg(target_arch = "riscv32")]
+pub mod env;
+#[cfg(target_arch = "riscv32")]
+pub mod fs;
+#[cfg(target_arch = "riscv32")]
+pub mod io;
+#[cfg(target_arch = "riscv32")]
+pub mod process;
+#[cfg(target_arch = "riscv32")]
+pub mod time;
+
+#[cfg(not(target_arch = "riscv32"))]
+pub use std::{env, fs, io, process, time};
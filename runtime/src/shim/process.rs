@@ -0,0 +1,16 @@
+//! Minimal `std::process`.
+
+/// Terminate the program with the given exit code.
+pub fn exit(code: i32) -> ! {
+    crate::exit(code)
+}
+
+/// Terminate the program abnormally, as a panic.
+pub fn abort() -> ! {
+    panic!("process aborted")
+}
+
+/// Return the identifier of the process, which is always 0.
+pub fn id() -> u32 {
+    0
+}
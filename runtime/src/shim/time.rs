@@ -0,0 +1,58 @@
+//! Stub `std::time`, the zkVM has no clock so time never advances.
+
+pub use core::time::Duration;
+
+use core::fmt;
+
+/// A measurement of a monotonic clock, all instants are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(());
+
+impl Instant {
+    pub fn now() -> Instant {
+        Instant(())
+    }
+
+    pub fn duration_since(&self, _earlier: Instant) -> Duration {
+        Duration::ZERO
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// A measurement of the system clock, always the unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemTime(());
+
+/// The unix epoch, the only value of `SystemTime`.
+pub const UNIX_EPOCH: SystemTime = SystemTime(());
+
+/// The error type for `SystemTime::duration_since`, never returned.
+#[derive(Debug, Clone)]
+pub struct SystemTimeError(());
+
+impl fmt::Display for SystemTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("second time provided was later than self")
+    }
+}
+
+impl core::error::Error for SystemTimeError {}
+
+impl SystemTime {
+    pub const UNIX_EPOCH: SystemTime = UNIX_EPOCH;
+
+    pub fn now() -> SystemTime {
+        UNIX_EPOCH
+    }
+
+    pub fn duration_since(&self, _earlier: SystemTime) -> Result<Duration, SystemTimeError> {
+        Ok(Duration::ZERO)
+    }
+
+    pub fn elapsed(&self) -> Result<Duration, SystemTimeError> {
+        Ok(Duration::ZERO)
+    }
+}
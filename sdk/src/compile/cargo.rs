@@ -31,6 +31,7 @@ impl Compile for Compiler<CargoPackager> {
             debug: false,
             native: false,
             unique: false,
+            std_shim: false,
             _packager: PhantomData,
         }
     }
@@ -43,6 +44,7 @@ impl Compile for Compiler<CargoPackager> {
             debug: false,
             native: false,
             unique: false,
+            std_shim: false,
             _packager: PhantomData,
         }
     }
@@ -64,6 +66,13 @@ impl Compile for Compiler<CargoPackager> {
         self.unique = unique;
     }
 
+    /// Set dynamic compilation to enable the `std-shim` feature of `nexus-rt`, which provides a `std`-compatible facade for the guest program in `nexus_rt::shim`.
+    ///
+    /// Note: the guest program must depend on `nexus-rt` directly.
+    fn set_std_shim(&mut self, std_shim: bool) {
+        self.std_shim = std_shim;
+    }
+
     /// Compile and build the guest binary.
     fn build(&mut self) -> Result<PathBuf, BuildError> {
         let linker_path = Compiler::set_linker()?;
@@ -115,6 +124,10 @@ impl Compile for Compiler<CargoPackager> {
             profile,
         ]);
 
+        if self.std_shim {
+            cmd.args(["--features", "nexus-rt/std-shim"]);
+        }
+
         let res = cmd.output()?;
 
         if !res.status.success() {
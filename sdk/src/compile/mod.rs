@@ -30,6 +30,7 @@ pub struct Compiler<P: Packager> {
     debug: bool,
     native: bool,
     unique: bool,
+    std_shim: bool,
     _packager: PhantomData<P>,
 }
 
@@ -52,6 +53,11 @@ pub trait Compile {
     /// Note: the SDK does not automatically clean or otherwise manage the resultant builds in the output directory.
     fn set_unique_build(&mut self, unique: bool);
 
+    /// Set dynamic compilation to enable the `std-shim` feature of `nexus-rt`, which provides a `std`-compatible facade for the guest program in `nexus_rt::shim`.
+    ///
+    /// Note: the guest program must depend on `nexus-rt` directly.
+    fn set_std_shim(&mut self, std_shim: bool);
+
     /// Set the linker script to use when building the guest binary.
     fn set_linker() -> Result<PathBuf, BuildError> {
         let linker_script = include_str!("./linker-scripts/default.x");
@@ -42,6 +42,23 @@ pub enum IOError {
     /// Error parsing the logging tape due to an encoding issue.
     #[error("encoding  error: {0}")]
     EncodingError(#[from] std::string::FromUtf8Error),
+
+    /// Error accessing a public output segment that was not written by the execution.
+    #[error("public output segment not found: {0}")]
+    SegmentNotFound(String),
+
+    /// Error encoding an input whose value does not match the schema of the type declared by the guest.
+    #[error("{input} has type `{found}`, but the guest program expects `{expected}`: {mismatch}")]
+    TypeMismatch {
+        /// The input being encoded.
+        input: &'static str,
+        /// The type declared by the guest program.
+        expected: String,
+        /// The type provided by the host.
+        found: String,
+        /// Where the input departs from the schema of the declared type.
+        mismatch: nexus_core::nvm::SchemaMismatch,
+    },
 }
 
 /// Errors that occur while manipulating host system file paths.
@@ -0,0 +1,329 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{self, Debug, Display};
+use thiserror::Error;
+
+use nexus_core::nvm::Trace;
+
+use crate::compile::{cargo::CargoPackager, Compiler};
+use crate::legacy::compile::CompileOpts;
+use crate::legacy::traits::{LegacyProver, LegacyViewable};
+use crate::stwo::seq::{Error as StwoError, Stwo};
+use crate::traits::*;
+use crate::KnownExitCodes;
+
+/// Errors that occur while comparing a legacy execution against the Stwo-based zkVM.
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    /// The guest program could not be built or loaded for the legacy prover.
+    #[error("unable to build the legacy guest program: {0}")]
+    LegacyBuildError(String),
+
+    /// An error occurred building or running the guest program with the Stwo-based zkVM.
+    #[error(transparent)]
+    StwoError(#[from] StwoError),
+}
+
+/// The observable outcome of executing a guest program.
+#[derive(Debug, Clone)]
+pub struct Execution<U> {
+    /// The decoded public output, or the reason it could not be obtained.
+    pub output: Result<U, String>,
+    /// The exit code of the execution.
+    ///
+    /// The legacy zkVM does not report exit codes, so a legacy execution that completes is recorded as
+    /// [`KnownExitCodes::ExitSuccess`] and one that fails as [`KnownExitCodes::ExitPanic`].
+    pub exit_code: u32,
+    /// The debug logs written by the guest program.
+    pub logs: Vec<String>,
+    /// The number of executed instructions, if known.
+    pub cycles: Option<usize>,
+}
+
+/// A difference between the legacy and the Stwo-based executions of a guest program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// The public outputs differ.
+    Output { legacy: String, stwo: String },
+    /// The exit codes differ.
+    ExitCode { legacy: u32, stwo: u32 },
+    /// The debug logs differ.
+    Logs { legacy: String, stwo: String },
+    /// The cycle counts differ.
+    Cycles { legacy: usize, stwo: usize },
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Output { legacy, stwo } => {
+                write!(f, "output: legacy {legacy}, stwo {stwo}")
+            }
+            Difference::ExitCode { legacy, stwo } => {
+                write!(f, "exit code: legacy {legacy}, stwo {stwo}")
+            }
+            Difference::Logs { legacy, stwo } => {
+                write!(f, "logs: legacy {legacy:?}, stwo {stwo:?}")
+            }
+            Difference::Cycles { legacy, stwo } => {
+                write!(f, "cycles: legacy {legacy}, stwo {stwo}")
+            }
+        }
+    }
+}
+
+/// The result of re-running a legacy guest program on the Stwo-based zkVM.
+#[derive(Debug, Clone)]
+pub struct MigrationReport<U> {
+    /// The execution on the legacy zkVM.
+    pub legacy: Execution<U>,
+    /// The execution on the Stwo-based zkVM.
+    pub stwo: Execution<U>,
+}
+
+impl<U: PartialEq + Debug> MigrationReport<U> {
+    /// List all differences between the two executions.
+    ///
+    /// Logs are compared by their concatenation, as the two runtimes may split the same output into different writes.
+    pub fn differences(&self) -> Vec<Difference> {
+        let mut differences = Vec::new();
+
+        let describe = |output: &Result<U, String>| match output {
+            Ok(output) => format!("{output:?}"),
+            Err(err) => format!("<{err}>"),
+        };
+        if self.legacy.output != self.stwo.output {
+            differences.push(Difference::Output {
+                legacy: describe(&self.legacy.output),
+                stwo: describe(&self.stwo.output),
+            });
+        }
+
+        if self.legacy.exit_code != self.stwo.exit_code {
+            differences.push(Difference::ExitCode {
+                legacy: self.legacy.exit_code,
+                stwo: self.stwo.exit_code,
+            });
+        }
+
+        let (legacy_logs, stwo_logs) = (self.legacy.logs.concat(), self.stwo.logs.concat());
+        if legacy_logs != stwo_logs {
+            differences.push(Difference::Logs {
+                legacy: legacy_logs,
+                stwo: stwo_logs,
+            });
+        }
+
+        if let (Some(legacy), Some(stwo)) = (self.legacy.cycles, self.stwo.cycles) {
+            if legacy != stwo {
+                differences.push(Difference::Cycles { legacy, stwo });
+            }
+        }
+
+        differences
+    }
+
+    /// Check whether the two executions agree on their outputs, exit codes and logs.
+    ///
+    /// Cycle counts are not taken into account, as the two machines are expected to execute a different number of
+    /// instructions for the same program.
+    pub fn is_compatible(&self) -> bool {
+        self.differences()
+            .iter()
+            .all(|difference| matches!(difference, Difference::Cycles { .. }))
+    }
+}
+
+impl<U: PartialEq + Debug> Display for MigrationReport<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let differences = self.differences();
+        if differences.is_empty() {
+            return write!(f, "no differences between the legacy and stwo executions");
+        }
+
+        write!(
+            f,
+            "{} difference(s) between the legacy and stwo executions:",
+            differences.len()
+        )?;
+        for difference in differences {
+            write!(f, "\n  {difference}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Re-run a legacy guest program on the Stwo-based zkVM and compare the executions.
+///
+/// The legacy guest program is built with [`CompileOpts`] for the legacy prover `P`, while its port to the new
+/// runtime is built with a [`Compiler`]. Both are run on the same private input, as saved for the legacy project.
+///
+/// Saved legacy proofs, parameters and inputs are not loaded, the legacy guest program is rebuilt and re-executed.
+pub struct Migration {
+    legacy: CompileOpts,
+    stwo: Compiler<CargoPackager>,
+    legacy_cycles: Option<usize>,
+}
+
+impl Migration {
+    /// Set up a migration between a legacy guest program and its port to the new runtime.
+    pub fn new(legacy: CompileOpts, stwo: Compiler<CargoPackager>) -> Self {
+        Self {
+            legacy,
+            stwo,
+            legacy_cycles: None,
+        }
+    }
+
+    /// Set the number of cycles of the legacy execution, as recorded when it was proven.
+    ///
+    /// The legacy provers do not report cycle counts, so they are only compared if provided.
+    pub fn set_legacy_cycles(&mut self, cycles: usize) {
+        self.legacy_cycles = Some(cycles);
+    }
+
+    /// Run both guest programs on private input of type `S` and compare their executions, decoding the public output as of type `U`.
+    pub fn run<'a, P, S, U>(
+        &mut self,
+        private_input: &S,
+    ) -> Result<MigrationReport<U>, MigrationError>
+    where
+        P: LegacyProver<'a>,
+        P::Error: From<std::io::Error> + Display,
+        S: Serialize + Sized,
+        U: Serialize + DeserializeOwned + Sized,
+    {
+        let prover = P::compile(&self.legacy)
+            .map_err(|e| MigrationError::LegacyBuildError(e.to_string()))?;
+
+        let legacy = match prover.run_with_input(private_input) {
+            Ok(view) => Execution {
+                output: view.output::<U>().map_err(|e| e.to_string()),
+                exit_code: KnownExitCodes::ExitSuccess as u32,
+                logs: view.logs().clone(),
+                cycles: self.legacy_cycles,
+            },
+            Err(e) => Execution {
+                output: Err(e.to_string()),
+                exit_code: KnownExitCodes::ExitPanic as u32,
+                logs: Vec::new(),
+                cycles: self.legacy_cycles,
+            },
+        };
+
+        let prover = Stwo::<Local>::compile(&mut self.stwo)?;
+        let (view, trace) = prover.trace_with_input(private_input, &())?;
+
+        let stwo = Execution {
+            output: view.public_output::<U>().map_err(|e| e.to_string()),
+            exit_code: view.exit_code().map_err(StwoError::from)?,
+            logs: view.logs().map_err(StwoError::from)?,
+            cycles: Some(trace.get_num_steps()),
+        };
+
+        Ok(MigrationReport { legacy, stwo })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(output: Result<u32, String>, exit_code: u32, logs: &[&str]) -> Execution<u32> {
+        Execution {
+            output,
+            exit_code,
+            logs: logs.iter().map(|log| log.to_string()).collect(),
+            cycles: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_executions() {
+        let legacy = execution(Ok(42), 0, &["hello"]);
+        let report = MigrationReport {
+            stwo: legacy.clone(),
+            legacy,
+        };
+
+        assert!(report.differences().is_empty());
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn test_logs_compared_by_concatenation() {
+        let report = MigrationReport {
+            legacy: execution(Ok(42), 0, &["hello, world\n"]),
+            stwo: execution(Ok(42), 0, &["hello", ", world", "\n"]),
+        };
+
+        assert!(report.differences().is_empty());
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn test_cycles_do_not_break_compatibility() {
+        let mut report = MigrationReport {
+            legacy: execution(Ok(42), 0, &[]),
+            stwo: execution(Ok(42), 0, &[]),
+        };
+        report.legacy.cycles = Some(100);
+        report.stwo.cycles = Some(80);
+
+        assert_eq!(
+            report.differences(),
+            [Difference::Cycles {
+                legacy: 100,
+                stwo: 80
+            }]
+        );
+        assert!(report.is_compatible());
+
+        // Cycles are only compared if both are known.
+        report.legacy.cycles = None;
+        assert!(report.differences().is_empty());
+    }
+
+    #[test]
+    fn test_incompatible_executions() {
+        let report = MigrationReport {
+            legacy: execution(Ok(42), 0, &["done"]),
+            stwo: execution(Err("deserialization failed".to_string()), 1, &[]),
+        };
+
+        assert_eq!(
+            report.differences(),
+            [
+                Difference::Output {
+                    legacy: "42".to_string(),
+                    stwo: "<deserialization failed>".to_string(),
+                },
+                Difference::ExitCode { legacy: 0, stwo: 1 },
+                Difference::Logs {
+                    legacy: "done".to_string(),
+                    stwo: String::new(),
+                },
+            ]
+        );
+        assert!(!report.is_compatible());
+        assert!(report
+            .to_string()
+            .starts_with("3 difference(s) between the legacy and stwo executions:"));
+    }
+
+    #[test]
+    fn test_output_difference_alone_is_incompatible() {
+        let report = MigrationReport {
+            legacy: execution(Ok(42), 0, &[]),
+            stwo: execution(Ok(43), 0, &[]),
+        };
+
+        assert_eq!(
+            report.differences(),
+            [Difference::Output {
+                legacy: "42".to_string(),
+                stwo: "43".to_string(),
+            }]
+        );
+        assert!(!report.is_compatible());
+    }
+}
@@ -19,5 +19,8 @@ pub mod compile;
 /// View the output of an execution.
 pub mod views;
 
+/// Compare legacy executions against the Stwo-based zkVM.
+pub mod migrate;
+
 #[cfg(any(feature = "legacy-nova", feature = "legacy-hypernova"))]
 pub(crate) mod ark_serialize_utils;
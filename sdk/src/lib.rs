@@ -18,6 +18,10 @@ pub mod legacy;
 mod traits;
 pub use traits::*;
 
+/// Typed host handles for guest programs with a shared I/O interface.
+pub mod typed;
+pub use typed::{Interface, Typed};
+
 /// Configure the dynamic compilation of guest programs.
 pub mod compile;
 
@@ -1,5 +1,6 @@
 use crate::compile::Compile;
 use crate::traits::*;
+use nexus_core::nvm::internals::InternalView;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -7,6 +8,8 @@ use thiserror::Error;
 
 use crate::error::{BuildError, ConfigurationError, IOError, PathError};
 
+pub use nexus_core::nvm::{LayoutOverrides, MemoryReport};
+
 /// Errors that occur while proving using Stwo.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -45,6 +48,19 @@ pub enum Error {
     /// An error occured configuring the prover.
     #[error(transparent)]
     ConfigurationError(#[from] ConfigurationError),
+
+    /// An error occurred decoding a stored proof.
+    #[error(transparent)]
+    ProofFormatError(#[from] nexus_core::stwo::format::DecodeError),
+
+    /// A stored proof was produced for a different program.
+    #[error("proof is for program {found}, expected {expected}")]
+    ProgramMismatch {
+        /// The program of the view the proof is checked against.
+        expected: nexus_core::stwo::format::ProgramId,
+        /// The program recorded in the proof.
+        found: nexus_core::stwo::format::ProgramId,
+    },
 }
 
 /// Prover for the Nexus zkVM, when using Stwo.
@@ -53,6 +69,8 @@ pub struct Stwo<C: Compute = Local> {
     pub elf: nexus_core::nvm::ElfFile,
     /// The associated data to prove with.
     pub ad: Vec<u8>,
+    /// The heap and stack sizes to prove with, instead of those used by the execution.
+    pub layout_overrides: LayoutOverrides,
     _compute: PhantomData<C>,
 }
 
@@ -63,6 +81,28 @@ pub struct Proof {
     memory_layout: nexus_core::nvm::internals::LinearMemoryLayout,
 }
 
+impl Proof {
+    /// Encode the proof into the versioned proof format, binding it to the program of `view`.
+    pub fn to_bytes(&self, view: &nexus_core::nvm::View) -> Vec<u8> {
+        let program_id = nexus_core::stwo::format::ProgramId::new(view.get_program_memory());
+        nexus_core::stwo::format::encode(self, program_id)
+    }
+
+    /// Decode a proof from the versioned proof format, checking that it was produced for the program of `view`.
+    pub fn from_bytes(bytes: &[u8], view: &nexus_core::nvm::View) -> Result<Self, Error> {
+        let (header, proof) = nexus_core::stwo::format::decode(bytes)?;
+
+        let expected = nexus_core::stwo::format::ProgramId::new(view.get_program_memory());
+        if header.program_id != expected {
+            return Err(Error::ProgramMismatch {
+                expected,
+                found: header.program_id,
+            });
+        }
+        Ok(proof)
+    }
+}
+
 impl<C: Compute> ByGuestCompilation for Stwo<C>
 where
     Stwo<C>: Prover,
@@ -76,32 +116,24 @@ where
     }
 }
 
-impl Prover for Stwo<Local> {
-    type Proof = Proof;
-    type View = nexus_core::nvm::View;
-    type Error = Error;
-
-    /// Construct a new proving instance.
-    fn new(elf: &nexus_core::nvm::ElfFile) -> Result<Self, <Self as Prover>::Error> {
-        Ok(Self {
-            elf: elf.clone(),
-            ad: Vec::new(),
-            _compute: PhantomData,
-        })
-    }
-
-    /// Set the associated data bytes to be bound into the proof.
-    fn set_associated_data(&mut self, ad: &[u8]) -> Result<(), <Self as Prover>::Error> {
-        self.ad = ad.to_vec();
-        Ok(())
-    }
-
-    /// Run the zkVM on private input of type `S` and public input of type `T` and return a view of the execution output.
-    fn run_with_input<S: Serialize + Sized, T: Serialize + DeserializeOwned + Sized>(
+impl Stwo<Local> {
+    /// Run the zkVM on the encoded private and public inputs and return a view of the execution output along with its trace.
+    pub(crate) fn trace_with_input<
+        S: Serialize + Sized,
+        T: Serialize + DeserializeOwned + Sized,
+    >(
         &self,
         private_input: &S,
         public_input: &T,
-    ) -> Result<Self::View, <Self as Prover>::Error> {
+    ) -> Result<(nexus_core::nvm::View, nexus_core::nvm::UniformTrace), Error> {
+        let schema = &self.elf.io_schema;
+        check_input(
+            schema.private_input.as_ref(),
+            private_input,
+            "private input",
+        )?;
+        check_input(schema.public_input.as_ref(), public_input, "public input")?;
+
         let mut private_encoded = postcard::to_stdvec(&private_input).map_err(IOError::from)?;
         if !private_encoded.is_empty() {
             let private = private_input.to_owned();
@@ -124,52 +156,98 @@ impl Prover for Stwo<Local> {
             public_encoded.resize(public_padded_len, 0x00); // cobs ignores 0x00 padding
         }
 
-        let (view, _) = nexus_core::nvm::k_trace(
+        Ok(nexus_core::nvm::k_trace_with_overrides(
             self.elf.clone(),
             self.ad.as_slice(),
             public_encoded.as_slice(),
             private_encoded.as_slice(),
             1,
-        )?; // todo: run without tracing?
-
-        Ok(view)
+            &self.layout_overrides,
+        )?)
     }
 
-    /// Run the zkVM on private input of type `S` and public input of type `T` and return a verifiable proof, along with a view of the execution output.
-    fn prove_with_input<S: Serialize + Sized, T: Serialize + DeserializeOwned + Sized>(
+    /// Same as [`Prover::prove_with_input`], additionally returns a report of the proving costs per phase and chip.
+    pub fn prove_with_input_and_report<
+        S: Serialize + Sized,
+        T: Serialize + DeserializeOwned + Sized,
+    >(
         self,
         private_input: &S,
         public_input: &T,
-    ) -> Result<(Self::View, Self::Proof), <Self as Prover>::Error> {
-        let mut private_encoded = postcard::to_stdvec(&private_input).map_err(IOError::from)?;
-        if !private_encoded.is_empty() {
-            let private = private_input.to_owned();
+    ) -> Result<(nexus_core::nvm::View, Proof, nexus_core::stwo::ProverReport), Error> {
+        let (view, trace) = self.trace_with_input(private_input, public_input)?;
+        let (proof, report) = nexus_core::stwo::prove_with_report(&trace, &view)?;
 
-            private_encoded = postcard::to_stdvec_cobs(&private).map_err(IOError::from)?;
-            let private_padded_len = (private_encoded.len() + 3) & !3;
+        Ok((
+            view,
+            Proof {
+                proof,
+                memory_layout: trace.memory_layout,
+            },
+            report,
+        ))
+    }
+}
 
-            assert!(private_padded_len >= private_encoded.len());
-            private_encoded.resize(private_padded_len, 0x00); // cobs ignores 0x00 padding
-        }
+/// Check an input against the schema of the type declared for it by the guest program, if any.
+fn check_input<S: Serialize + ?Sized>(
+    expected: Option<&nexus_core::nvm::TypeSchema>,
+    value: &S,
+    input: &'static str,
+) -> Result<(), IOError> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+
+    expected
+        .check(value)
+        .map_err(|mismatch| IOError::TypeMismatch {
+            input,
+            expected: expected.name.clone(),
+            found: nexus_core::nvm::normalize_type_name(std::any::type_name::<S>()),
+            mismatch,
+        })
+}
 
-        let mut public_encoded = postcard::to_stdvec(&public_input).map_err(IOError::from)?;
-        if !public_encoded.is_empty() {
-            let public = public_input.to_owned();
+impl Prover for Stwo<Local> {
+    type Proof = Proof;
+    type View = nexus_core::nvm::View;
+    type Error = Error;
 
-            public_encoded = postcard::to_stdvec_cobs(&public).map_err(IOError::from)?;
-            let public_padded_len = (public_encoded.len() + 3) & !3;
+    /// Construct a new proving instance.
+    fn new(elf: &nexus_core::nvm::ElfFile) -> Result<Self, <Self as Prover>::Error> {
+        Ok(Self {
+            elf: elf.clone(),
+            ad: Vec::new(),
+            layout_overrides: Default::default(),
+            _compute: PhantomData,
+        })
+    }
 
-            assert!(public_padded_len >= public_encoded.len());
-            public_encoded.resize(public_padded_len, 0x00); // cobs ignores 0x00 padding
-        }
+    /// Set the associated data bytes to be bound into the proof.
+    fn set_associated_data(&mut self, ad: &[u8]) -> Result<(), <Self as Prover>::Error> {
+        self.ad = ad.to_vec();
+        Ok(())
+    }
 
-        let (view, trace) = nexus_core::nvm::k_trace(
-            self.elf.clone(),
-            self.ad.as_slice(),
-            public_encoded.as_slice(),
-            private_encoded.as_slice(),
-            1,
-        )?;
+    /// Run the zkVM on private input of type `S` and public input of type `T` and return a view of the execution output.
+    fn run_with_input<S: Serialize + Sized, T: Serialize + DeserializeOwned + Sized>(
+        &self,
+        private_input: &S,
+        public_input: &T,
+    ) -> Result<Self::View, <Self as Prover>::Error> {
+        let (view, _) = self.trace_with_input(private_input, public_input)?; // todo: run without tracing?
+
+        Ok(view)
+    }
+
+    /// Run the zkVM on private input of type `S` and public input of type `T` and return a verifiable proof, along with a view of the execution output.
+    fn prove_with_input<S: Serialize + Sized, T: Serialize + DeserializeOwned + Sized>(
+        self,
+        private_input: &S,
+        public_input: &T,
+    ) -> Result<(Self::View, Self::Proof), <Self as Prover>::Error> {
+        let (view, trace) = self.trace_with_input(private_input, public_input)?;
         let proof = nexus_core::stwo::prove(&trace, &view)?;
 
         Ok((
@@ -41,11 +41,10 @@ impl CheckedView for nexus_core::nvm::View {
         let emulator = LinearEmulator::default();
 
         // Replace custom instructions `rin` and `wou` with `lw` and `sw`.
-        let instructions = expected_elf
-            .instructions
-            .iter()
-            .map(|instr| convert_instruction(&emulator.executor.instruction_executor, instr))
-            .collect();
+        let instructions = convert_instructions(
+            &emulator.executor.instruction_executor,
+            &expected_elf.instructions,
+        );
 
         let converted_elf = nexus_core::nvm::ElfFile {
             instructions,
@@ -101,6 +100,7 @@ impl CheckedView for nexus_core::nvm::View {
             &exit_code,
             &output_memory,
             &expected_ad.to_vec(),
+            &None,
         )
     }
 }
@@ -127,6 +127,23 @@ pub trait Viewable {
     /// Deserialize the exit code resulting from the execution.
     fn exit_code(&self) -> Result<u32, IOError>;
 
+    /// Deserialize the public output segment at `index`, for guests that write several segments.
+    ///
+    /// Each call to `nexus_rt::write_public_output` or `nexus_rt::write_public_output_named` in the guest appends a segment.
+    fn public_output_segment<U: Serialize + DeserializeOwned + Sized>(
+        &self,
+        index: usize,
+    ) -> Result<U, IOError>;
+
+    /// Deserialize the public output segment written under `name` by `nexus_rt::write_public_output_named`.
+    fn public_output_named<U: Serialize + DeserializeOwned + Sized>(
+        &self,
+        name: &str,
+    ) -> Result<U, IOError>;
+
+    /// Return the number of public output segments written by the execution.
+    fn public_output_segment_count(&self) -> Result<usize, IOError>;
+
     /// Compute a digest over the public output resulting from the execution.
     fn exit_code_digest<H: Digest>(&self) -> Result<GenericArray<u8, H::OutputSize>, IOError>
     where
@@ -135,7 +152,7 @@ pub trait Viewable {
         Ok(H::digest(Self::exit_code(self)?.to_le_bytes()))
     }
 
-    /// Deserialize the public output resulting from the execution.
+    /// Deserialize the public output resulting from the execution, the first segment for guests that write several segments.
     fn public_output<U: Serialize + DeserializeOwned + Sized>(&self) -> Result<U, IOError>;
 
     /// Compute a digest over the public output resulting from the execution.
@@ -186,13 +203,47 @@ impl Viewable for nexus_core::nvm::View {
         }
     }
 
-    /// Deserialize the public output resulting from the execution.
+    /// Deserialize the public output resulting from the execution, the first segment for guests that write several segments.
     fn public_output<U: Serialize + DeserializeOwned + Sized>(&self) -> Result<U, IOError> {
-        if let Some(mut bytes) = self.view_public_output() {
-            Ok(postcard::from_bytes_cobs::<U>(&mut bytes).map_err(IOError::from)?)
-        } else {
-            Err(IOError::NotYetAvailableError)
+        self.public_output_segment::<U>(0)
+    }
+
+    /// Deserialize the public output segment at `index`, for guests that write several segments.
+    fn public_output_segment<U: Serialize + DeserializeOwned + Sized>(
+        &self,
+        index: usize,
+    ) -> Result<U, IOError> {
+        let bytes = self
+            .view_public_output()
+            .ok_or(IOError::NotYetAvailableError)?;
+        let segment = public_output_segments(&bytes)
+            .nth(index)
+            .ok_or_else(|| IOError::SegmentNotFound(index.to_string()))?;
+        segment_value::<U>(segment)
+    }
+
+    /// Deserialize the public output segment written under `name` by `nexus_rt::write_public_output_named`.
+    fn public_output_named<U: Serialize + DeserializeOwned + Sized>(
+        &self,
+        name: &str,
+    ) -> Result<U, IOError> {
+        let bytes = self
+            .view_public_output()
+            .ok_or(IOError::NotYetAvailableError)?;
+        for segment in public_output_segments(&bytes) {
+            if segment_name(segment)?.is_some_and(|segment_name| segment_name == name) {
+                return segment_value::<U>(segment);
+            }
         }
+        Err(IOError::SegmentNotFound(name.to_string()))
+    }
+
+    /// Return the number of public output segments written by the execution.
+    fn public_output_segment_count(&self) -> Result<usize, IOError> {
+        let bytes = self
+            .view_public_output()
+            .ok_or(IOError::NotYetAvailableError)?;
+        Ok(public_output_segments(&bytes).count())
     }
 
     /// Deserialize the associated data bound into the execution.
@@ -433,6 +484,9 @@ pub trait Verifiable: Serialize + DeserializeOwned {
     fn verify(&self, expected_view: &Self::View) -> Result<(), <Self as Verifiable>::Error>;
 
     /// Verify the proof of an execution.
+    ///
+    /// The expected output is encoded as a single unnamed segment, i.e. as written by one call to
+    /// `nexus_rt::write_public_output`, which is how `#[nexus_rt::main]` returns a single value.
     fn verify_expected<
         T: Serialize + DeserializeOwned + Sized,
         U: Serialize + DeserializeOwned + Sized,
@@ -459,7 +513,8 @@ pub trait Verifiable: Serialize + DeserializeOwned {
         let mut output_encoded =
             postcard::to_stdvec(&expected_public_output).map_err(IOError::from)?;
         if !output_encoded.is_empty() {
-            let output = expected_public_output.to_owned();
+            // The guest writes its output as a single unnamed segment.
+            let output = (UNNAMED_SEGMENT, expected_public_output);
 
             output_encoded = postcard::to_stdvec_cobs(&output).map_err(IOError::from)?;
             let output_padded_len = (output_encoded.len() + 3) & !3;
@@ -526,3 +581,110 @@ pub trait Verifiable: Serialize + DeserializeOwned {
     /// Return a size estimate for the proof, in bytes.
     fn size_estimate(&self) -> usize;
 }
+
+/// Split the public output into its segments, each a COBS frame ending with a zero byte.
+///
+/// Segments are padded to a word boundary with zero bytes, which are skipped.
+fn public_output_segments(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes
+        .split_inclusive(|&byte| byte == 0)
+        .filter(|frame| !matches!(frame, [0]))
+}
+
+// Tags of the public output segments, written by `nexus_rt::write_public_output` and
+// `nexus_rt::write_public_output_named`.
+const UNNAMED_SEGMENT: u8 = 0;
+const NAMED_SEGMENT: u8 = 1;
+
+/// Decode the name of a segment, or `None` for an unnamed segment.
+///
+/// Segments start with a tag, followed by the name for named segments, and then the value.
+fn segment_name(segment: &[u8]) -> Result<Option<String>, IOError> {
+    match postcard::from_bytes_cobs::<u8>(&mut segment.to_vec())? {
+        UNNAMED_SEGMENT => Ok(None),
+        NAMED_SEGMENT => {
+            let (_, name) = postcard::from_bytes_cobs::<(u8, String)>(&mut segment.to_vec())?;
+            Ok(Some(name))
+        }
+        _ => Err(postcard::Error::DeserializeBadEnum.into()),
+    }
+}
+
+/// Decode the value of a segment, whether it is named or not.
+fn segment_value<U: DeserializeOwned>(segment: &[u8]) -> Result<U, IOError> {
+    let mut segment = segment.to_vec();
+    match postcard::from_bytes_cobs::<u8>(&mut segment.clone())? {
+        UNNAMED_SEGMENT => {
+            let (_, value) = postcard::from_bytes_cobs::<(u8, U)>(&mut segment)?;
+            Ok(value)
+        }
+        NAMED_SEGMENT => {
+            let (_, _, value) = postcard::from_bytes_cobs::<(u8, String, U)>(&mut segment)?;
+            Ok(value)
+        }
+        _ => Err(postcard::Error::DeserializeBadEnum.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Encode segments as the runtime does, padding each one to a word boundary.
+    fn encode<T: Serialize>(segments: &[(Option<&str>, T)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (name, value) in segments {
+            let mut segment = match name {
+                None => postcard::to_stdvec_cobs(&(UNNAMED_SEGMENT, value)),
+                Some(name) => postcard::to_stdvec_cobs(&(NAMED_SEGMENT, name, value)),
+            }
+            .unwrap();
+            segment.resize(segment.len().next_multiple_of(WORD_SIZE), 0);
+            bytes.extend(segment);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_public_output_segments() {
+        let bytes = encode(&[(None, 1u32), (Some("sum"), 300), (None, 0)]);
+        let segments: Vec<&[u8]> = public_output_segments(&bytes).collect();
+        assert_eq!(segments.len(), 3);
+
+        assert_eq!(segment_name(segments[0]).unwrap(), None);
+        assert_eq!(segment_value::<u32>(segments[0]).unwrap(), 1);
+        assert_eq!(segment_name(segments[1]).unwrap().as_deref(), Some("sum"));
+        assert_eq!(segment_value::<u32>(segments[1]).unwrap(), 300);
+        assert_eq!(segment_name(segments[2]).unwrap(), None);
+        assert_eq!(segment_value::<u32>(segments[2]).unwrap(), 0);
+
+        assert_eq!(public_output_segments(&[]).count(), 0);
+        assert_eq!(public_output_segments(&[0, 0, 0, 0]).count(), 0);
+    }
+
+    #[test]
+    fn test_unnamed_string_segment_is_not_named() {
+        // An unnamed string has the same encoding as a name, only the tag tells them apart.
+        let bytes = encode(&[
+            (None, "sum".to_string()),
+            (Some("sum"), "value".to_string()),
+        ]);
+        let segments: Vec<&[u8]> = public_output_segments(&bytes).collect();
+        assert_eq!(segments.len(), 2);
+
+        assert_eq!(segment_name(segments[0]).unwrap(), None);
+        assert_eq!(segment_value::<String>(segments[0]).unwrap(), "sum");
+        assert_eq!(segment_name(segments[1]).unwrap().as_deref(), Some("sum"));
+        assert_eq!(segment_value::<String>(segments[1]).unwrap(), "value");
+    }
+
+    #[test]
+    fn test_invalid_segment_tag() {
+        let mut segment = postcard::to_stdvec_cobs(&(2u8, 1u32)).unwrap();
+        segment.resize(segment.len().next_multiple_of(WORD_SIZE), 0);
+        let segment = public_output_segments(&segment).next().unwrap();
+
+        assert!(segment_name(segment).is_err());
+        assert!(segment_value::<u32>(segment).is_err());
+    }
+}
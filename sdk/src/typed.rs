@@ -0,0 +1,72 @@
+use std::marker::PhantomData;
+
+use crate::compile::Compile;
+use crate::error::IOError;
+use crate::traits::*;
+
+pub use nexus_common::interface::Interface;
+
+/// A prover for a guest program with a typed I/O [`Interface`].
+///
+/// The inputs and the output are checked against the interface at compile time, so the host cannot disagree
+/// with a guest declared with `#[nexus_rt::main(interface = ...)]` on the same interface.
+pub struct Typed<I: Interface, P: Prover> {
+    prover: P,
+    _interface: PhantomData<I>,
+}
+
+impl<I: Interface, P: Prover> Typed<I, P>
+where
+    P::View: Viewable,
+    <P as Prover>::Error: From<IOError>,
+{
+    /// Wrap a proving instance for a guest program with the interface `I`.
+    pub fn new(prover: P) -> Self {
+        Self {
+            prover,
+            _interface: PhantomData,
+        }
+    }
+
+    /// Construct a new proving instance through dynamic compilation (see [`compile`](crate::compile)).
+    pub fn compile(compiler: &mut impl Compile) -> Result<Self, <P as Prover>::Error>
+    where
+        P: ByGuestCompilation,
+    {
+        Ok(Self::new(P::compile(compiler)?))
+    }
+
+    /// Get the underlying proving instance.
+    pub fn prover(&self) -> &P {
+        &self.prover
+    }
+
+    /// Unwrap the underlying proving instance.
+    pub fn into_prover(self) -> P {
+        self.prover
+    }
+
+    /// Run the zkVM and return the public output, along with a view of the execution output.
+    pub fn run(
+        &self,
+        private_input: &I::PrivateInput,
+        public_input: &I::PublicInput,
+    ) -> Result<(I::PublicOutput, P::View), <P as Prover>::Error> {
+        let view = self.prover.run_with_input(private_input, public_input)?;
+        let output = view.public_output::<I::PublicOutput>()?;
+
+        Ok((output, view))
+    }
+
+    /// Run the zkVM and return the public output and a verifiable proof, along with a view of the execution output.
+    pub fn prove(
+        self,
+        private_input: &I::PrivateInput,
+        public_input: &I::PublicInput,
+    ) -> Result<(I::PublicOutput, P::View, P::Proof), <P as Prover>::Error> {
+        let (view, proof) = self.prover.prove_with_input(private_input, public_input)?;
+        let output = view.public_output::<I::PublicOutput>()?;
+
+        Ok((output, view, proof))
+    }
+}
@@ -0,0 +1,345 @@
+#!/usr/bin/env python3
+"""Generates the vendored RV32IM conformance subset.
+
+Each test exercises a single instruction over a fixed set of operands and stores every result in
+the signature region, following the riscv-arch-test conventions (see README.md). The reference
+signatures are computed here from the ISA semantics, independently of the Nexus VM.
+
+Usage: python3 generate.py [--build]
+
+Without `--build`, only the assembly sources and reference outputs are written. With `--build`,
+the sources are also assembled with `llvm-mc` and linked with `ld.lld` (or `rust-lld -flavor gnu`)
+against `model/link.ld`.
+"""
+
+import os
+import shutil
+import subprocess
+import sys
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+SUITE = os.path.join(HERE, "rv32i_m")
+
+MASK = 0xFFFFFFFF
+VALUES = [0x0, 0x1, 0xFFFFFFFF, 0x7FFFFFFF, 0x80000000, 0x12345678, 0xFEDCBA98, 0xFFFFFFF9]
+IMMEDIATES = [0, 1, -1, 0x555, 2047, -2048]
+SHIFTS = [0, 1, 4, 15, 16, 31]
+FILL = 0xDEADBEEF
+
+
+def signed(x):
+    x &= MASK
+    return x - (1 << 32) if x & 0x80000000 else x
+
+
+def u(x):
+    return x & MASK
+
+
+def div(a, b):
+    a, b = signed(a), signed(b)
+    if b == 0:
+        return MASK
+    if a == -(1 << 31) and b == -1:
+        return u(a)
+    q = abs(a) // abs(b)
+    return u(q if (a < 0) == (b < 0) else -q)
+
+
+def rem(a, b):
+    sa, sb = signed(a), signed(b)
+    if sb == 0:
+        return u(a)
+    if sa == -(1 << 31) and sb == -1:
+        return 0
+    r = abs(sa) % abs(sb)
+    return u(-r if sa < 0 else r)
+
+
+R_TYPE = {
+    "add": lambda a, b: u(a + b),
+    "sub": lambda a, b: u(a - b),
+    "and": lambda a, b: a & b,
+    "or": lambda a, b: a | b,
+    "xor": lambda a, b: a ^ b,
+    "sll": lambda a, b: u(a << (b & 31)),
+    "srl": lambda a, b: a >> (b & 31),
+    "sra": lambda a, b: u(signed(a) >> (b & 31)),
+    "slt": lambda a, b: int(signed(a) < signed(b)),
+    "sltu": lambda a, b: int(a < b),
+}
+
+M_TYPE = {
+    "mul": lambda a, b: u(a * b),
+    "mulh": lambda a, b: u((signed(a) * signed(b)) >> 32),
+    "mulhsu": lambda a, b: u((signed(a) * b) >> 32),
+    "mulhu": lambda a, b: u((a * b) >> 32),
+    "div": div,
+    "divu": lambda a, b: MASK if b == 0 else a // b,
+    "rem": rem,
+    "remu": lambda a, b: a if b == 0 else a % b,
+}
+
+I_TYPE = {
+    "addi": lambda a, i: u(a + i),
+    "andi": lambda a, i: a & u(i),
+    "ori": lambda a, i: a | u(i),
+    "xori": lambda a, i: a ^ u(i),
+    "slti": lambda a, i: int(signed(a) < i),
+    "sltiu": lambda a, i: int(a < u(i)),
+}
+
+SHIFT_I_TYPE = {
+    "slli": lambda a, s: u(a << s),
+    "srli": lambda a, s: a >> s,
+    "srai": lambda a, s: u(signed(a) >> s),
+}
+
+BRANCHES = {
+    "beq": lambda a, b: a == b,
+    "bne": lambda a, b: a != b,
+    "blt": lambda a, b: signed(a) < signed(b),
+    "bge": lambda a, b: signed(a) >= signed(b),
+    "bltu": lambda a, b: a < b,
+    "bgeu": lambda a, b: a >= b,
+}
+
+LOADS = {
+    "lb": (1, True),
+    "lbu": (1, False),
+    "lh": (2, True),
+    "lhu": (2, False),
+    "lw": (4, False),
+}
+
+STORES = {"sb": 1, "sh": 2, "sw": 4}
+
+# Scratch memory for load and store tests, laid out after the signature.
+SCRATCH = [0x8899AABB, 0x00112233, 0xF0E1D2C3, 0x7F80017E]
+
+
+class Test:
+    def __init__(self):
+        self.body = []
+        self.signature = []
+
+    def emit(self, line):
+        self.body.append("    " + line)
+
+    def label(self, name):
+        self.body.append(name + ":")
+
+    def store(self, reg, value):
+        """Stores `reg`, whose expected value is `value`, in the next signature word."""
+        offset = len(self.signature) * 4 % 2048
+        if len(self.signature) > 0 and offset == 0:
+            self.emit("addi x1, x1, 2047")
+            self.emit("addi x1, x1, 1")
+        self.emit(f"sw {reg}, {offset}(x1)")
+        self.signature.append(u(value))
+
+    def source(self, name):
+        words = len(self.signature) + (-len(self.signature)) % 4
+        lines = [
+            f"# {name}: generated by tests/arch-tests/generate.py, do not edit.",
+            "    .section .text.init",
+            "    .globl rvtest_entry_point",
+            "rvtest_entry_point:",
+            "    la x1, begin_signature",
+            *self.body,
+            "    # RVMODEL_HALT",
+            "    li a0, 0",
+            "    li a7, 0x201",
+            "    ecall",
+            "",
+            "    .data",
+            "    .align 4",
+            "    .globl begin_signature",
+            "begin_signature:",
+            f"    .fill {words}, 4, {FILL:#x}",
+            "    .globl end_signature",
+            "end_signature:",
+            "scratch:",
+            *(f"    .word {w:#010x}" for w in SCRATCH),
+            "",
+        ]
+        return "\n".join(lines)
+
+    def reference(self):
+        words = self.signature + [FILL] * ((-len(self.signature)) % 4)
+        return "".join(f"{w:08x}\n" for w in words)
+
+
+def r_type(op, f):
+    t = Test()
+    for a in VALUES:
+        for b in VALUES:
+            t.emit(f"li x2, {a:#x}")
+            t.emit(f"li x3, {b:#x}")
+            t.emit(f"{op} x4, x2, x3")
+            t.store("x4", f(a, b))
+    return t
+
+
+def i_type(op, f, immediates):
+    t = Test()
+    for a in VALUES:
+        for i in immediates:
+            t.emit(f"li x2, {a:#x}")
+            t.emit(f"{op} x4, x2, {i}")
+            t.store("x4", f(a, i))
+    return t
+
+
+def upper(op):
+    t = Test()
+    for imm in [0x0, 0x1, 0x80000, 0xFFFFF, 0x12345]:
+        if op == "lui":
+            t.emit(f"lui x4, {imm:#x}")
+            t.store("x4", imm << 12)
+        else:
+            # The result depends on the pc, so store its offset from the instruction address.
+            n = len(t.signature)
+            t.label(f"auipc_{n}")
+            t.emit(f"auipc x4, {imm:#x}")
+            t.emit(f"la x5, auipc_{n}")
+            t.emit("sub x4, x4, x5")
+            t.store("x4", imm << 12)
+    return t
+
+
+def branch(op, f):
+    t = Test()
+    for a in VALUES:
+        for b in VALUES:
+            n = len(t.signature)
+            t.emit(f"li x2, {a:#x}")
+            t.emit(f"li x3, {b:#x}")
+            t.emit("li x4, 1")
+            t.emit(f"{op} x2, x3, {op}_{n}")
+            t.emit("li x4, 0")
+            t.label(f"{op}_{n}")
+            t.store("x4", int(f(a, b)))
+    return t
+
+
+def jumps(op):
+    t = Test()
+    for n in range(4):
+        # Store the link offset from the jump, and a marker that is only set if the jump fell through.
+        t.label(f"{op}_src_{n}")
+        t.emit("li x5, 1")
+        if op == "jal":
+            t.emit(f"jal x4, {op}_dst_{n}")
+        else:
+            t.emit(f"la x6, {op}_dst_{n}")
+            t.label(f"{op}_jump_{n}")
+            t.emit("jalr x4, 0(x6)")
+        t.emit("li x5, 0")
+        t.label(f"{op}_dst_{n}")
+        jump = f"{op}_src_{n}" if op == "jal" else f"{op}_jump_{n}"
+        offset = 8 if op == "jal" else 4
+        t.emit(f"la x6, {jump}")
+        t.emit("sub x4, x4, x6")
+        t.store("x4", offset if op == "jal" else 4)
+        t.store("x5", 1)
+    # The lowest bit of the jalr target is cleared.
+    if op == "jalr":
+        t.emit("la x6, jalr_odd")
+        t.emit("jalr x0, 1(x6)")
+        t.label("jalr_odd")
+        t.emit("li x5, 1")
+        t.store("x5", 1)
+    return t
+
+
+def load_bytes():
+    return b"".join(w.to_bytes(4, "little") for w in SCRATCH)
+
+
+def loads(op, size, sign):
+    t = Test()
+    memory = load_bytes()
+    t.emit("la x2, scratch")
+    for offset in range(0, len(memory), size):
+        t.emit(f"{op} x4, {offset}(x2)")
+        value = int.from_bytes(memory[offset : offset + size], "little", signed=sign)
+        t.store("x4", value)
+    return t
+
+
+def stores(op, size):
+    t = Test()
+    memory = bytearray(load_bytes())
+    t.emit("la x2, scratch")
+    values = [0x11223344, 0xFFFFFFFF, 0x80000000, 0x0000007F, 0xCAFEBABE]
+    for i, offset in enumerate(range(0, len(memory), size)):
+        value = values[i % len(values)]
+        t.emit(f"li x3, {value:#x}")
+        t.emit(f"{op} x3, {offset}(x2)")
+        memory[offset : offset + size] = (value & ((1 << (8 * size)) - 1)).to_bytes(size, "little")
+    for offset in range(0, len(memory), 4):
+        t.emit(f"lw x4, {offset}(x2)")
+        t.store("x4", int.from_bytes(memory[offset : offset + 4], "little"))
+    return t
+
+
+def tests():
+    for op, f in R_TYPE.items():
+        yield "I", op, r_type(op, f)
+    for op, f in I_TYPE.items():
+        yield "I", op, i_type(op, f, IMMEDIATES)
+    for op, f in SHIFT_I_TYPE.items():
+        yield "I", op, i_type(op, f, SHIFTS)
+    for op in ["lui", "auipc"]:
+        yield "I", op, upper(op)
+    for op, f in BRANCHES.items():
+        yield "I", op, branch(op, f)
+    for op in ["jal", "jalr"]:
+        yield "I", op, jumps(op)
+    for op, (size, sign) in LOADS.items():
+        yield "I", op, loads(op, size, sign)
+    for op, size in STORES.items():
+        yield "I", op, stores(op, size)
+    for op, f in M_TYPE.items():
+        yield "M", op, r_type(op, f)
+
+
+def linker():
+    for candidate in ["ld.lld", "rust-lld"]:
+        path = shutil.which(candidate)
+        if path:
+            return [path] if candidate == "ld.lld" else [path, "-flavor", "gnu"]
+    sys.exit("neither ld.lld nor rust-lld found in PATH")
+
+
+def build(source, elf):
+    obj = elf + ".o"
+    subprocess.run(
+        ["llvm-mc", "-triple=riscv32", "-mattr=+m,-relax,-c", "-filetype=obj", source, "-o", obj],
+        check=True,
+    )
+    subprocess.run(
+        [*linker(), "-T", os.path.join(HERE, "model", "link.ld"), "--no-relax", obj, "-o", elf],
+        check=True,
+    )
+    os.remove(obj)
+
+
+def main():
+    do_build = "--build" in sys.argv[1:]
+    for extension, op, test in tests():
+        directory = os.path.join(SUITE, extension)
+        os.makedirs(directory, exist_ok=True)
+        name = f"{op}-01"
+        source = os.path.join(directory, name + ".S")
+        with open(source, "w") as f:
+            f.write(test.source(name))
+        with open(os.path.join(directory, name + ".reference_output"), "w") as f:
+            f.write(test.reference())
+        if do_build:
+            build(source, os.path.join(directory, name + ".elf"))
+
+
+if __name__ == "__main__":
+    main()
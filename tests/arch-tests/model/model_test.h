@@ -0,0 +1,42 @@
+// riscv-arch-test target model for the Nexus zkVM.
+//
+// The VM has no privileged architecture, so tests are built without trap handlers and halt through
+// the Nexus exit syscall. The signature lives in `.data` and is read back from memory by the
+// conformance runner in `tests/testing-framework`.
+
+#ifndef _NEXUS_MODEL_TEST_H
+#define _NEXUS_MODEL_TEST_H
+
+#define RVMODEL_DATA_SECTION
+
+#define RVMODEL_BOOT
+
+// Exit with status 0, see `SyscallCode::Exit`.
+#define RVMODEL_HALT                                                          \
+  li a0, 0;                                                                   \
+  li a7, 0x201;                                                               \
+  ecall;
+
+#define RVMODEL_DATA_BEGIN                                                    \
+  .align 4;                                                                   \
+  .global begin_signature;                                                    \
+  begin_signature:
+
+#define RVMODEL_DATA_END                                                      \
+  .align 4;                                                                   \
+  .global end_signature;                                                      \
+  end_signature:
+
+#define RVMODEL_IO_INIT
+#define RVMODEL_IO_WRITE_STR(_R, _STR)
+#define RVMODEL_IO_CHECK()
+#define RVMODEL_IO_ASSERT_GPR_EQ(_S, _R, _I)
+#define RVMODEL_IO_ASSERT_SFPR_EQ(_F, _R, _I)
+#define RVMODEL_IO_ASSERT_DFPR_EQ(_D, _R, _I)
+
+#define RVMODEL_SET_MSW_INT
+#define RVMODEL_CLEAR_MSW_INT
+#define RVMODEL_CLEAR_MTIMER_INT
+#define RVMODEL_CLEAR_MEXT_INT
+
+#endif // _NEXUS_MODEL_TEST_H
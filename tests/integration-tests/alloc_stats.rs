@@ -0,0 +1,17 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::{boxed::Box, vec, vec::Vec};
+
+#[nexus_rt::main]
+fn main() {
+    let mut boxes = Vec::new();
+    for i in 0..16u32 {
+        boxes.push(Box::new(i));
+    }
+    let buffer = vec![7u8; 4096];
+    core::hint::black_box((&boxes, &buffer));
+
+    assert_eq!(boxes.iter().map(|b| **b).sum::<u32>(), 120);
+    assert_eq!(buffer.iter().map(|&b| b as u32).sum::<u32>(), 7 * 4096);
+}
@@ -0,0 +1,19 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+#[nexus_rt::main]
+fn main() {
+    // Reserve heap memory that is never accessed, then use memory allocated past it.
+    let reserved: Vec<u32> = Vec::with_capacity(16 * 1024);
+    core::hint::black_box(&reserved);
+
+    let mut values = Vec::new();
+    for i in 0..256 {
+        values.push(i);
+    }
+    core::hint::black_box(&values);
+
+    assert_eq!(values.iter().sum::<u32>(), 255 * 128);
+}
@@ -0,0 +1,27 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+#[nexus_rt::main]
+fn main() {
+    for round in 0..64u32 {
+        let mut values = Vec::new();
+        for i in 0..256 {
+            values.push(round + i);
+        }
+
+        let mut names = BTreeMap::new();
+        for i in 0..16u32 {
+            let mut name = String::new();
+            for _ in 0..=i {
+                name.push('a');
+            }
+            names.insert(i, name);
+        }
+        core::hint::black_box((&values, &names));
+
+        assert_eq!(values.iter().sum::<u32>(), 256 * round + 255 * 128);
+        assert_eq!(names.values().map(String::len).sum::<usize>(), 136);
+    }
+}
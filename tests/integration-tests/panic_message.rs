@@ -0,0 +1,9 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+#[nexus_rt::main]
+fn main() {
+    let value = core::hint::black_box(42u32);
+    if value == 42 {
+        panic!("unexpected value {value}");
+    }
+}
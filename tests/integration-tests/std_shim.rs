@@ -0,0 +1,23 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::string::String;
+
+use nexus_rt::shim::{
+    env, fs,
+    io::{self, Read, Write},
+};
+
+#[nexus_rt::main]
+fn main() {
+    let mut greeting = String::new();
+    fs::File::open("greeting.txt")
+        .unwrap()
+        .read_to_string(&mut greeting)
+        .unwrap();
+    assert!(fs::read("missing.txt").is_err());
+    assert!(env::args().next().is_none());
+    assert!(env::var("HOME").is_err());
+
+    writeln!(io::stdout(), "{greeting}, World!").unwrap();
+}
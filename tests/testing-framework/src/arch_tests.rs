@@ -0,0 +1,206 @@
+//! Conformance runner for the RISC-V architectural tests.
+//!
+//! See `tests/arch-tests/README.md` for how the suite is built and vendored.
+
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use nexus_common::constants::WORD_SIZE;
+use nexus_vm::elf::ElfFile;
+use nexus_vm::emulator::{Emulator, HarvardEmulator};
+use nexus_vm::error::VMError;
+use nexus_vm::memory::{LoadOp, MemAccessSize, MemoryProcessor};
+use nexus_vm::trace::k_trace;
+use nexus_vm_prover::{prove, verify};
+
+const SUITE_PATH: &str = "../arch-tests/rv32i_m";
+const EXTENSIONS: &[&str] = &["I", "M"];
+/// Tests the prover is known to fail, since no chip constrains their instruction. These must fail to
+/// prove, so that an exclusion is dropped here once the instruction is supported.
+const PROVER_EXCLUSIONS: &[&str] = &[
+    "M/div-01",
+    "M/divu-01",
+    "M/mul-01",
+    "M/mulh-01",
+    "M/mulhsu-01",
+    "M/mulhu-01",
+    "M/rem-01",
+    "M/remu-01",
+];
+const K: usize = 1;
+
+struct ArchTest {
+    name: String,
+    elf: PathBuf,
+    reference: PathBuf,
+}
+
+/// Finds all vendored tests, i.e. ELF files with a matching reference signature.
+fn discover(suite: &Path) -> Vec<ArchTest> {
+    let mut tests = Vec::new();
+
+    for &extension in EXTENSIONS {
+        let Ok(entries) = fs::read_dir(suite.join(extension)) else {
+            continue;
+        };
+        for path in entries.map(|entry| entry.unwrap().path()) {
+            if path.extension().is_some_and(|ext| ext == "elf") {
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                tests.push(ArchTest {
+                    name: format!("{extension}/{name}"),
+                    reference: path.with_extension("reference_output"),
+                    elf: path,
+                });
+            }
+        }
+    }
+
+    tests.sort_by(|a, b| a.name.cmp(&b.name));
+    tests
+}
+
+/// Parses a reference signature: one hex-encoded 32-bit word per line.
+fn parse_reference_signature(text: &str) -> Result<Vec<u32>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| u32::from_str_radix(line, 16).map_err(|e| format!("{line:?}: {e}")))
+        .collect()
+}
+
+fn symbol(data: &[u8], name: &str) -> Result<u32, String> {
+    ElfFile::symbol_address(data, name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("missing symbol {name}"))
+}
+
+/// Runs the test on the emulator and checks its signature against `reference`.
+fn emulate(data: &[u8], reference: &[u32]) -> Result<(), String> {
+    let elf = ElfFile::from_bytes(data).map_err(|e| e.to_string())?;
+    let begin = symbol(data, "begin_signature")?;
+    let end = symbol(data, "end_signature")?;
+
+    let mut emulator = HarvardEmulator::from_elf(&elf, &[], &[]);
+    match emulator.execute(false) {
+        Err(VMError::VMExited(0)) => {}
+        Err(e) => return Err(format!("emulation failed: {e}")),
+        Ok(_) => unreachable!(),
+    }
+
+    let signature = (begin..end)
+        .step_by(WORD_SIZE)
+        .map(
+            |address| match emulator.data_memory.read(address, MemAccessSize::Word) {
+                Ok(LoadOp::Op(_, _, value)) => Ok(value),
+                Err(e) => Err(format!("reading signature at {address:#x}: {e}")),
+            },
+        )
+        .collect::<Result<Vec<u32>, String>>()?;
+
+    if signature.len() != reference.len() {
+        return Err(format!(
+            "signature has {} words, reference has {}",
+            signature.len(),
+            reference.len()
+        ));
+    }
+    if let Some(i) = (0..reference.len()).find(|&i| signature[i] != reference[i]) {
+        return Err(format!(
+            "signature mismatch at {:#x}: expected {:08x}, got {:08x}",
+            begin as usize + i * WORD_SIZE,
+            reference[i],
+            signature[i]
+        ));
+    }
+
+    Ok(())
+}
+
+/// Proves and verifies the execution of the test. Unsupported instructions make the prover panic,
+/// which is reported as a failure rather than aborting the whole run.
+fn prove_and_verify(elf: ElfFile) -> Result<(), String> {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let (view, trace) = k_trace(elf, &[], &[], &[], K).map_err(|e| e.to_string())?;
+        let proof = prove(&trace, &view).map_err(|e| e.to_string())?;
+        verify(proof, &view).map_err(|e| e.to_string())
+    }));
+
+    result.unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| panic.downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic");
+        Err(format!("prover panicked: {message}"))
+    })
+}
+
+fn status(result: &Result<(), String>) -> &'static str {
+    if result.is_ok() {
+        "pass"
+    } else {
+        "FAIL"
+    }
+}
+
+#[test]
+fn test_arch_conformance() {
+    // A build of the official suite can be run in place of the vendored subset.
+    let suite = std::env::var_os("ARCH_TEST_SUITE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(SUITE_PATH));
+    let tests = discover(&suite);
+    assert!(
+        !tests.is_empty(),
+        "no architectural tests found under {}",
+        suite.display()
+    );
+
+    let mut failures = Vec::new();
+    for test in &tests {
+        let data = fs::read(&test.elf).expect("failed to read test ELF");
+        let reference = fs::read_to_string(&test.reference)
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_reference_signature(&text));
+
+        let emulated = reference.and_then(|reference| emulate(&data, &reference));
+        let proved = ElfFile::from_bytes(&data)
+            .map_err(|e| e.to_string())
+            .and_then(prove_and_verify);
+
+        println!(
+            "{:<24} emulator: {:<4}  prover: {}",
+            test.name,
+            status(&emulated),
+            status(&proved)
+        );
+
+        if let Err(e) = emulated {
+            failures.push(format!("{} (emulator): {e}", test.name));
+        }
+        match (proved, PROVER_EXCLUSIONS.contains(&test.name.as_str())) {
+            (Err(e), false) => failures.push(format!("{} (prover): {e}", test.name)),
+            (Ok(()), true) => failures.push(format!(
+                "{} (prover): proved, but is listed in PROVER_EXCLUSIONS",
+                test.name
+            )),
+            _ => {}
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "architectural test failures:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_parse_reference_signature() {
+    assert_eq!(
+        parse_reference_signature("00000000\nDEADBEEF\n\nffffffff\n"),
+        Ok(vec![0, 0xdead_beef, u32::MAX])
+    );
+    assert!(parse_reference_signature("not a word").is_err());
+}
@@ -1,10 +1,15 @@
+#[cfg(test)]
+mod arch_tests;
+
 #[cfg(test)]
 mod test {
     use nexus_common::word_align;
     use nexus_common_testing::emulator::{
-        compile_multi, emulate, parse_output, EmulatorType, IOArgs, Input, Output,
+        compile_multi, compile_multi_with_features, emulate, parse_output, serialize_output,
+        EmulatorType, IOArgs, Input, Output,
     };
     use nexus_common_testing::program_trace;
+    use nexus_sdk::Viewable;
     use nexus_vm::elf::ElfFile;
     use nexus_vm::emulator::InternalView;
     use nexus_vm::trace::{k_trace, k_trace_direct};
@@ -48,9 +53,8 @@ mod test {
         private_input_bytes.resize(padded_len, 0x00);
 
         // Serialize expected output
-        let mut expected_output_bytes = if let Some(mut expected) = io_args.expected_output.clone()
-        {
-            to_allocvec_cobs(&mut expected).expect("Failed to serialize expected output")
+        let mut expected_output_bytes = if let Some(expected) = io_args.expected_output.clone() {
+            serialize_output(&expected)
         } else {
             Vec::new()
         };
@@ -126,7 +130,7 @@ mod test {
 
         let mut public_input_bytes = to_allocvec_cobs(&mut 512u32).unwrap();
         let mut private_input_bytes = to_allocvec_cobs(&mut 2u32).unwrap();
-        let mut expected_output_bytes = to_allocvec_cobs(&mut 1024u32).unwrap();
+        let mut expected_output_bytes = serialize_output(&1024u32);
 
         let padded_len = word_align!(public_input_bytes.len());
         public_input_bytes.resize(padded_len, 0);
@@ -158,6 +162,30 @@ mod test {
         verify(proof, &view).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_prove_verify_expected_io() {
+        use nexus_sdk::{stwo::seq::Stwo, KnownExitCodes, Local, Prover, Verifiable};
+
+        let elfs = compile_multi(
+            "examples/src/bin/input_output",
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+        let exit_code = KnownExitCodes::ExitSuccess as u32;
+
+        let prover: Stwo<Local> = Stwo::new(&elfs[0]).unwrap();
+        let (view, proof) = prover.prove_with_input::<u32, u32>(&2, &512).unwrap();
+        assert_eq!(view.public_output::<u32>().unwrap(), 1024);
+
+        proof
+            .verify_expected::<u32, u32>(&512, exit_code, &1024, &elfs[0], &[])
+            .unwrap();
+        assert!(proof
+            .verify_expected::<u32, u32>(&512, exit_code, &1025, &elfs[0], &[])
+            .is_err());
+    }
+
     #[test]
     #[serial]
     fn test_emulate_fact() {
@@ -401,8 +429,7 @@ mod test {
 
         let mut public_input_bytes = to_allocvec_cobs(&mut (true, 1u8, 2u16, 3u32, 4u64)).unwrap();
         let mut private_input_bytes = to_allocvec_cobs(&mut (true, 1u8, 2u16, 3u32, 4u64)).unwrap();
-        let mut expected_output_bytes =
-            to_allocvec_cobs(&mut (true, 2u8, 4u16, 6u32, 8u64)).unwrap();
+        let mut expected_output_bytes = serialize_output(&(true, 2u8, 4u16, 6u32, 8u64));
 
         let padded_len = word_align!(public_input_bytes.len());
         public_input_bytes.resize(padded_len, 0);
@@ -613,6 +640,131 @@ mod test {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_alloc_stats() {
+        let elfs = compile_multi_with_features(
+            "tests/integration-tests/alloc_stats",
+            &["alloc-stats"],
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        for elf in elfs {
+            let (view, _) = k_trace(elf, &[], &[], &[], K).expect("error generating trace");
+            assert_eq!(view.get_exit_code()[0].value, 0, "guest panicked");
+
+            let stats = view
+                .view_alloc_stats()
+                .expect("guest reported no statistics");
+            // 16 boxes, the buffer and at least one allocation for the vector of boxes.
+            assert!(stats.allocations >= 18, "{stats}");
+            assert_eq!(stats.deallocations, stats.allocations, "{stats}");
+            assert!(!stats.has_leaks(), "{stats}");
+            assert!(!stats.heap_overflow, "{stats}");
+            assert_eq!(stats.largest_allocations[0], 4096, "{stats}");
+            assert!(stats.heap_start > 0, "{stats}");
+            assert!(stats.peak_heap_usage() >= 4096 + 16 * 4, "{stats}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_panic_message() {
+        let elfs = compile_multi_with_features(
+            "tests/integration-tests/panic_message",
+            &["panic-message"],
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        for elf in elfs {
+            let (view, _) = k_trace(elf, &[], &[], &[], K).expect("error generating trace");
+            assert_eq!(view.get_exit_code()[0].value, 1, "guest did not panic");
+
+            let logs = view.logs().expect("guest produced no logs");
+            assert!(
+                logs.iter()
+                    .any(|log| log.contains("panicked at src/main.rs:7:9")
+                        && log.contains("unexpected value 42")),
+                "{logs:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_std_shim() {
+        let elfs = compile_multi_with_features(
+            "tests/integration-tests/std_shim",
+            &["std-shim"],
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        let mut files = vec![("greeting.txt".to_string(), b"Hello".to_vec())];
+        let mut private_input_bytes = to_allocvec_cobs(&mut files).unwrap();
+        let padded_len = word_align!(private_input_bytes.len());
+        private_input_bytes.resize(padded_len, 0);
+
+        for elf in elfs {
+            let (view, _) =
+                k_trace(elf, &[], &[], &private_input_bytes, K).expect("error generating trace");
+            assert_eq!(view.get_exit_code()[0].value, 0, "guest panicked");
+
+            let logs = view.logs().expect("guest produced no logs");
+            assert_eq!(logs.concat(), "Hello, World!\n");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_free_list() {
+        let elfs = compile_multi_with_features(
+            "tests/integration-tests/free_list",
+            &["free-list", "alloc-stats"],
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        for elf in elfs {
+            let (view, _) = k_trace(elf, &[], &[], &[], K).expect("error generating trace");
+            assert_eq!(view.get_exit_code()[0].value, 0, "guest panicked");
+
+            let stats = view
+                .view_alloc_stats()
+                .expect("guest reported no statistics");
+            assert_eq!(stats.deallocations, stats.allocations, "{stats}");
+            assert!(!stats.has_leaks(), "{stats}");
+            assert!(!stats.heap_overflow, "{stats}");
+            // Every round allocates at least the 1 KiB vector, which must be reused by later rounds.
+            assert!(stats.allocated_bytes >= 64 * 1024, "{stats}");
+            assert!(
+                stats.peak_heap_usage() < stats.allocated_bytes / 4,
+                "{stats}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_alloc_untouched() {
+        let elfs = compile_multi(
+            "tests/integration-tests/alloc_untouched",
+            &["-C opt-level=3"],
+            &HOME_PATH,
+        );
+
+        for elf in elfs {
+            let (view, trace) = k_trace(elf, &[], &[], &[], K).expect("error generating trace");
+            assert_eq!(view.get_exit_code()[0].value, 0, "guest panicked");
+
+            // The heap must cover the reserved memory even though it is never accessed.
+            let layout = trace.memory_layout;
+            assert!(layout.heap_end() - layout.heap_start() >= 64 * 1024);
+        }
+    }
+
     #[test]
     #[serial]
     fn test_prove_synthetic_trace() {
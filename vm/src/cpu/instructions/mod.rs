@@ -17,5 +17,9 @@ pub use m::{
 
 pub use nexus_common::cpu::InstructionResult;
 
+// Zicsr extension (counter reads only)
+mod zicsr;
+pub use zicsr::CsrrsInstruction;
+
 // Macro implementations
 pub(crate) mod macros;
@@ -0,0 +1,89 @@
+use crate::{
+    cpu::state::Cpu,
+    memory::{LoadOps, MemoryProcessor, StoreOps},
+    riscv::{Instruction, Register},
+};
+use nexus_common::{
+    cpu::{InstructionResult, Registers},
+    error::MemoryError,
+    riscv::csr::CounterCsr,
+};
+
+/// Reads a counter CSR, i.e. `csrrs rd, csr, x0`.
+///
+/// Unlike the other instructions this one needs the cycle counter of the CPU, which is not exposed
+/// through [`nexus_common::cpu::Processor`], hence the dedicated evaluator operating on [`Cpu`].
+/// The emulator keeps [`Cpu::cycles`] in sync with its global clock before dispatching.
+pub struct CsrrsInstruction {
+    rd: Register,
+    csr: CounterCsr,
+}
+
+impl CsrrsInstruction {
+    fn decode(ins: &Instruction) -> Self {
+        Self {
+            rd: ins.op_a,
+            // The decoder only emits CSRRS for supported counters.
+            csr: CounterCsr::from_address(ins.op_c).expect("Unsupported CSR"),
+        }
+    }
+
+    pub fn evaluator(
+        cpu: &mut Cpu,
+        _: &mut impl MemoryProcessor,
+        ins: &Instruction,
+    ) -> Result<(InstructionResult, (LoadOps, StoreOps)), MemoryError> {
+        let instruction = Self::decode(ins);
+        let value = instruction.csr.read(cpu.cycles);
+        cpu.registers.write(instruction.rd, value);
+
+        Ok((Some(value), (LoadOps::default(), StoreOps::default())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{VariableMemory, RW};
+    use crate::riscv::{BuiltinOpcode, Opcode};
+
+    fn counter_read(rd: u8, csr: CounterCsr) -> Instruction {
+        Instruction::new_ir(Opcode::from(BuiltinOpcode::CSRRS), rd, 0, csr.address())
+    }
+
+    #[test]
+    fn test_csrrs_reads_counters() {
+        let mut cpu = Cpu {
+            cycles: 0x0000_0002_0000_0010,
+            ..Default::default()
+        };
+        let mut memory = VariableMemory::<RW>::default();
+
+        for (csr, expected) in [
+            (CounterCsr::Cycle, 0x10),
+            (CounterCsr::Time, 0x10),
+            (CounterCsr::Instret, 0x10),
+            (CounterCsr::CycleH, 2),
+            (CounterCsr::TimeH, 2),
+            (CounterCsr::InstretH, 2),
+        ] {
+            let (res, _) =
+                CsrrsInstruction::evaluator(&mut cpu, &mut memory, &counter_read(5, csr)).unwrap();
+            assert_eq!(res, Some(expected));
+            assert_eq!(cpu.registers.read(Register::X5), expected);
+        }
+    }
+
+    #[test]
+    fn test_csrrs_to_x0() {
+        let mut cpu = Cpu {
+            cycles: 42,
+            ..Default::default()
+        };
+        let mut memory = VariableMemory::<RW>::default();
+
+        let ins = counter_read(0, CounterCsr::Cycle);
+        CsrrsInstruction::evaluator(&mut cpu, &mut memory, &ins).unwrap();
+        assert_eq!(cpu.registers.read(Register::X0), 0);
+    }
+}
@@ -0,0 +1,4 @@
+// Zicsr extension, restricted to reads of the Zicntr counters
+mod csrrs;
+
+pub use csrrs::CsrrsInstruction;
@@ -130,6 +130,10 @@ pub enum ParserError {
     /// The calculated offset is not within the file.
     #[error("Invalid offset in file")]
     InvalidOffsetInFile,
+
+    /// The I/O schema section contains an unknown or incomplete entry.
+    #[error("invalid I/O schema entry: {0}")]
+    InvalidIoSchema(String),
 }
 
 /// Result type for VM functions that can produce errors
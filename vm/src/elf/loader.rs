@@ -43,7 +43,7 @@
 //! This loader is designed for little-endian RISC-V 32-bit executables and implements
 //! a Harvard architecture model. Ensure your ELF files are compatible with these specifications.
 
-use crate::elf::parser;
+use crate::elf::{parser, schema, IoSchema};
 
 use elf::{endian::LittleEndian, ElfBytes};
 use std::collections::BTreeMap;
@@ -72,6 +72,19 @@ pub struct ElfFile {
 
     /// Nexus-specific metadata embedded in the ELF file.
     pub nexus_metadata: Vec<u32>,
+
+    /// Types of the guest program inputs and output, as declared through the runtime macros.
+    #[serde(default)]
+    pub io_schema: IoSchema,
+
+    /// The address of the `_end` symbol, where the guest allocator starts the heap unless the VM
+    /// places it elsewhere.
+    #[serde(default)]
+    pub heap_start: Option<u32>,
+
+    /// The address of the `__heap_pos` symbol, where the guest allocator keeps the end of the heap.
+    #[serde(default)]
+    pub heap_pos_address: Option<u32>,
 }
 
 impl ElfFile {
@@ -90,6 +103,9 @@ impl ElfFile {
             rom_image,
             ram_image,
             nexus_metadata,
+            io_schema: IoSchema::default(),
+            heap_start: None,
+            heap_pos_address: None,
         }
     }
 
@@ -109,6 +125,12 @@ impl ElfFile {
             .map_err(|_| ParserError::InvalidEntryPointOffset)?;
 
         let parsed_elf_data = parser::parse_segments(&elf, data)?;
+        let io_schema = schema::parse_io_schema(&elf)?;
+        // Stripped binaries have no symbol table, in which case the heap bounds are unknown.
+        let heap_start = parser::parse_symbol_address(&elf, "_end").ok().flatten();
+        let heap_pos_address = parser::parse_symbol_address(&elf, "__heap_pos")
+            .ok()
+            .flatten();
 
         Ok(ElfFile {
             instructions: parsed_elf_data.instructions,
@@ -117,9 +139,21 @@ impl ElfFile {
             rom_image: parsed_elf_data.readonly_memory,
             ram_image: parsed_elf_data.writable_memory,
             nexus_metadata: parsed_elf_data.nexus_metadata,
+            io_schema,
+            heap_start,
+            heap_pos_address,
         })
     }
 
+    /// Returns the address of the symbol `name` in the ELF file `data`, if present.
+    ///
+    /// Symbols are not retained by [`ElfFile`], so this works on the raw bytes it was loaded from.
+    pub fn symbol_address(data: &[u8], name: &str) -> Result<Option<u32>, ParserError> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(data).map_err(ParserError::ELFError)?;
+
+        parser::parse_symbol_address(&elf, name)
+    }
+
     pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, ParserError> {
         let file = File::open(path)?;
         let data: Vec<u8> = std::io::Read::bytes(file)
@@ -190,4 +224,23 @@ mod tests {
             assert_eq!(elf.instructions.len(), *number_of_instruction);
         }
     }
+
+    #[test]
+    fn test_symbol_address() {
+        let data = std::fs::read("test/fib_10.elf").unwrap();
+
+        assert_eq!(
+            ElfFile::symbol_address(&data, "_start").unwrap(),
+            Some(0x1000)
+        );
+        assert_eq!(
+            ElfFile::symbol_address(&data, "main").unwrap(),
+            Some(0x11d8)
+        );
+        assert_eq!(
+            ElfFile::symbol_address(&data, "no_such_symbol").unwrap(),
+            None
+        );
+        assert_eq!(ElfFile::from_bytes(&data).unwrap().heap_start, Some(0x287c));
+    }
 }
@@ -1,7 +1,11 @@
 mod error;
 mod loader;
 mod parser;
+mod schema;
 
 pub use error::ParserError as ElfError;
 pub use loader::ElfFile;
 pub use nexus_common::constants::WORD_SIZE;
+pub use schema::{
+    normalize_type_name, IoSchema, SchemaMismatch, Shape, TypeSchema, IO_SCHEMA_SECTION,
+};
@@ -20,6 +20,7 @@
 //! - `create_allowed_section_map`: Builds a map of allowed ELF sections and their address ranges
 //! - `parse_segment_content`: Processes segment content and populates instruction and memory structures
 //! - `parse_precompile_metadata`: Extracts and validates precompile metadata from ELF symbols
+//! - `parse_symbol_address`: Looks up the address of a named ELF symbol
 //!
 //! # Memory Types
 //!
@@ -319,6 +320,28 @@ impl Ord for PrecompileDescription<'_> {
     }
 }
 
+/// Looks up the virtual address of the symbol `name` in the ELF symbol table.
+///
+/// Returns `None` if no symbol with that name exists.
+pub fn parse_symbol_address(elf: &ElfBytes<LittleEndian>, name: &str) -> Result<Option<u32>> {
+    let symbol_table = elf.symbol_table().map_err(ParserError::ELFError)?;
+    let (symbol_table, symbol_string_table) = symbol_table.ok_or(ParserError::NoSymbolTable)?;
+
+    for symbol in symbol_table {
+        if symbol_string_table.get(symbol.st_name as usize)? != name {
+            continue;
+        }
+
+        let virtual_address: u32 = symbol
+            .st_value
+            .try_into()
+            .map_err(|_| ParserError::InvalidVirtualAddress(symbol.st_value))?;
+        return Ok(Some(virtual_address));
+    }
+
+    Ok(None)
+}
+
 /// Parses the precompile metadata from the ELF file. This function finds all symbols that indicate
 /// pieces of precompile metadata and then ensures that there is a complete contiguous set of unique
 /// precompiles labeled 0 though N-1 via heapification.
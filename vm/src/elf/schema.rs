@@ -0,0 +1,942 @@
+//! Guest I/O Type Schema
+//!
+//! The runtime macros record the types of the public input, the private input and the public
+//! output of a guest program in the `.note.nexus-io-schema` section of its ELF file. Each entry
+//! holds the kind of I/O, the type as written in the guest and the schema of its postcard encoding,
+//! see [`nexus_common::schema`] for the format.
+//!
+//! Host values are checked against the schema by walking them with a [`serde::Serializer`], so
+//! that any type with the same encoding is accepted, e.g. `(&str, &[u8])` for `(String, Vec<u8>)`
+//! or a host struct with the same fields as the guest struct.
+
+use std::fmt::{self, Display};
+
+use elf::{endian::LittleEndian, ElfBytes};
+use nexus_common::schema::{tag, MAX_ENCODED_LEN};
+use serde::{ser, Deserialize, Serialize};
+
+use super::error::{ParserError, Result};
+
+/// The name of the ELF section holding the I/O type schema.
+pub const IO_SCHEMA_SECTION: &str = ".note.nexus-io-schema";
+
+/// The types of the inputs and output of a guest program.
+///
+/// Entries are `None` when the guest does not declare the corresponding I/O through the runtime macros.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoSchema {
+    pub public_input: Option<TypeSchema>,
+    pub private_input: Option<TypeSchema>,
+    pub public_output: Option<TypeSchema>,
+}
+
+/// A type declared by a guest program, with the shape of its encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeSchema {
+    /// The normalized name of the type, see [`normalize_type_name`].
+    pub name: String,
+    pub shape: Shape,
+}
+
+impl IoSchema {
+    /// Decodes the contents of the I/O schema section.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        let mut schema = IoSchema::default();
+
+        loop {
+            // Entries may be separated by zero padding.
+            while let [0, rest @ ..] = bytes {
+                bytes = rest;
+            }
+            if bytes.is_empty() {
+                break;
+            }
+
+            let kind = read_str(&mut bytes)?;
+            let entry = match kind {
+                "public_input" => &mut schema.public_input,
+                "private_input" => &mut schema.private_input,
+                "public_output" => &mut schema.public_output,
+                _ => return Err(ParserError::InvalidIoSchema(kind.to_string())),
+            };
+            if entry.is_some() {
+                return Err(ParserError::InvalidIoSchema(format!("duplicate {kind}")));
+            }
+
+            let name = normalize_type_name(read_str(&mut bytes)?);
+            let shape = Shape::decode(&mut bytes, MAX_ENCODED_LEN)
+                .ok_or_else(|| ParserError::InvalidIoSchema(format!("schema of {kind}")))?;
+            *entry = Some(TypeSchema { name, shape });
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns `true` if the guest does not declare any of its I/O types.
+    pub fn is_empty(&self) -> bool {
+        self.public_input.is_none() && self.private_input.is_none() && self.public_output.is_none()
+    }
+}
+
+// Reads a NUL-terminated string.
+fn read_str<'a>(bytes: &mut &'a [u8]) -> Result<&'a str> {
+    let end = bytes
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| ParserError::InvalidIoSchema("unterminated entry".to_string()))?;
+    let s = std::str::from_utf8(&bytes[..end])?;
+    *bytes = &bytes[end + 1..];
+    Ok(s)
+}
+
+impl TypeSchema {
+    /// Checks that `value` is encoded as a value of the type.
+    pub fn check<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), SchemaMismatch> {
+        value.serialize(Checker(&self.shape))
+    }
+}
+
+/// Normalizes a type name so that names written in source and those returned by `std::any::type_name` agree.
+///
+/// Whitespace and lifetimes are removed, and paths are reduced to their last segment, so that
+/// `alloc::vec::Vec<u8>` and `Vec < u8 >` both become `Vec<u8>`. A single space is kept between
+/// keywords and what follows them, as in `&mut u8` or `dyn Any`.
+pub fn normalize_type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    // Start of the path segment being written, dropped if it turns out to be followed by `::`.
+    let mut segment_start = 0;
+
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                // Keep keywords, as in `dyn Trait`, apart from the path that follows them.
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if out.ends_with(is_ident_char) && chars.peek().is_some_and(|c| is_ident_char(*c)) {
+                    out.push(' ');
+                    segment_start = out.len();
+                }
+            }
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                out.truncate(segment_start);
+            }
+            '\'' => {
+                // Skip the lifetime, along with the comma separating it from the next generic argument.
+                while chars.next_if(|c| is_ident_char(*c)).is_some() {}
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                chars.next_if_eq(&',');
+            }
+            _ => {
+                out.push(c);
+                if !is_ident_char(c) {
+                    segment_start = out.len();
+                }
+            }
+        }
+    }
+
+    // Drop the brackets of generics that only had lifetimes.
+    out.replace("<>", "")
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The shape of the postcard encoding of a type.
+///
+/// Structs are tuples of their fields, and a tuple with a single field has the shape of the field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shape {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    Char,
+    Str,
+    Option(Box<Shape>),
+    /// A length-prefixed sequence, such as `Vec<T>` or `[T]`.
+    Seq(Box<Shape>),
+    Map(Box<Shape>, Box<Shape>),
+    Tuple(Vec<Shape>),
+    /// An array, which is encoded as a tuple of its elements.
+    Array(usize, Box<Shape>),
+    /// An enum, given by the shapes of its variants in order.
+    Enum(Vec<Shape>),
+    /// A type whose structure is unknown, which matches any value.
+    Opaque,
+}
+
+impl Shape {
+    // Decodes a schema, nested at most `depth` deep. Each level takes at least a byte, so the depth
+    // of any schema the runtime can record is bounded by its maximum length.
+    fn decode(bytes: &mut &[u8], depth: usize) -> Option<Self> {
+        let depth = depth.checked_sub(1)?;
+        let (&t, rest) = bytes.split_first()?;
+        *bytes = rest;
+
+        let inner = |bytes: &mut &[u8]| Shape::decode(bytes, depth).map(Box::new);
+        Some(match t {
+            tag::UNIT => Shape::Unit,
+            tag::BOOL => Shape::Bool,
+            tag::U8 => Shape::U8,
+            tag::U16 => Shape::U16,
+            tag::U32 => Shape::U32,
+            tag::U64 => Shape::U64,
+            tag::U128 => Shape::U128,
+            tag::I8 => Shape::I8,
+            tag::I16 => Shape::I16,
+            tag::I32 => Shape::I32,
+            tag::I64 => Shape::I64,
+            tag::I128 => Shape::I128,
+            tag::F32 => Shape::F32,
+            tag::F64 => Shape::F64,
+            tag::CHAR => Shape::Char,
+            tag::STR => Shape::Str,
+            tag::OPTION => Shape::Option(inner(bytes)?),
+            tag::SEQ => Shape::Seq(inner(bytes)?),
+            tag::MAP => Shape::Map(inner(bytes)?, inner(bytes)?),
+            tag::ARRAY => Shape::Array(decode_len(bytes)?, inner(bytes)?),
+            tag::TUPLE | tag::ENUM => {
+                let len = decode_len(bytes)?;
+                let parts = (0..len)
+                    .map(|_| Shape::decode(bytes, depth))
+                    .collect::<Option<Vec<_>>>()?;
+                if t == tag::TUPLE {
+                    Shape::Tuple(parts)
+                } else {
+                    Shape::Enum(parts)
+                }
+            }
+            tag::OPAQUE => Shape::Opaque,
+            _ => return None,
+        })
+    }
+}
+
+// Decodes an unsigned LEB128 length.
+fn decode_len(bytes: &mut &[u8]) -> Option<usize> {
+    let mut len = 0usize;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&b, rest) = bytes.split_first()?;
+        *bytes = rest;
+        len |= usize::from(b & 0x7f).checked_shl(shift)?;
+        if b & 0x80 == 0 {
+            return Some(len);
+        }
+    }
+    None
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, parts: &[Shape]| {
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{part}")?;
+            }
+            Ok(())
+        };
+
+        match self {
+            Shape::Unit => f.write_str("()"),
+            Shape::Bool => f.write_str("bool"),
+            Shape::U8 => f.write_str("u8"),
+            Shape::U16 => f.write_str("u16"),
+            Shape::U32 => f.write_str("u32"),
+            Shape::U64 => f.write_str("u64"),
+            Shape::U128 => f.write_str("u128"),
+            Shape::I8 => f.write_str("i8"),
+            Shape::I16 => f.write_str("i16"),
+            Shape::I32 => f.write_str("i32"),
+            Shape::I64 => f.write_str("i64"),
+            Shape::I128 => f.write_str("i128"),
+            Shape::F32 => f.write_str("f32"),
+            Shape::F64 => f.write_str("f64"),
+            Shape::Char => f.write_str("char"),
+            Shape::Str => f.write_str("String"),
+            Shape::Option(inner) => write!(f, "Option<{inner}>"),
+            Shape::Seq(elem) => write!(f, "Vec<{elem}>"),
+            Shape::Map(key, value) => write!(f, "Map<{key}, {value}>"),
+            Shape::Tuple(fields) => {
+                f.write_str("(")?;
+                list(f, fields)?;
+                f.write_str(")")
+            }
+            Shape::Array(len, elem) => write!(f, "[{elem}; {len}]"),
+            Shape::Enum(variants) => {
+                f.write_str("enum { ")?;
+                list(f, variants)?;
+                f.write_str(" }")
+            }
+            Shape::Opaque => f.write_str("_"),
+        }
+    }
+}
+
+/// A value that is not encoded as a value of the expected type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// The fields leading to the mismatched value, innermost first.
+    path: Vec<String>,
+    message: String,
+}
+
+impl SchemaMismatch {
+    fn new(expected: &Shape, found: impl Display) -> Self {
+        Self {
+            path: Vec::new(),
+            message: format!("expected {expected}, found {found}"),
+        }
+    }
+
+    fn within(mut self, field: String) -> Self {
+        self.path.push(field);
+        self
+    }
+}
+
+impl Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if !self.path.is_empty() {
+            let path: Vec<&str> = self.path.iter().rev().map(String::as_str).collect();
+            write!(f, " at `.{}`", path.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+impl ser::Error for SchemaMismatch {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self {
+            path: Vec::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+// Checks that the serialized value matches the shape, without encoding it.
+struct Checker<'a>(&'a Shape);
+
+impl<'a> Checker<'a> {
+    fn primitive(self, found: Shape) -> Result<(), SchemaMismatch> {
+        match self.0 {
+            Shape::Opaque => Ok(()),
+            expected if *expected == found => Ok(()),
+            expected => Err(SchemaMismatch::new(expected, found)),
+        }
+    }
+
+    fn tuple(self, len: usize, found: impl Display) -> Result<Compound<'a>, SchemaMismatch> {
+        let fields = match self.0 {
+            Shape::Opaque => Fields::Any,
+            // A single field is encoded as the field itself.
+            shape if len == 1 => Fields::List(std::slice::from_ref(shape)),
+            Shape::Unit if len == 0 => Fields::List(&[]),
+            Shape::Tuple(fields) if fields.len() == len => Fields::List(fields),
+            Shape::Array(n, elem) if *n == len => Fields::Each(elem),
+            expected => return Err(SchemaMismatch::new(expected, found)),
+        };
+        Ok(Compound { fields, index: 0 })
+    }
+
+    // Returns the shape of the variant of an enum, or `None` if the enum is opaque.
+    fn variant(self, index: u32, name: &str) -> Result<Option<&'a Shape>, SchemaMismatch> {
+        match self.0 {
+            Shape::Opaque => Ok(None),
+            Shape::Enum(variants) => match variants.get(index as usize) {
+                Some(variant) => Ok(Some(variant)),
+                None => Err(SchemaMismatch::new(
+                    self.0,
+                    format_args!("variant `{name}` at index {index}"),
+                )),
+            },
+            expected => Err(SchemaMismatch::new(
+                expected,
+                format_args!("enum variant `{name}`"),
+            )),
+        }
+    }
+}
+
+enum Fields<'a> {
+    /// Fields of an opaque type, which are not checked.
+    Any,
+    /// Elements of a sequence or an array, which all have the same shape.
+    Each(&'a Shape),
+    List(&'a [Shape]),
+    Map(&'a Shape, &'a Shape),
+}
+
+struct Compound<'a> {
+    fields: Fields<'a>,
+    index: usize,
+}
+
+impl Compound<'_> {
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        name: Option<&str>,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        let index = self.index;
+        self.index += 1;
+
+        let shape = match self.fields {
+            Fields::Any => return Ok(()),
+            Fields::Each(shape) => shape,
+            // The number of fields was checked when starting the tuple.
+            Fields::List(shapes) => &shapes[index],
+            // Keys and values alternate.
+            Fields::Map(key, value) => {
+                if index % 2 == 0 {
+                    key
+                } else {
+                    value
+                }
+            }
+        };
+        let name = match (&self.fields, name) {
+            (_, Some(name)) => name.to_string(),
+            (Fields::Map(..), None) => format!("{}", index / 2),
+            (_, None) => index.to_string(),
+        };
+        value
+            .serialize(Checker(shape))
+            .map_err(|err| err.within(name))
+    }
+}
+
+impl<'a> ser::Serializer for Checker<'a> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Bool)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::I8)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::I16)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::I32)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::I64)
+    }
+
+    fn serialize_i128(self, _: i128) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::I128)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::U8)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::U16)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::U32)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::U64)
+    }
+
+    fn serialize_u128(self, _: u128) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::U128)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::F32)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::F64)
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Char)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Str)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Seq(Box::new(Shape::U8)))
+    }
+
+    fn serialize_none(self) -> Result<(), SchemaMismatch> {
+        match self.0 {
+            Shape::Opaque | Shape::Option(_) => Ok(()),
+            expected => Err(SchemaMismatch::new(expected, "None")),
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SchemaMismatch> {
+        match self.0 {
+            Shape::Opaque => Ok(()),
+            Shape::Option(inner) => value.serialize(Checker(inner)),
+            expected => Err(SchemaMismatch::new(expected, "Some")),
+        }
+    }
+
+    fn serialize_unit(self) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Unit)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), SchemaMismatch> {
+        self.primitive(Shape::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), SchemaMismatch> {
+        match self.variant(index, variant)? {
+            Some(shape) => Checker(shape).primitive(Shape::Unit),
+            None => Ok(()),
+        }
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        match self.variant(index, variant)? {
+            Some(shape) => value
+                .serialize(Checker(shape))
+                .map_err(|err| err.within(variant.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'a>, SchemaMismatch> {
+        let fields = match self.0 {
+            Shape::Opaque => Fields::Any,
+            Shape::Seq(elem) => Fields::Each(elem),
+            expected => return Err(SchemaMismatch::new(expected, "a sequence")),
+        };
+        Ok(Compound { fields, index: 0 })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, SchemaMismatch> {
+        self.tuple(len, format_args!("a tuple of {len} fields"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, SchemaMismatch> {
+        self.tuple(len, format_args!("struct `{name}` with {len} fields"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, SchemaMismatch> {
+        match self.variant(index, variant)? {
+            Some(shape) => {
+                Checker(shape).tuple(len, format_args!("variant `{variant}` with {len} fields"))
+            }
+            None => Ok(Compound {
+                fields: Fields::Any,
+                index: 0,
+            }),
+        }
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'a>, SchemaMismatch> {
+        let fields = match self.0 {
+            Shape::Opaque => Fields::Any,
+            Shape::Map(key, value) => Fields::Map(key, value),
+            expected => return Err(SchemaMismatch::new(expected, "a map")),
+        };
+        Ok(Compound { fields, index: 0 })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, SchemaMismatch> {
+        self.tuple(len, format_args!("struct `{name}` with {len} fields"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, SchemaMismatch> {
+        self.serialize_tuple_variant("", index, variant, len)
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        self.field(None, value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        self.field(None, value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaMismatch> {
+        self.field(None, value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaMismatch> {
+        self.field(None, value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SchemaMismatch> {
+        self.field(None, key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaMismatch> {
+        self.field(None, value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        self.field(Some(name), value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = SchemaMismatch;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaMismatch> {
+        self.field(Some(name), value)
+    }
+
+    fn end(self) -> Result<(), SchemaMismatch> {
+        Ok(())
+    }
+}
+
+/// Parses the I/O schema section of the ELF file, if present.
+pub(crate) fn parse_io_schema(elf: &ElfBytes<LittleEndian>) -> Result<IoSchema> {
+    let Some(section) = elf
+        .section_header_by_name(IO_SCHEMA_SECTION)
+        .map_err(ParserError::ELFError)?
+    else {
+        return Ok(IoSchema::default());
+    };
+
+    let (bytes, _) = elf.section_data(&section).map_err(ParserError::ELFError)?;
+    IoSchema::from_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_common::schema::{entry, entry_len, Encoded};
+    use std::collections::BTreeMap;
+
+    const I32: Encoded = Encoded::leaf(tag::I32);
+    // The schema of `struct Point { x: i32, y: i32 }`, as derived in the guest.
+    const POINT: Encoded = Encoded::tuple(&[I32, I32]);
+
+    fn schema(encoded: &Encoded) -> TypeSchema {
+        let shape = Shape::decode(&mut encoded.as_bytes(), MAX_ENCODED_LEN).unwrap();
+        TypeSchema {
+            name: String::new(),
+            shape,
+        }
+    }
+
+    #[test]
+    fn test_normalize_type_name() {
+        let cases = [
+            ("u32", "u32"),
+            ("(u32 , u32)", "(u32,u32)"),
+            ("alloc::vec::Vec<u8>", "Vec<u8>"),
+            ("Vec < u8 >", "Vec<u8>"),
+            (
+                "::core::option::Option<alloc::string::String>",
+                "Option<String>",
+            ),
+            ("[u8 ; 32]", "[u8;32]"),
+            ("&'static str", "&str"),
+            ("Foo<'a, u32>", "Foo<u32>"),
+            ("Foo<'a>", "Foo"),
+            ("Box<dyn core::any::Any>", "Box<dyn Any>"),
+            ("&'a mut u8", "&mut u8"),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(normalize_type_name(name), expected, "normalizing {name}");
+        }
+    }
+
+    #[test]
+    fn test_io_schema_from_bytes() {
+        const HEADER: &[u8] = b"private_input\0(Point , alloc::string::String)\0";
+        const SCHEMA: Encoded = Encoded::tuple(&[POINT, Encoded::leaf(tag::STR)]);
+        const ENTRY: [u8; entry_len(HEADER, &SCHEMA)] = entry(HEADER, &SCHEMA);
+
+        let mut bytes = ENTRY.to_vec();
+        bytes.extend_from_slice(b"\0\0\0public_output\0u64\0\x05");
+        let schema = IoSchema::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            schema,
+            IoSchema {
+                public_input: None,
+                private_input: Some(TypeSchema {
+                    name: "(Point,String)".to_string(),
+                    shape: Shape::Tuple(vec![
+                        Shape::Tuple(vec![Shape::I32, Shape::I32]),
+                        Shape::Str
+                    ]),
+                }),
+                public_output: Some(TypeSchema {
+                    name: "u64".to_string(),
+                    shape: Shape::U64,
+                }),
+            }
+        );
+        assert!(IoSchema::from_bytes(b"").unwrap().is_empty());
+        assert!(IoSchema::from_bytes(b"private_input\0").is_err());
+        assert!(IoSchema::from_bytes(b"private_input\0u32\0").is_err());
+        assert!(IoSchema::from_bytes(b"private_input\0u32\0\xff").is_err());
+        assert!(IoSchema::from_bytes(b"unknown\0u32\0\x04").is_err());
+        assert!(IoSchema::from_bytes(b"private_input\0u32\0\x04private_input\0u64\0\x05").is_err());
+
+        // Nesting deeper than any recorded schema is rejected rather than overflowing the stack.
+        let mut deep = b"private_input\0T\0".to_vec();
+        deep.extend(std::iter::repeat(tag::OPTION).take(1 << 20));
+        deep.push(tag::U8);
+        assert!(IoSchema::from_bytes(&deep).is_err());
+    }
+
+    #[test]
+    fn test_check_compatible() {
+        let pair = schema(&Encoded::tuple(&[
+            Encoded::leaf(tag::STR),
+            Encoded::seq(&Encoded::leaf(tag::U8)),
+        ]));
+        pair.check(&("a", &[1u8, 2][..])).unwrap();
+        pair.check(&("a".to_string(), vec![1u8, 2])).unwrap();
+
+        let array = schema(&Encoded::array(2, &Encoded::leaf(tag::U32)));
+        array.check(&[1u32, 2]).unwrap();
+        array.check(&(1u32, 2u32)).unwrap();
+
+        let map = schema(&Encoded::map(
+            &Encoded::leaf(tag::U32),
+            &Encoded::leaf(tag::STR),
+        ));
+        map.check(&BTreeMap::from([(1u32, "one")])).unwrap();
+
+        let option = schema(&Encoded::option(&POINT));
+        option.check(&None::<(i32, i32)>).unwrap();
+        option.check(&Some((1i32, 2i32))).unwrap();
+
+        // Opaque types, such as guest types without a schema, match any value.
+        schema(&Encoded::tuple(&[Encoded::OPAQUE, I32]))
+            .check(&(vec!["anything"], 1i32))
+            .unwrap();
+
+        // `usize` is encoded as a 64-bit integer.
+        schema(&Encoded::leaf(tag::U64)).check(&1usize).unwrap();
+    }
+
+    #[test]
+    fn test_check_struct() {
+        #[derive(Serialize)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Serialize)]
+        struct Point3 {
+            x: i32,
+            y: i32,
+            z: i32,
+        }
+
+        #[derive(Serialize)]
+        struct WidePoint {
+            x: i32,
+            y: i64,
+        }
+
+        let point = schema(&POINT);
+        point.check(&Point { x: 1, y: 2 }).unwrap();
+        point.check(&(1i32, 2i32)).unwrap();
+
+        let err = point.check(&Point3 { x: 1, y: 2, z: 3 }).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected (i32, i32), found struct `Point3` with 3 fields"
+        );
+        let err = point.check(&WidePoint { x: 1, y: 2 }).unwrap_err();
+        assert_eq!(err.to_string(), "expected i32, found i64 at `.y`");
+
+        // Mismatches are reported at their path in nested values.
+        let points = schema(&Encoded::seq(&POINT));
+        points
+            .check(&vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }])
+            .unwrap();
+        let err = points.check(&vec![(1i32, 2u32)]).unwrap_err();
+        assert_eq!(err.to_string(), "expected i32, found u32 at `.0.1`");
+    }
+
+    #[test]
+    fn test_check_enum() {
+        #[derive(Serialize)]
+        #[allow(dead_code)]
+        enum Command {
+            Stop,
+            Move(i32, i32),
+            Say(String),
+        }
+
+        let command = schema(&Encoded::enumeration(&[
+            Encoded::leaf(tag::UNIT),
+            POINT,
+            Encoded::leaf(tag::STR),
+        ]));
+        command.check(&Command::Stop).unwrap();
+        command.check(&Command::Move(1, 2)).unwrap();
+        command.check(&Command::Say("hi".to_string())).unwrap();
+
+        let err = schema(&Encoded::enumeration(&[Encoded::leaf(tag::UNIT), POINT]))
+            .check(&Command::Say("hi".to_string()))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected enum { (), (i32, i32) }, found variant `Say` at index 2"
+        );
+
+        let err = command.check(&1u32).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected enum { (), (i32, i32), String }, found u32"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Consistency Check Between the Harvard and Linear Passes
+//!
+//! Proving a program runs it twice: first on the `HarvardEmulator`, whose memory statistics size the
+//! memory layout, then on the `LinearEmulator`, which produces the trace. Both runs must agree on
+//! everything observable by the host, which [`check_consistency`] verifies once both have exited.
+
+use std::ops::RangeInclusive;
+
+use nexus_common::{constants::MEMORY_TOP, cpu::Registers};
+
+use super::{Emulator, HarvardEmulator, LinearEmulator};
+use crate::error::{Divergence, Result, VMError};
+use crate::riscv::Register;
+
+/// Compares the exit code, public output, debug logs and final register state of the Harvard and Linear
+/// executions of the same program, both of which must have exited.
+///
+/// The two passes place the public I/O, heap and stack at different addresses, so registers holding the
+/// same offset into the same one of these segments in both passes are not reported as diverging. The
+/// linear pass only records debug logs if it was set to capture them.
+///
+/// Returns [`VMError::ExecutionDivergence`] listing every difference found.
+pub fn check_consistency(harvard: &HarvardEmulator, linear: &LinearEmulator) -> Result<()> {
+    let mut divergences = Vec::new();
+    let (harvard_view, linear_view) = (harvard.finalize(), linear.finalize());
+
+    // The linear pass reserves the exit code word up front, while the Harvard pass only holds the bytes the
+    // guest wrote, so bytes missing from either are zero.
+    let harvard_exit_code = harvard_view.view_exit_code().unwrap_or_default();
+    let linear_exit_code = linear_view.view_exit_code().unwrap_or_default();
+    if !zero_extended_eq(&harvard_exit_code, &linear_exit_code) {
+        divergences.push(Divergence::ExitCode {
+            harvard: harvard_exit_code,
+            linear: linear_exit_code,
+        });
+    }
+
+    let harvard_output = harvard_view.view_public_output().unwrap_or_default();
+    let linear_output = linear_view.view_public_output().unwrap_or_default();
+    if let Some(offset) = first_difference(&harvard_output, &linear_output) {
+        divergences.push(Divergence::PublicOutput {
+            offset,
+            harvard: harvard_output.get(offset).copied(),
+            linear: linear_output.get(offset).copied(),
+        });
+    }
+
+    if let (Some(harvard_logs), Some(linear_logs)) = (&harvard.executor.logs, &linear.executor.logs)
+    {
+        if let Some(index) = first_difference(harvard_logs, linear_logs) {
+            let entry = |logs: &[Vec<u8>]| {
+                logs.get(index)
+                    .map(|log| String::from_utf8_lossy(log).into_owned())
+            };
+            divergences.push(Divergence::Logs {
+                index,
+                harvard: entry(harvard_logs),
+                linear: entry(linear_logs),
+            });
+        }
+    }
+
+    let (harvard_cpu, linear_cpu) = (&harvard.executor.cpu, &linear.executor.cpu);
+    if harvard_cpu.pc.value != linear_cpu.pc.value {
+        divergences.push(Divergence::Pc {
+            harvard: harvard_cpu.pc.value,
+            linear: linear_cpu.pc.value,
+        });
+    }
+
+    let layout = &linear.memory_layout;
+    // The base address of each relocated segment in both passes, along with the extent of the segment
+    // in the linear pass. The Harvard pass addresses the public input and output from zero, and the
+    // stack grows down from the stack pointer the guest started from in each pass.
+    let relocated = [
+        (
+            0,
+            layout.public_input_start(),
+            layout.public_input_start()..=layout.public_input_end(),
+        ),
+        (
+            0,
+            layout.exit_code(),
+            layout.exit_code()..=layout.public_output_end(),
+        ),
+        (
+            harvard.heap_start,
+            layout.heap_start(),
+            layout.heap_start()..=layout.heap_end(),
+        ),
+        (
+            harvard.executor.stack_top.unwrap_or(MEMORY_TOP),
+            linear.executor.stack_top.unwrap_or(layout.stack_top()),
+            layout.stack_bottom()..=layout.stack_top(),
+        ),
+    ];
+    for register in (0..32u8).map(Register::from) {
+        let harvard = harvard_cpu.registers.read(register);
+        let linear = linear_cpu.registers.read(register);
+        if harvard != linear && !is_relocated(harvard, linear, &relocated) {
+            divergences.push(Divergence::Register {
+                register,
+                harvard,
+                linear,
+            });
+        }
+    }
+
+    if divergences.is_empty() {
+        Ok(())
+    } else {
+        Err(VMError::ExecutionDivergence(divergences))
+    }
+}
+
+/// Returns `true` if `harvard` and `linear` are the same offset into one of the `relocated` segments.
+fn is_relocated(harvard: u32, linear: u32, relocated: &[(u32, u32, RangeInclusive<u32>)]) -> bool {
+    relocated.iter().any(|(harvard_base, linear_base, extent)| {
+        extent.contains(&linear)
+            && harvard.wrapping_sub(*harvard_base) == linear.wrapping_sub(*linear_base)
+    })
+}
+
+/// Returns `true` if `a` and `b` are equal once the shorter is padded with zeros.
+fn zero_extended_eq(a: &[u8], b: &[u8]) -> bool {
+    let len = a.len().max(b.len());
+    (0..len).all(|i| a.get(i).unwrap_or(&0) == b.get(i).unwrap_or(&0))
+}
+
+/// Returns the index of the first element that differs between `a` and `b`, if any.
+fn first_difference<T: PartialEq>(a: &[T], b: &[T]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or((a.len() != b.len()).then(|| a.len().min(b.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::ElfFile;
+
+    fn run(elf: &ElfFile) -> (HarvardEmulator, LinearEmulator) {
+        let mut harvard = HarvardEmulator::from_elf(elf, &[], &[]);
+        harvard.get_executor_mut().capture_logs(true);
+        assert_eq!(harvard.execute(false), Err(VMError::VMExited(0)));
+
+        let mut linear = LinearEmulator::from_harvard(&harvard, elf.clone(), &[], &[]).unwrap();
+        linear.get_executor_mut().capture_logs(true);
+        assert_eq!(linear.execute(false), Err(VMError::VMExited(0)));
+
+        (harvard, linear)
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let (harvard, linear) = run(&elf);
+
+        assert_eq!(check_consistency(&harvard, &linear), Ok(()));
+    }
+
+    #[test]
+    fn test_check_consistency_relocated_registers() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let (mut harvard, mut linear) = run(&elf);
+        let layout = linear.memory_layout;
+
+        // The same offset into the stack and the heap of each pass.
+        harvard
+            .executor
+            .cpu
+            .registers
+            .write(Register::X11, harvard.executor.stack_top.unwrap());
+        linear
+            .executor
+            .cpu
+            .registers
+            .write(Register::X11, layout.stack_top());
+        harvard
+            .executor
+            .cpu
+            .registers
+            .write(Register::X12, harvard.heap_start);
+        linear
+            .executor
+            .cpu
+            .registers
+            .write(Register::X12, layout.heap_start());
+        assert_eq!(check_consistency(&harvard, &linear), Ok(()));
+
+        // A value that is not a pointer in the Harvard pass, but happens to fall into the stack of the
+        // linear pass.
+        harvard.executor.cpu.registers.write(Register::X11, 42);
+        let Err(VMError::ExecutionDivergence(divergences)) = check_consistency(&harvard, &linear)
+        else {
+            panic!("expected a divergence");
+        };
+        assert_eq!(
+            divergences,
+            vec![Divergence::Register {
+                register: Register::X11,
+                harvard: 42,
+                linear: layout.stack_top(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_consistency_divergence() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let (harvard, mut linear) = run(&elf);
+
+        linear.executor.cpu.pc.value += 4;
+        linear.executor.cpu.registers.write(Register::X10, 42);
+        linear
+            .executor
+            .logs
+            .as_mut()
+            .unwrap()
+            .push(b"extra".to_vec());
+
+        let Err(VMError::ExecutionDivergence(divergences)) = check_consistency(&harvard, &linear)
+        else {
+            panic!("expected a divergence");
+        };
+        let index = harvard.executor.logs.as_ref().unwrap().len();
+        assert_eq!(
+            divergences,
+            vec![
+                Divergence::Logs {
+                    index,
+                    harvard: None,
+                    linear: Some("extra".to_string()),
+                },
+                Divergence::Pc {
+                    harvard: harvard.executor.cpu.pc.value,
+                    linear: harvard.executor.cpu.pc.value + 4,
+                },
+                Divergence::Register {
+                    register: Register::X10,
+                    harvard: harvard.executor.cpu.registers.read(Register::X10),
+                    linear: 42,
+                },
+            ]
+        );
+    }
+}
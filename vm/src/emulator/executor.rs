@@ -137,12 +137,12 @@ use super::{
 use crate::{
     cpu::{instructions::InstructionResult, Cpu},
     elf::ElfFile,
-    error::{Result, VMError},
+    error::{AccessReport, AccessViolation, MemoryError, Result, VMError},
     memory::{
         FixedMemory, LoadOp, MemoryProcessor, MemoryRecords, Modes, StoreOp, UnifiedMemory,
         VariableMemory, NA, RO, RW, WO,
     },
-    riscv::{decode_until_end_of_a_block, BasicBlock, Instruction, Opcode, Register},
+    riscv::{decode_until_end_of_a_block_at, BasicBlock, Instruction, Opcode, Register},
     system::SyscallInstruction,
 };
 
@@ -191,8 +191,14 @@ pub struct Executor {
     // Debug logs written by the guest program
     pub logs: Option<Vec<Vec<u8>>>,
 
+    // Allocator statistics reported by guests built with the runtime's `alloc-stats` feature
+    pub alloc_stats: Option<AllocStats>,
+
     // A map of memory addresses to the last timestamp when they were accessed
     pub access_timestamps: HashMap<u32, usize>,
+
+    // The stack pointer in effect after the guest's stack pointer overwrite syscall, if it made one
+    pub stack_top: Option<u32>,
 }
 
 impl Executor {
@@ -216,6 +222,40 @@ impl Executor {
             self.logs = None;
         }
     }
+
+    /// Attaches the program counter to a memory access stopped by a watchpoint or strict mode checks.
+    ///
+    /// With a known memory layout, an access into the heap-stack gap is reported as a stack overflow if the
+    /// stack pointer is below the bottom of the stack.
+    fn report_access_violation(
+        &self,
+        err: VMError,
+        memory_layout: Option<&LinearMemoryLayout>,
+    ) -> VMError {
+        let VMError::MemoryError(MemoryError::AccessViolation(address, kind, violation)) = err
+        else {
+            return err;
+        };
+
+        let sp = self.cpu.registers.read(Register::X2);
+        let violation = match memory_layout {
+            Some(layout)
+                if violation == AccessViolation::HeapStackGap
+                    && sp != 0
+                    && sp < layout.stack_bottom() =>
+            {
+                AccessViolation::StackOverflow
+            }
+            _ => violation,
+        };
+
+        VMError::AccessViolation(AccessReport {
+            pc: self.cpu.pc.value,
+            kind,
+            address,
+            violation,
+        })
+    }
 }
 
 pub trait Emulator {
@@ -275,6 +315,19 @@ pub trait Emulator {
     /// Return a mutable reference to the internal executor component used by the emulator.
     fn get_executor_mut(&mut self) -> &mut Executor;
 
+    /// Fetch the basic block holding `pc`, along with the index of the instruction starting at `pc`.
+    ///
+    /// Returns [`VMError::MisalignedPc`] if `pc` points into the middle of an instruction of the block.
+    fn fetch_block_at(&mut self, pc: u32) -> Result<(BasicBlockEntry, usize)> {
+        let entry = self.fetch_block(pc)?;
+        let at = entry
+            .block
+            .index_at_offset((pc - entry.start) as usize)
+            .ok_or(VMError::MisalignedPc(pc))?;
+
+        Ok((entry, at))
+    }
+
     /// Execute an entire basic block.
     fn execute_basic_block(
         &mut self,
@@ -289,8 +342,11 @@ pub trait Emulator {
         let mut results: Vec<InstructionResult> = Vec::new();
         let mut transcript: MemoryTranscript = Vec::new();
 
-        let at = (self.get_executor().cpu.pc.value as usize - basic_block_entry.start as usize)
-            / WORD_SIZE;
+        let pc = self.get_executor().cpu.pc.value;
+        let at = basic_block_entry
+            .block
+            .index_at_offset((pc - basic_block_entry.start) as usize)
+            .ok_or(VMError::MisalignedPc(pc))?;
 
         // Execute the instructions in the basic block
         for instruction in basic_block_entry.block.0[at..].iter() {
@@ -411,6 +467,12 @@ pub struct HarvardEmulator {
 
     // Tracker for the memory sizes since they are not known ahead of time
     memory_stats: MemoryStats,
+
+    // Where the guest allocator starts the heap, since the VM does not place it
+    pub(crate) heap_start: u32,
+
+    // Where the guest allocator keeps the end of the heap, if known
+    heap_pos_address: Option<u32>,
 }
 
 impl Default for HarvardEmulator {
@@ -425,6 +487,8 @@ impl Default for HarvardEmulator {
             static_ram_image: BTreeMap::new(),
             data_memory: UnifiedMemory::default(),
             memory_stats: MemoryStats::default(),
+            heap_start: 0,
+            heap_pos_address: None,
         }
     }
 }
@@ -522,6 +586,8 @@ impl HarvardEmulator {
             static_ram_image,
             data_memory,
             memory_stats: MemoryStats::new(data_end, MEMORY_TOP),
+            heap_start: elf.heap_start.unwrap_or(data_end),
+            heap_pos_address: elf.heap_pos_address,
         };
         emulator.executor.cpu.pc.value = emulator.executor.entrypoint;
         emulator
@@ -555,6 +621,20 @@ impl HarvardEmulator {
         emulator.executor.cpu.pc.value = emulator.executor.entrypoint;
         emulator
     }
+
+    /// Returns one byte past the last allocation of the guest allocator, read from its `__heap_pos`
+    /// variable, or `None` if the guest did not allocate or does not export the variable.
+    ///
+    /// Unlike the memory statistics, this covers allocated memory that is never accessed.
+    pub fn allocator_heap_end(&self) -> Option<u32> {
+        let address = self.heap_pos_address?;
+        let heap_pos = self
+            .data_memory
+            .read(address, MemAccessSize::Word)
+            .ok()?
+            .get_value();
+        (heap_pos != 0).then_some(heap_pos)
+    }
 }
 
 impl Emulator for HarvardEmulator {
@@ -569,7 +649,10 @@ impl Emulator for HarvardEmulator {
         bare_instruction: &Instruction,
         force_provable_transcript: bool,
     ) -> Result<(InstructionResult, MemoryRecords)> {
-        let ((res, (load_ops, store_ops)), accessed_io_memory) = match (
+        // Counter CSRs expose the global clock, see `nexus_common::riscv::csr`.
+        self.executor.cpu.cycles = self.executor.global_clock as u64;
+
+        let (res, accessed_io_memory) = match (
             self.executor
                 .instruction_executor
                 .get_for_read_input(&bare_instruction.opcode),
@@ -587,7 +670,7 @@ impl Emulator for HarvardEmulator {
                     None,
                     bare_instruction,
                     force_provable_transcript,
-                )?,
+                ),
                 false,
             ),
             (Some(read_input), _, _) => (
@@ -595,7 +678,8 @@ impl Emulator for HarvardEmulator {
                     &mut self.executor.cpu,
                     &mut self.input_memory,
                     bare_instruction,
-                )?,
+                )
+                .map_err(VMError::from),
                 true,
             ),
             (_, Some(write_output), _) => (
@@ -603,7 +687,8 @@ impl Emulator for HarvardEmulator {
                     &mut self.executor.cpu,
                     &mut self.output_memory,
                     bare_instruction,
-                )?,
+                )
+                .map_err(VMError::from),
                 true,
             ),
             (_, _, Ok(executor)) => (
@@ -611,11 +696,14 @@ impl Emulator for HarvardEmulator {
                     &mut self.executor.cpu,
                     &mut self.data_memory,
                     bare_instruction,
-                )?,
+                )
+                .map_err(VMError::from),
                 false,
             ),
             (_, _, Err(e)) => return Err(e),
         };
+        let (res, (load_ops, store_ops)) =
+            res.map_err(|err| self.executor.report_access_violation(err, None))?;
 
         let mut memory_records = MemoryRecords::new();
 
@@ -637,7 +725,7 @@ impl Emulator for HarvardEmulator {
         }
 
         if !bare_instruction.is_branch_or_jump_instruction() {
-            self.executor.cpu.pc.step();
+            self.executor.cpu.pc.step_by(bare_instruction.size() as u32);
         }
 
         // The global clock will update according to the currency of ZK (constraint?)
@@ -663,7 +751,10 @@ impl Emulator for HarvardEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block = decode_until_end_of_a_block(self.instruction_memory.segment(pc, None));
+        let block = decode_until_end_of_a_block_at(
+            self.instruction_memory.segment(pc, None),
+            pc as usize % WORD_SIZE,
+        );
         if block.is_empty() {
             return Err(VMError::VMOutOfInstructions);
         }
@@ -776,6 +867,7 @@ impl Emulator for HarvardEmulator {
             exit_code,
             output_memory,
             associated_data: Vec::new(),
+            alloc_stats: self.executor.alloc_stats,
         }
     }
 }
@@ -815,6 +907,24 @@ impl LinearEmulator {
         compiled_elf: ElfFile,
         ad: &[u8],
         private_input: &[u8],
+    ) -> Result<Self> {
+        Self::from_harvard_with_overrides(
+            emulator_harvard,
+            compiled_elf,
+            ad,
+            private_input,
+            &LayoutOverrides::default(),
+        )
+    }
+
+    /// Same as [`LinearEmulator::from_harvard`], but uses the heap and stack sizes pinned in `overrides`
+    /// instead of those observed by the Harvard emulator.
+    pub fn from_harvard_with_overrides(
+        emulator_harvard: &HarvardEmulator,
+        compiled_elf: ElfFile,
+        ad: &[u8],
+        private_input: &[u8],
+        overrides: &LayoutOverrides,
     ) -> Result<Self> {
         // Reminder!: Add feature flag to control pre-populating output memory.
         // This allows flexibility in the consistency argument used by the prover.
@@ -825,13 +935,10 @@ impl LinearEmulator {
         let output_memory = emulator_harvard.output_memory.segment_bytes(0, None)?; // grab the whole output segment, exit code included
 
         // Replace custom instructions `rin` and `wou` with `lw` and `sw`.
-        let instructions = compiled_elf
-            .instructions
-            .iter()
-            .map(|instr| {
-                super::convert_instruction(&emulator_harvard.executor.instruction_executor, instr)
-            })
-            .collect();
+        let instructions = super::convert_instructions(
+            &emulator_harvard.executor.instruction_executor,
+            &compiled_elf.instructions,
+        );
 
         let elf = ElfFile {
             instructions,
@@ -839,20 +946,25 @@ impl LinearEmulator {
         };
 
         // Create an optimized memory layout using memory statistics from the first pass.
-        let memory_layout = emulator_harvard
-            .memory_stats
-            .create_optimized_layout(
-                ((elf.instructions.len()
-                    + WORD_SIZE
-                    + elf.rom_image.len()
-                    + WORD_SIZE
-                    + elf.ram_image.len())
-                    * WORD_SIZE) as u32,
-                ad.len() as u32,
-                public_input.len() as u32,
-                (output_memory.len() - WORD_SIZE) as u32, // Exclude the first word which is the exit code
-            )
-            .unwrap();
+        let mut memory_stats = emulator_harvard.memory_stats.clone();
+        if let Some(heap_end) = emulator_harvard.allocator_heap_end() {
+            memory_stats.record_heap_end(heap_end);
+        }
+        if let Some(alloc_stats) = emulator_harvard.executor.alloc_stats {
+            memory_stats.record_heap_end(alloc_stats.heap_end);
+        }
+        let memory_layout = memory_stats.create_optimized_layout(
+            ((elf.instructions.len()
+                + WORD_SIZE
+                + elf.rom_image.len()
+                + WORD_SIZE
+                + elf.ram_image.len())
+                * WORD_SIZE) as u32,
+            ad.len() as u32,
+            public_input.len() as u32,
+            (output_memory.len() - WORD_SIZE) as u32, // Exclude the first word which is the exit code
+            overrides,
+        )?;
 
         Ok(Self::from_elf(
             memory_layout,
@@ -970,6 +1082,9 @@ impl LinearEmulator {
             let _ = memory.add_fixed_rw(&heap_memory).unwrap();
         }
 
+        // Only checked in strict mode, see `UnifiedMemory::set_strict_mode`.
+        memory.set_gap(Some(memory_layout.gap_start()..memory_layout.gap_end()));
+
         let stack_len = (memory_layout.stack_top() - memory_layout.stack_bottom()) as usize;
         if stack_len > 0 {
             let init = vec![0; stack_len / WORD_SIZE];
@@ -1036,6 +1151,9 @@ impl Emulator for LinearEmulator {
         bare_instruction: &Instruction,
         _force_second_pass: bool, // Linear Emulator always does second pass
     ) -> Result<(InstructionResult, MemoryRecords)> {
+        // Counter CSRs expose the global clock, see `nexus_common::riscv::csr`.
+        self.executor.cpu.cycles = self.executor.global_clock as u64;
+
         let (res, (load_ops, store_ops)) = match (
             self.executor
                 .instruction_executor
@@ -1054,19 +1172,26 @@ impl Emulator for LinearEmulator {
                     Some(self.memory_layout),
                     bare_instruction,
                     true,
-                )?
+                )
             }
             (Some(read_input), _, _) => {
-                read_input(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
+                read_input(&mut self.executor.cpu, &mut self.memory, bare_instruction)
+                    .map_err(VMError::from)
             }
             (_, Some(write_output), _) => {
-                write_output(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
+                write_output(&mut self.executor.cpu, &mut self.memory, bare_instruction)
+                    .map_err(VMError::from)
             }
             (_, _, Ok(executor)) => {
-                executor(&mut self.executor.cpu, &mut self.memory, bare_instruction)?
+                executor(&mut self.executor.cpu, &mut self.memory, bare_instruction)
+                    .map_err(VMError::from)
             }
             (_, _, Err(e)) => return Err(e),
-        };
+        }
+        .map_err(|err| {
+            self.executor
+                .report_access_violation(err, Some(&self.memory_layout))
+        })?;
 
         let mut memory_records = MemoryRecords::new();
 
@@ -1079,7 +1204,7 @@ impl Emulator for LinearEmulator {
         });
 
         if !bare_instruction.is_branch_or_jump_instruction() {
-            self.executor.cpu.pc.step();
+            self.executor.cpu.pc.step_by(bare_instruction.size() as u32);
         }
 
         // The global clock will update according to the currency of ZK (constraint?)
@@ -1105,8 +1230,10 @@ impl Emulator for LinearEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block =
-            decode_until_end_of_a_block(self.memory.segment(self.instruction_index, pc, None)?);
+        let block = decode_until_end_of_a_block_at(
+            self.memory.segment(self.instruction_index, pc, None)?,
+            pc as usize % WORD_SIZE,
+        );
         if block.is_empty() {
             return Err(VMError::VMOutOfInstructions);
         }
@@ -1271,6 +1398,7 @@ impl Emulator for LinearEmulator {
             exit_code,
             output_memory,
             associated_data,
+            alloc_stats: self.executor.alloc_stats,
         }
     }
 }
@@ -1279,6 +1407,8 @@ impl Emulator for LinearEmulator {
 mod tests {
     use super::*;
     use crate::elf::ElfFile;
+    use crate::error::AccessKind;
+    use crate::memory::WatchKind;
     use crate::riscv::{BuiltinOpcode, Instruction, Opcode};
     use serial_test::serial;
 
@@ -1364,6 +1494,59 @@ mod tests {
         assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
     }
 
+    #[test]
+    fn test_harvard_compressed_instructions() {
+        // c.li a0, 1; addi a1, a0, 2; c.add a0, a1
+        let instructions = vec![0x0593_4505, 0x952e_0025];
+        let mut emulator = HarvardEmulator {
+            executor: Executor {
+                base_address: ELF_TEXT_START,
+                entrypoint: ELF_TEXT_START,
+                global_clock: 1,
+                ..Default::default()
+            },
+            instruction_memory: FixedMemory::<RO>::from_vec(
+                ELF_TEXT_START,
+                instructions.len() * WORD_SIZE,
+                instructions,
+            ),
+            data_memory: UnifiedMemory::from(VariableMemory::<RW>::default()),
+            ..Default::default()
+        };
+        emulator.executor.cpu.pc.value = ELF_TEXT_START;
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.cpu.registers[Register::X10], 4);
+        assert_eq!(emulator.executor.cpu.registers[Register::X11], 3);
+        assert_eq!(emulator.executor.cpu.pc.value, ELF_TEXT_START + 8);
+    }
+
+    #[test]
+    fn test_harvard_counter_csrs() {
+        // rdcycle a0; nop; nop; rdinstret a1
+        let instructions = vec![0xc000_2573, 0x0000_0013, 0x0000_0013, 0xc020_25f3];
+        let mut emulator = HarvardEmulator {
+            executor: Executor {
+                base_address: ELF_TEXT_START,
+                entrypoint: ELF_TEXT_START,
+                global_clock: 1,
+                ..Default::default()
+            },
+            instruction_memory: FixedMemory::<RO>::from_vec(
+                ELF_TEXT_START,
+                instructions.len() * WORD_SIZE,
+                instructions,
+            ),
+            data_memory: UnifiedMemory::from(VariableMemory::<RW>::default()),
+            ..Default::default()
+        };
+        emulator.executor.cpu.pc.value = ELF_TEXT_START;
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.cpu.registers[Register::X10], 1);
+        assert_eq!(emulator.executor.cpu.registers[Register::X11], 4);
+    }
+
     #[test]
     #[serial]
     fn test_linear_emulate_nexus_rt_binary() {
@@ -1416,4 +1599,89 @@ mod tests {
 
         assert_eq!(res, Err(VMError::UndefinedInstruction(op)));
     }
+
+    #[test]
+    fn test_harvard_watchpoint() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+            // *x1 = x1
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 1, 0),
+        ])];
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .data_memory
+            .add_watchpoint(0x100..0x104, WatchKind::Write);
+
+        assert_eq!(
+            emulator.execute(false).map(|_| ()),
+            Err(VMError::AccessViolation(AccessReport {
+                pc: ELF_TEXT_START + WORD_SIZE as u32,
+                kind: AccessKind::Write,
+                address: 0x100,
+                violation: AccessViolation::Watchpoint,
+            }))
+        );
+
+        // The stopped store is executed once the watchpoint is removed.
+        assert!(emulator.data_memory.remove_watchpoint(&(0x100..0x104)));
+        assert_eq!(
+            emulator.execute(false).map(|_| ()),
+            Err(VMError::VMOutOfInstructions)
+        );
+        assert_eq!(
+            emulator.data_memory.read(0x100, MemAccessSize::Word),
+            Ok(LoadOp::Op(MemAccessSize::Word, 0x100, 0x100))
+        );
+    }
+
+    #[test]
+    fn test_linear_strict_mode() {
+        let layout = LinearMemoryLayout::default();
+        // *x3 = 0
+        let store = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 3, 0, 0);
+        let elf = ElfFile::new(
+            BasicBlock::new(vec![store.clone()]).encode(),
+            layout.program_start(),
+            layout.program_start(),
+            BTreeMap::new(),
+            BTreeMap::new(),
+            Vec::new(),
+        );
+        let mut emulator = LinearEmulator::from_elf(layout, &[], &elf, &[], &[]);
+
+        let address = layout.gap_start() + WORD_SIZE as u32;
+        emulator.executor.cpu.registers.write(Register::X3, address);
+        emulator
+            .executor
+            .cpu
+            .registers
+            .write(Register::X2, layout.stack_top());
+
+        assert_eq!(
+            emulator.execute_instruction(&store, false).map(|_| ()),
+            Err(VMError::MemoryError(MemoryError::InvalidMemoryAccess(
+                address
+            )))
+        );
+
+        emulator.memory.set_strict_mode(true);
+        let report = |violation| {
+            Err(VMError::AccessViolation(AccessReport {
+                pc: layout.program_start(),
+                kind: AccessKind::Write,
+                address,
+                violation,
+            }))
+        };
+        assert_eq!(
+            emulator.execute_instruction(&store, false).map(|_| ()),
+            report(AccessViolation::HeapStackGap)
+        );
+
+        emulator.executor.cpu.registers.write(Register::X2, address);
+        assert_eq!(
+            emulator.execute_instruction(&store, false).map(|_| ()),
+            report(AccessViolation::StackOverflow)
+        );
+    }
 }
@@ -103,6 +103,7 @@ use crate::error::{Result, VMError};
 use nexus_common::constants::{ELF_TEXT_START, MEMORY_GAP, NUM_REGISTERS, WORD_SIZE};
 use nexus_common::word_align;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 // nb: all measurements are in terms of virtual memory
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -301,9 +302,9 @@ impl LinearMemoryLayout {
                 .checked_sub(self.stack_bottom)
                 .expect("stack top should be above stack bottom") as usize;
         let heap_size = self
-            .heap
+            .heap_end()
             .checked_sub(self.heap_start())
-            .expect("heap should be above heap start") as usize;
+            .expect("heap end should be above heap start") as usize;
         let public_input_size =
             self.public_input_end()
                 .checked_sub(self.public_input_start())
@@ -329,3 +330,48 @@ impl LinearMemoryLayout {
         total
     }
 }
+
+impl fmt::Display for LinearMemoryLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments = [
+            ("program", self.program_start(), self.program_end()),
+            (
+                "public input",
+                self.public_input_start(),
+                self.public_input_end(),
+            ),
+            ("associated data", self.ad_start(), self.ad_end()),
+            ("exit code", self.exit_code(), self.public_output_start()),
+            (
+                "public output",
+                self.public_output_start(),
+                self.public_output_end(),
+            ),
+            ("heap", self.heap_start(), self.heap_end()),
+            ("gap", self.gap_start(), self.gap_end()),
+            ("stack", self.stack_bottom(), self.stack_top),
+        ];
+        for (i, (name, start, end)) in segments.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{name}: {start:#x}..{end:#x} ({} bytes)", end - start)?;
+        }
+        Ok(())
+    }
+}
+
+/// The memory layout of an execution, along with the number of addresses under RAM memory checking
+/// that determines the proving cost.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryReport {
+    pub layout: LinearMemoryLayout,
+    pub tracked_ram_size: usize,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.layout)?;
+        write!(f, "tracked RAM size: {} bytes", self.tracked_ram_size)
+    }
+}
@@ -6,10 +6,13 @@
 //! # Key Components
 //!
 //! - `MemoryStats`: A struct that keeps track of memory access statistics.
+//! - `LayoutOverrides`: Segment sizes pinned by the user, taking precedence over the statistics.
+//! - `AllocStats`: Heap allocator statistics reported by guests built with the runtime's
+//!   `alloc-stats` feature.
 //!
 //! # Features
 //!
-//! - Tracks the maximum heap access and minimum stack access addresses.
+//! - Tracks the highest heap access and lowest stack access addresses.
 //! - Updates statistics based on load and store operations during program execution.
 //! - Creates an optimized `LinearMemoryLayout` based on observed memory usage.
 //!
@@ -26,24 +29,36 @@
 //! # Implementation Details
 //!
 //! - The `update` method processes `LoadOp` and `StoreOp` operations to track memory accesses.
-//! - The `create_optimized_layout` method generates a new `LinearMemoryLayout` based on the observed statistics.
-//! - The stack pointer is used directly to determine the minimum stack access, ensuring that the full reserved stack frame is respected.
-//!
-//! # Note
-//!
-//! - This implementation prioritizes safety over potential further optimizations
-//!   that could be achieved by tracking actual stack accesses.
+//!   Accesses at or above the stack pointer belong to the stack, those below it to the heap.
+//! - Both bounds are byte precise: the heap ends one byte past the last byte accessed, and the
+//!   stack extends down to the first byte accessed, so stack frames that are reserved but never
+//!   touched do not count towards the stack size.
+//! - The `create_optimized_layout` method generates a new `LinearMemoryLayout` based on the observed
+//!   statistics, unless the sizes are pinned through `LayoutOverrides`.
+use std::fmt;
+
 use crate::emulator::layout::LinearMemoryLayout;
 use crate::{
     error::Result,
     memory::{LoadOp, StoreOp},
 };
+use nexus_common::constants::WORD_SIZE;
+use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
 use std::collections::HashSet;
 
-#[derive(Debug)]
+/// Extra heap space for allocations aligned beyond a word.
+///
+/// Neither the heap of the first pass nor that of the optimized layout is aligned beyond a word, so
+/// allocations aligned to 16 bytes, the largest alignment of a primitive type, may be placed up to
+/// 12 bytes further in the second pass than they were in the first.
+const HEAP_ALIGNMENT_PADDING: u32 = 16 - WORD_SIZE as u32;
+
+#[derive(Debug, Clone)]
 pub struct MemoryStats {
-    pub max_heap_access: u32,
+    /// One byte past the highest heap access.
+    pub heap_end: u32,
+    /// The lowest stack access.
     pub min_stack_access: u32,
     heap_bottom: u32,
     stack_top: u32,
@@ -58,7 +73,7 @@ impl Default for MemoryStats {
 impl MemoryStats {
     pub fn new(heap_bottom: u32, stack_top: u32) -> Self {
         Self {
-            max_heap_access: heap_bottom,
+            heap_end: heap_bottom,
             min_stack_access: stack_top,
             heap_bottom,
             stack_top,
@@ -72,43 +87,70 @@ impl MemoryStats {
         store_ops: HashSet<StoreOp>,
         stack_pointer: u32,
     ) -> Result<()> {
-        // Collect all memory accesses.
-        let memory_accesses: HashSet<u32> = load_ops
+        // Collect the first address and the size of all memory accesses.
+        let memory_accesses = load_ops
             .iter()
-            .map(|op| op.get_address())
-            .chain(store_ops.iter().map(|op| op.get_address()))
-            .collect();
-
-        // Find the highest memory access in the heap.
-        self.max_heap_access = max(
-            self.max_heap_access,
-            *memory_accesses
-                .iter()
-                .filter(|&addr| addr < &stack_pointer && addr > &self.heap_bottom)
-                .max()
-                .unwrap_or(&0),
-        );
+            .map(|op| (op.get_address(), op.get_size() as u32))
+            .chain(
+                store_ops
+                    .iter()
+                    .map(|op| (op.get_address(), op.get_size() as u32)),
+            );
 
-        // For safety, we just check the stack pointer directly rather than looking for the lowest memory access.
-        // This ensures we respect the full stack frame that was reserved, even if not all of it is used.
-        // We could optimize this in the future by tracking actual stack accesses if needed.
-        if stack_pointer > 0 {
-            self.min_stack_access = min(self.min_stack_access, stack_pointer);
+        for (address, size) in memory_accesses {
+            // The stack pointer is not yet set up at the very start of the program.
+            if stack_pointer > 0 && (stack_pointer..self.stack_top).contains(&address) {
+                self.min_stack_access = min(self.min_stack_access, address);
+            } else if (self.heap_bottom..stack_pointer).contains(&address) {
+                self.heap_end = max(self.heap_end, address.saturating_add(size));
+            }
         }
         Ok(())
     }
 
+    /// Extend the heap up to `heap_end`, even if the memory below it is never accessed.
+    ///
+    /// The guest allocator checks the memory it hands out against the stack pointer, so the heap must
+    /// cover all allocations for the checks to pass the same way on a layout built from these stats.
+    pub fn record_heap_end(&mut self, heap_end: u32) {
+        if heap_end > self.heap_bottom {
+            self.heap_end = max(self.heap_end, heap_end);
+        }
+    }
+
+    /// Returns the number of heap bytes used by the execution.
+    pub fn heap_size(&self) -> u32 {
+        self.heap_end - self.heap_bottom
+    }
+
+    /// Returns the number of stack bytes used by the execution.
+    pub fn stack_size(&self) -> u32 {
+        self.stack_top - self.min_stack_access
+    }
+
     /// Create an optimized linear memory layout based on the memory stats.
+    ///
+    /// Heap and stack sizes pinned in `overrides` are used as is.
     pub fn create_optimized_layout(
         &self,
         program_size: u32,
         ad_size: u32,
         input_size: u32,
         output_size: u32,
+        overrides: &LayoutOverrides,
     ) -> Result<LinearMemoryLayout> {
+        let heap_size = overrides.heap_size.unwrap_or(match self.heap_size() {
+            0 => 0,
+            size => size + HEAP_ALIGNMENT_PADDING,
+        });
+        // The stack pointer starts at the last word of the stack segment, rather than one past it.
+        let stack_size = overrides
+            .stack_size
+            .unwrap_or(self.stack_size() + WORD_SIZE as u32);
+
         LinearMemoryLayout::new(
-            self.max_heap_access - self.heap_bottom + 0x100,
-            self.stack_top - self.min_stack_access + 0x100,
+            heap_size,
+            stack_size,
             input_size,
             output_size,
             program_size,
@@ -118,9 +160,7 @@ impl MemoryStats {
 
     /// Returns the total number of addresses under RAM memory checking.
     pub fn get_tracked_ram_size(&self, input_size: u32, output_size: u32) -> u32 {
-        let heap_size = self.max_heap_access - self.heap_bottom;
-        let stack_size = self.stack_top - self.min_stack_access;
-        let total = [heap_size, stack_size, input_size, output_size]
+        let total = [self.heap_size(), self.stack_size(), input_size, output_size]
             .iter()
             .try_fold(0u32, |acc, &val| acc.checked_add(val))
             .expect("overflow");
@@ -128,6 +168,120 @@ impl MemoryStats {
     }
 }
 
+/// Heap and stack sizes to use for the memory layout of the second pass, instead of those observed
+/// during the first pass.
+///
+/// Pinning a size keeps the memory layout, and so the proving cost, independent of the inputs, but
+/// the execution fails if it needs more memory than the pinned size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutOverrides {
+    /// Size of the heap in bytes.
+    pub heap_size: Option<u32>,
+    /// Size of the stack in bytes.
+    pub stack_size: Option<u32>,
+}
+
+/// Number of largest allocations tracked by the guest allocator.
+pub const NUM_LARGEST_ALLOCATIONS: usize = 4;
+
+/// Heap allocator statistics reported by the guest runtime.
+///
+/// Guests built with the runtime's `alloc-stats` feature send these to the host through the
+/// write syscall on [`ALLOC_STATS_FD`](crate::system::ALLOC_STATS_FD) when they exit or panic.
+/// The guest encodes them as consecutive little-endian words in field order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of calls to the allocator.
+    pub allocations: u32,
+    /// Number of calls to the deallocator.
+    pub deallocations: u32,
+    /// Total number of bytes requested over the whole execution.
+    pub allocated_bytes: u32,
+    /// Number of bytes still allocated when the report was sent.
+    pub live_bytes: u32,
+    /// First address of the heap.
+    pub heap_start: u32,
+    /// Highest address reached by the heap.
+    pub heap_end: u32,
+    /// Whether an allocation collided with the stack.
+    pub heap_overflow: bool,
+    /// Sizes of the largest allocations, in decreasing order.
+    pub largest_allocations: [u32; NUM_LARGEST_ALLOCATIONS],
+}
+
+impl AllocStats {
+    /// Size of the encoded statistics in bytes.
+    pub const ENCODED_SIZE: usize = (7 + NUM_LARGEST_ALLOCATIONS) * 4;
+
+    /// Decode the statistics sent by the guest, returns `None` if the buffer is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_SIZE {
+            return None;
+        }
+        let mut words = bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()));
+        let mut next = || words.next().unwrap();
+
+        Some(Self {
+            allocations: next(),
+            deallocations: next(),
+            allocated_bytes: next(),
+            live_bytes: next(),
+            heap_start: next(),
+            heap_end: next(),
+            heap_overflow: next() != 0,
+            largest_allocations: std::array::from_fn(|_| next()),
+        })
+    }
+
+    /// Peak heap usage in bytes, that is the smallest heap that fits the execution.
+    pub fn peak_heap_usage(&self) -> u32 {
+        self.heap_end.saturating_sub(self.heap_start)
+    }
+
+    /// Whether some allocations were never freed.
+    ///
+    /// Only meaningful for allocators that release memory, the default bump allocator
+    /// never reuses freed memory.
+    pub fn has_leaks(&self) -> bool {
+        self.live_bytes > 0
+    }
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "allocations: {} ({} freed)",
+            self.allocations, self.deallocations
+        )?;
+        writeln!(
+            f,
+            "allocated bytes: {} ({} live)",
+            self.allocated_bytes, self.live_bytes
+        )?;
+        writeln!(
+            f,
+            "peak heap usage: {} bytes ({:#x}..{:#x})",
+            self.peak_heap_usage(),
+            self.heap_start,
+            self.heap_end
+        )?;
+        let largest: Vec<String> = self
+            .largest_allocations
+            .iter()
+            .filter(|&&size| size > 0)
+            .map(u32::to_string)
+            .collect();
+        write!(f, "largest allocations: [{}]", largest.join(", "))?;
+        if self.heap_overflow {
+            write!(f, "\nheap overflow: the heap collided with the stack")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,9 +302,33 @@ mod tests {
         load_ops.insert(LoadOp::Op(MemAccessSize::Word, 1100, 0));
         store_ops.insert(StoreOp::Op(MemAccessSize::Word, 1200, 0, 0));
 
+        // Accesses by the allocator or the stack frames that are not yet set up.
+        load_ops.insert(LoadOp::Op(MemAccessSize::Byte, 2000000, 0));
+
         sizes.update(load_ops, store_ops, stack_pointer).unwrap();
-        assert_eq!(sizes.max_heap_access, 600);
-        assert_eq!(sizes.min_stack_access, 1000);
+        assert_eq!(sizes.heap_end, 604);
+        assert_eq!(sizes.min_stack_access, 1100);
+        assert_eq!(sizes.heap_size(), 604);
+        assert_eq!(sizes.stack_size(), 1000000 - 1100);
+
+        // The stack pointer moving down does not count as a stack access by itself.
+        let mut store_ops = HashSet::new();
+        store_ops.insert(StoreOp::Op(MemAccessSize::HalfWord, 900, 0, 0));
+        sizes.update(HashSet::new(), store_ops, 800).unwrap();
+        assert_eq!(sizes.heap_end, 604);
+        assert_eq!(sizes.min_stack_access, 900);
+
+        // Byte accesses extend the heap by a single byte.
+        let mut store_ops = HashSet::new();
+        store_ops.insert(StoreOp::Op(MemAccessSize::Byte, 700, 0, 0));
+        sizes.update(HashSet::new(), store_ops, 800).unwrap();
+        assert_eq!(sizes.heap_end, 701);
+
+        // Memory handed out by the allocator is part of the heap, even if never accessed.
+        sizes.record_heap_end(750);
+        assert_eq!(sizes.heap_end, 750);
+        sizes.record_heap_end(640);
+        assert_eq!(sizes.heap_end, 750);
     }
 
     #[test]
@@ -193,14 +371,48 @@ mod tests {
         let ad_size = 100;
 
         let layout = stats
-            .create_optimized_layout(program_size, ad_size, 0, 0)
+            .create_optimized_layout(program_size, ad_size, 0, 0, &LayoutOverrides::default())
             .unwrap();
 
-        assert_eq!(layout.heap_end(), 5760);
-        assert_eq!(layout.stack_bottom(), 9856);
-        assert_eq!(layout.stack_top(), 1007108);
+        assert_eq!(layout.heap_end(), 4504 + 1004 + HEAP_ALIGNMENT_PADDING);
+        assert_eq!(layout.stack_bottom(), 9616);
+        assert_eq!(layout.stack_top(), 1006616);
+        assert_eq!(
+            layout.stack_top() - layout.stack_bottom(),
+            stats.stack_size()
+        );
         assert_eq!(layout.public_input_end(), 4400);
         assert_eq!(layout.ad_end(), 4500);
         assert_eq!(layout.public_output_end(), 4504);
+
+        let overrides = LayoutOverrides {
+            heap_size: Some(0x2000),
+            stack_size: None,
+        };
+        let layout = stats
+            .create_optimized_layout(program_size, ad_size, 0, 0, &overrides)
+            .unwrap();
+
+        assert_eq!(layout.heap_end() - layout.heap_start(), 0x2000);
+        assert_eq!(
+            layout.stack_top() - layout.stack_bottom(),
+            stats.stack_size()
+        );
+    }
+
+    #[test]
+    fn test_alloc_stats_from_bytes() {
+        let words: [u32; 11] = [3, 1, 96, 32, 0x1000, 0x1080, 0, 64, 16, 16, 0];
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+        let stats = AllocStats::from_bytes(&bytes).unwrap();
+        assert_eq!(stats.allocations, 3);
+        assert_eq!(stats.deallocations, 1);
+        assert_eq!(stats.peak_heap_usage(), 0x80);
+        assert!(stats.has_leaks());
+        assert!(!stats.heap_overflow);
+        assert_eq!(stats.largest_allocations, [64, 16, 16, 0]);
+
+        assert!(AllocStats::from_bytes(&bytes[..bytes.len() - 4]).is_none());
     }
 }
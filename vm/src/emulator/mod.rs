@@ -52,13 +52,16 @@
 //! supporting both Harvard and Linear architectures (unified memory from Harvard architecture
 //! with a single memory space, with added read and write protection), and offering detailed
 //! visibility into the emulator's state and execution results.
+mod consistency;
 mod executor;
 mod layout;
 mod memory_stats;
 mod registry;
 
+pub use consistency::check_consistency;
 pub use executor::{Emulator, Executor, HarvardEmulator, LinearEmulator};
-pub use layout::LinearMemoryLayout;
+pub use layout::{LinearMemoryLayout, MemoryReport};
+pub use memory_stats::{AllocStats, LayoutOverrides, NUM_LARGEST_ALLOCATIONS};
 
 mod utils;
 pub use utils::*;
@@ -19,6 +19,7 @@
 //! - Memory Operations (LB, LH, LW, SB, SH, SW, etc.)
 //! - Control Flow Operations (JAL, JALR, BEQ, BNE, etc.)
 //! - Upper Immediate Operations (LUI, AUIPC)
+//! - Counter CSR Reads (CSRRS as used by `rdcycle`, `rdtime` and `rdinstret`)
 //!
 //! ## Special Instructions
 //!
@@ -188,6 +189,9 @@ impl Default for InstructionExecutorRegistry {
                 None, // ecall, handled by src/system/syscall.rs instead
                 None, // ebreak
                 None, // fence
+                Some(register_instruction_executor!(
+                    instructions::CsrrsInstruction::evaluator
+                )), // csrrs
                 Some(register_instruction_executor!(
                     instructions::SbInstruction::evaluator
                 )), // sb
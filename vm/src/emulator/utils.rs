@@ -1,9 +1,10 @@
 use crate::elf::ElfFile;
-use crate::riscv::{decode_instruction, BasicBlock};
+use crate::riscv::{compressed::is_compressed, decode_instruction, BasicBlock};
 
 pub use super::executor::Emulator;
 pub use super::layout::LinearMemoryLayout;
 use super::registry;
+use super::{AllocStats, MemoryReport};
 
 use nexus_common::constants::WORD_SIZE;
 use nexus_common::memory::MemoryRecords;
@@ -48,6 +49,9 @@ macro_rules! io {
 }
 
 /// Convert `rin` and `wou` instructions into `lb` and `sb` for the second pass in two pass tracing.
+///
+/// The instruction word is assumed to hold a single 32-bit instruction, use `convert_instructions`
+/// for programs that may contain compressed instructions.
 pub fn convert_instruction(registry: &registry::InstructionExecutorRegistry, instr: &u32) -> u32 {
     let mut decoded_ins = decode_instruction(*instr);
 
@@ -62,6 +66,38 @@ pub fn convert_instruction(registry: &registry::InstructionExecutorRegistry, ins
     }
 }
 
+/// Convert `rin` and `wou` instructions of a whole program, which may contain compressed
+/// instructions, so that 32-bit instructions are not necessarily word-aligned.
+pub fn convert_instructions(
+    registry: &registry::InstructionExecutorRegistry,
+    instrs: &[u32],
+) -> Vec<u32> {
+    let mut halfwords: Vec<u16> = instrs
+        .iter()
+        .flat_map(|&word| [word as u16, (word >> 16) as u16])
+        .collect();
+
+    let mut i = 0;
+    while i + 1 < halfwords.len() {
+        if is_compressed(halfwords[i]) {
+            i += 1;
+            continue;
+        }
+
+        // Custom instructions are never compressed, so only 32-bit instructions need converting.
+        let instr = ((halfwords[i + 1] as u32) << 16) | halfwords[i] as u32;
+        let converted = convert_instruction(registry, &instr);
+        halfwords[i] = converted as u16;
+        halfwords[i + 1] = (converted >> 16) as u16;
+        i += 2;
+    }
+
+    halfwords
+        .chunks(2)
+        .map(|halves| ((halves[1] as u32) << 16) | halves[0] as u32)
+        .collect()
+}
+
 pub fn io_entries_into_vec<T: IOEntry>(base: u32, entries: &[T]) -> Vec<u8> {
     let mut vec: Vec<u8> = Vec::new();
     vec.resize(entries.len(), u8::default());
@@ -160,7 +196,7 @@ impl BasicBlockEntry {
     pub fn new(start: u32, block: BasicBlock) -> Self {
         BasicBlockEntry {
             start,
-            end: start + (block.len() * WORD_SIZE) as u32,
+            end: start + block.byte_len() as u32,
             block,
         }
     }
@@ -179,7 +215,7 @@ pub trait InternalView {
     /// Return information about the exit code.
     fn get_exit_code(&self) -> &[PublicOutputEntry];
 
-    /// Add debug logs from another emulator.
+    /// Add debug logs and allocator statistics from another emulator.
     fn add_logs(&mut self, emulator: &impl Emulator);
 }
 
@@ -195,6 +231,7 @@ pub struct View {
     pub(crate) output_memory: Vec<PublicOutputEntry>,
     // todo: incorporate into initial memory
     pub(crate) associated_data: Vec<u8>,
+    pub(crate) alloc_stats: Option<AllocStats>,
 }
 
 impl View {
@@ -209,6 +246,7 @@ impl View {
         exit_code: &Vec<PublicOutputEntry>,
         output_memory: &Vec<PublicOutputEntry>,
         associated_data: &Vec<u8>,
+        alloc_stats: &Option<AllocStats>,
     ) -> Self {
         Self {
             memory_layout: memory_layout.to_owned(),
@@ -219,6 +257,7 @@ impl View {
             exit_code: exit_code.to_owned(),
             output_memory: output_memory.to_owned(),
             associated_data: associated_data.to_owned(),
+            alloc_stats: alloc_stats.to_owned(),
         }
     }
 
@@ -257,6 +296,14 @@ impl View {
         self.tracked_ram_size
     }
 
+    /// Return the memory layout of the execution along with its tracked RAM size, if any.
+    pub fn view_memory_report(&self) -> Option<MemoryReport> {
+        self.memory_layout.map(|layout| MemoryReport {
+            layout,
+            tracked_ram_size: self.tracked_ram_size,
+        })
+    }
+
     /// Return the raw bytes of the associated data, if any.
     pub fn view_associated_data(&self) -> Option<Vec<u8>> {
         if self.memory_layout.is_some() {
@@ -270,6 +317,14 @@ impl View {
     pub fn view_debug_logs(&self) -> Option<Vec<Vec<u8>>> {
         Some(self.debug_logs.clone())
     }
+
+    /// Retrieve the allocator statistics, if the guest was built with the runtime's
+    /// `alloc-stats` feature.
+    ///
+    /// The peak heap usage is a lower bound for the heap size of a `LinearMemoryLayout`.
+    pub fn view_alloc_stats(&self) -> Option<AllocStats> {
+        self.alloc_stats
+    }
 }
 
 impl InternalView for View {
@@ -293,10 +348,14 @@ impl InternalView for View {
         &self.exit_code
     }
 
-    /// Add logs from another emulator.
+    /// Add logs and allocator statistics from another emulator.
     fn add_logs(&mut self, emulator: &impl Emulator) {
-        if let Some(logs) = &emulator.get_executor().logs {
+        let executor = emulator.get_executor();
+        if let Some(logs) = &executor.logs {
             self.debug_logs = logs.to_vec();
         }
+        if executor.alloc_stats.is_some() {
+            self.alloc_stats = executor.alloc_stats;
+        }
     }
 }
@@ -1,8 +1,105 @@
 pub use nexus_common::error::*;
 
-use nexus_common::riscv::Opcode;
+use nexus_common::riscv::{register::Register, Opcode};
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
 use thiserror::Error;
 
+/// Memory access stopped by a watchpoint or by strict mode checks, see [`crate::memory::UnifiedMemory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessReport {
+    /// Address of the instruction performing the access.
+    pub pc: u32,
+    pub kind: AccessKind,
+    /// Lowest address of the access.
+    pub address: u32,
+    pub violation: AccessViolation,
+}
+
+impl Display for AccessReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} on {} of address 0x{:08X} at pc=0x{:08X}",
+            self.violation, self.kind, self.address, self.pc
+        )
+    }
+}
+
+/// A difference between the Harvard and Linear executions of the same program, see
+/// [`crate::emulator::check_consistency`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// The exit codes differ.
+    ExitCode { harvard: Vec<u8>, linear: Vec<u8> },
+    /// The public outputs differ, starting at byte `offset`.
+    PublicOutput {
+        offset: usize,
+        harvard: Option<u8>,
+        linear: Option<u8>,
+    },
+    /// The debug logs differ, starting at entry `index`.
+    Logs {
+        index: usize,
+        harvard: Option<String>,
+        linear: Option<String>,
+    },
+    /// The final program counters differ.
+    Pc { harvard: u32, linear: u32 },
+    /// The final values of a register differ.
+    Register {
+        register: Register,
+        harvard: u32,
+        linear: u32,
+    },
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Divergence::ExitCode { harvard, linear } => {
+                write!(f, "exit code: harvard {harvard:02x?}, linear {linear:02x?}")
+            }
+            Divergence::PublicOutput {
+                offset,
+                harvard,
+                linear,
+            } => write!(
+                f,
+                "public output at byte {offset}: harvard {harvard:02x?}, linear {linear:02x?}"
+            ),
+            Divergence::Logs {
+                index,
+                harvard,
+                linear,
+            } => write!(
+                f,
+                "log entry {index}: harvard {harvard:?}, linear {linear:?}"
+            ),
+            Divergence::Pc { harvard, linear } => {
+                write!(f, "pc: harvard 0x{harvard:08X}, linear 0x{linear:08X}")
+            }
+            Divergence::Register {
+                register,
+                harvard,
+                linear,
+            } => write!(
+                f,
+                "register {register}: harvard 0x{harvard:08X}, linear 0x{linear:08X}"
+            ),
+        }
+    }
+}
+
+/// Lists each divergence on its own indented line.
+fn display_divergences(divergences: &[Divergence]) -> String {
+    divergences
+        .iter()
+        .fold(String::new(), |mut out, divergence| {
+            let _ = write!(out, "\n  {divergence}");
+            out
+        })
+}
+
 /// Errors related to VM operations.
 #[derive(Debug, Error, PartialEq)]
 pub enum VMError {
@@ -22,6 +119,10 @@ pub enum VMError {
     #[error("VM has run out of instructions to execute")]
     VMOutOfInstructions,
 
+    // The program counter points into the middle of an instruction.
+    #[error("Misaligned program counter: pc=0x{0:08X} is not the start of an instruction")]
+    MisalignedPc(u32),
+
     // VM has exited with status code.
     #[error("VM has exited with status code {0}")]
     VMExited(u32),
@@ -30,6 +131,10 @@ pub enum VMError {
     #[error("Invalid profile label for cycle counter: \"{0}\"")]
     InvalidProfileLabel(String),
 
+    // Memory access stopped by a watchpoint or strict mode checks
+    #[error("Memory access violation: {0}")]
+    AccessViolation(AccessReport),
+
     #[error("Wrapped MemoryError: {0}")]
     MemoryError(#[from] nexus_common::error::MemoryError),
 
@@ -58,6 +163,13 @@ pub enum VMError {
     // Unsupported instruction (i.e., one with an invalid opcode)
     #[error("Unsupported instruction \"{0}\"")]
     UnsupportedInstruction(Opcode),
+
+    // Harvard and Linear executions of the same program disagree
+    #[error(
+        "Harvard and Linear executions diverged:{}",
+        display_divergences(.0)
+    )]
+    ExecutionDivergence(Vec<Divergence>),
 }
 
 /// Result type for VM functions that can produce errors.
@@ -17,6 +17,7 @@
 //! - Allows adding fixed memory regions with specific base addresses and sizes.
 //! - Supports a fallback variable memory for addresses not covered by fixed regions.
 //! - Implements display and debug formatting for easy visualization of the memory layout.
+//! - Supports watchpoints and a strict mode, stopping suspicious accesses with [`MemoryError::AccessViolation`].
 //!
 //! # Usage
 //!
@@ -51,6 +52,13 @@
 //! is used to determine which type of memory (fixed or variable) and which access mode
 //! should be used for a given address.
 //!
+//! # Watchpoints and Strict Mode
+//!
+//! A watchpoint stops every read and/or write overlapping a given address range. In strict mode, writes into
+//! read-only regions and accesses into the gap between heap and stack are stopped as well. Such accesses fail with
+//! [`MemoryError::AccessViolation`] before touching memory, which lets the emulator attach the program counter and
+//! resume execution once the watchpoint is removed.
+//!
 //! # Error Handling
 //!
 //! The module uses `Result` types with `MemoryError` for error handling, covering cases such as:
@@ -68,13 +76,14 @@
 //! The use of `RangeMap` for memory layout allows for efficient lookup of the correct memory
 //! region for a given address. However, the performance may vary depending on the number and
 //! size of fixed memory regions.
-use nexus_common::error::MemoryError;
+use nexus_common::error::{AccessKind, AccessViolation, MemoryError};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use rangemap::RangeMap;
 use std::{
     collections::BTreeMap,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
+    ops::Range,
 };
 
 use nexus_common::words_to_bytes;
@@ -91,6 +100,31 @@ pub enum Modes {
     RW = 3,
 }
 
+/// Accesses stopped by a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl WatchKind {
+    fn matches(self, kind: AccessKind) -> bool {
+        matches!(
+            (self, kind),
+            (Self::ReadWrite, _)
+                | (Self::Read, AccessKind::Read)
+                | (Self::Write, AccessKind::Write)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub range: Range<u32>,
+    pub kind: WatchKind,
+}
+
 // nb: we store outside the map becaues `rangemap::RangeMap` does not support a `get_mut` interface (https://github.com/jeffparsons/rangemap/issues/85)
 #[derive(Default, Clone, Eq, PartialEq)]
 pub struct UnifiedMemory {
@@ -110,6 +144,12 @@ pub struct UnifiedMemory {
     fna_store: Vec<FixedMemory<NA>>,
     // fallback variable read-write memory for all other addresses
     vrw: Option<VariableMemory<RW>>,
+    // address ranges whose accesses are stopped
+    watchpoints: Vec<Watchpoint>,
+    // whether writes to read-only regions and accesses into the gap are stopped
+    strict_mode: bool,
+    // the unmapped region between heap and stack, if known
+    gap: Option<Range<u32>>,
 }
 
 impl Display for UnifiedMemory {
@@ -199,6 +239,9 @@ impl From<VariableMemory<RW>> for UnifiedMemory {
             fna: RangeMap::new(),
             fna_store: Vec::new(),
             vrw: Some(vrw),
+            watchpoints: Vec::new(),
+            strict_mode: false,
+            gap: None,
         }
     }
 }
@@ -235,6 +278,63 @@ impl UnifiedMemory {
         Ok(())
     }
 
+    /// Stops accesses of `kind` overlapping `range`, until the watchpoint is removed.
+    pub fn add_watchpoint(&mut self, range: Range<u32>, kind: WatchKind) {
+        self.watchpoints.push(Watchpoint { range, kind });
+    }
+
+    /// Removes all watchpoints on exactly `range`, returns whether any existed.
+    pub fn remove_watchpoint(&mut self, range: &Range<u32>) -> bool {
+        let len = self.watchpoints.len();
+        self.watchpoints
+            .retain(|watchpoint| &watchpoint.range != range);
+        self.watchpoints.len() != len
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Enables or disables strict mode, see the [module documentation](self).
+    pub fn set_strict_mode(&mut self, strict_mode: bool) {
+        self.strict_mode = strict_mode;
+    }
+
+    /// Sets the gap between heap and stack, checked for accesses in strict mode.
+    pub fn set_gap(&mut self, gap: Option<Range<u32>>) {
+        self.gap = gap;
+    }
+
+    fn check_access(
+        &self,
+        address: u32,
+        size: MemAccessSize,
+        kind: AccessKind,
+    ) -> Result<(), MemoryError> {
+        let overlaps = |range: &Range<u32>| {
+            range.start < address.saturating_add(size as u32) && address < range.end
+        };
+        let violation = |violation| Err(MemoryError::AccessViolation(address, kind, violation));
+
+        if self
+            .watchpoints
+            .iter()
+            .any(|watchpoint| watchpoint.kind.matches(kind) && overlaps(&watchpoint.range))
+        {
+            return violation(AccessViolation::Watchpoint);
+        }
+        if !self.strict_mode {
+            return Ok(());
+        }
+        if self.gap.as_ref().is_some_and(overlaps) {
+            return violation(AccessViolation::HeapStackGap);
+        }
+        if kind == AccessKind::Write && self.meta.get(&address) == Some(&Modes::RO) {
+            return violation(AccessViolation::ReadOnlyWrite);
+        }
+        Ok(())
+    }
+
     add_fixed!(add_fixed_rw, frw, frw_store, RW);
     add_fixed!(add_fixed_ro, fro, fro_store, RO);
     add_fixed!(add_fixed_wo, fwo, fwo_store, WO);
@@ -345,6 +445,8 @@ impl MemoryProcessor for UnifiedMemory {
         size: MemAccessSize,
         value: u32,
     ) -> Result<StoreOp, MemoryError> {
+        self.check_access(address, size, AccessKind::Write)?;
+
         if let Some(meta) = self.meta.get(&address) {
             // Safety: that address is in meta means unwraps and indexing are safe
             match meta {
@@ -383,6 +485,8 @@ impl MemoryProcessor for UnifiedMemory {
     ///
     /// Returns a `Result` containing the read value or an error.
     fn read(&self, address: u32, size: MemAccessSize) -> Result<LoadOp, MemoryError> {
+        self.check_access(address, size, AccessKind::Read)?;
+
         if let Some(meta) = self.meta.get(&address) {
             // that address is in meta means unwraps are safe
             match meta {
@@ -1027,4 +1131,75 @@ mod tests {
             Err(MemoryError::InvalidMemoryAccess(0x4000))
         );
     }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut memory = memory_setup();
+        memory.add_watchpoint(0x1004..0x1008, WatchKind::Write);
+
+        // Reads and accesses next to the range are not stopped.
+        assert!(memory.read(0x1004, MemAccessSize::Word).is_ok());
+        assert!(memory.write(0x1000, MemAccessSize::Word, 1).is_ok());
+        assert!(memory.write(0x1008, MemAccessSize::Byte, 1).is_ok());
+
+        assert_eq!(
+            memory.write(0x1006, MemAccessSize::HalfWord, 1),
+            Err(MemoryError::AccessViolation(
+                0x1006,
+                AccessKind::Write,
+                AccessViolation::Watchpoint
+            ))
+        );
+        // The stopped access must not modify memory.
+        assert_eq!(
+            memory.read(0x1004, MemAccessSize::Word),
+            Ok(LoadOp::Op(MemAccessSize::Word, 0x1004, 0))
+        );
+
+        memory.add_watchpoint(0x5000..0x5001, WatchKind::ReadWrite);
+        assert_eq!(
+            memory.read(0x5000, MemAccessSize::Word),
+            Err(MemoryError::AccessViolation(
+                0x5000,
+                AccessKind::Read,
+                AccessViolation::Watchpoint
+            ))
+        );
+
+        assert!(memory.remove_watchpoint(&(0x1004..0x1008)));
+        assert!(!memory.remove_watchpoint(&(0x1004..0x1008)));
+        assert!(memory.write(0x1006, MemAccessSize::HalfWord, 1).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut memory = memory_setup();
+        memory.set_gap(Some(0x6000..0x7000));
+
+        assert!(memory.read(0x6000, MemAccessSize::Word).is_ok());
+        assert_eq!(
+            memory.write(0x0, MemAccessSize::Word, 1),
+            Err(MemoryError::UnauthorizedWrite(0x0))
+        );
+
+        memory.set_strict_mode(true);
+        assert_eq!(
+            memory.read(0x6ffe, MemAccessSize::HalfWord),
+            Err(MemoryError::AccessViolation(
+                0x6ffe,
+                AccessKind::Read,
+                AccessViolation::HeapStackGap
+            ))
+        );
+        assert_eq!(
+            memory.write(0x0, MemAccessSize::Word, 1),
+            Err(MemoryError::AccessViolation(
+                0x0,
+                AccessKind::Write,
+                AccessViolation::ReadOnlyWrite
+            ))
+        );
+        assert!(memory.read(0x0, MemAccessSize::Word).is_ok());
+        assert!(memory.write(0x5ffc, MemAccessSize::Word, 1).is_ok());
+    }
 }
@@ -0,0 +1,460 @@
+//! # Decoding of RISC-V Compressed (RVC) Instructions
+//!
+//! This module expands 16-bit instructions from the RV32C extension into the unified
+//! `Instruction` representation used by the rest of the VM. Every compressed instruction has
+//! an equivalent 32-bit RV32I instruction, so no new opcodes are introduced: the expanded
+//! instruction is only marked as compressed, so that the emulator advances the PC by 2 bytes
+//! and `jal`/`jalr` link to `pc + 2`.
+//!
+//! Floating-point loads and stores (`c.flw`, `c.fsw`, `c.fld`, `c.fsd` and their stack-pointer
+//! variants) as well as reserved and illegal encodings decode to `Instruction::unimpl()`.
+//!
+//! References:
+//! - The RISC-V Instruction Set Manual, Volume I, Chapter "C" Extension for Compressed Instructions
+//! - <https://github.com/riscv/riscv-opcodes/blob/master/rv_c>
+//! - <https://github.com/riscv/riscv-opcodes/blob/master/rv32_c>
+
+use nexus_common::riscv::{
+    instruction::{Instruction, InstructionType},
+    opcode::BuiltinOpcode,
+    register::Register,
+    Opcode,
+};
+
+const QUADRANT_MASK: u16 = 0b11;
+
+/// Returns true if the halfword is the start of a 16-bit compressed instruction.
+///
+/// Every 32-bit instruction has its two least significant bits set, compressed instructions use
+/// the remaining three quadrants.
+#[inline(always)]
+pub fn is_compressed(halfword: u16) -> bool {
+    halfword & QUADRANT_MASK != QUADRANT_MASK
+}
+
+/// Extracts `len` bits of the halfword starting at bit `lo`.
+#[inline(always)]
+fn bits(halfword: u16, lo: u32, len: u32) -> u32 {
+    (halfword as u32 >> lo) & ((1 << len) - 1)
+}
+
+/// Sign-extends the lowest `bits` bits of `value` to 32 bits.
+#[inline(always)]
+fn sign_extend(value: u32, bits: u32) -> u32 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+/// Full register index from bits 11:7 (`rd`/`rs1`).
+#[inline(always)]
+fn rd_full(halfword: u16) -> Register {
+    Register::from(bits(halfword, 7, 5) as u8)
+}
+
+/// Full register index from bits 6:2 (`rs2`).
+#[inline(always)]
+fn rs2_full(halfword: u16) -> Register {
+    Register::from(bits(halfword, 2, 5) as u8)
+}
+
+/// Popular register (x8-x15) from the 3-bit field at bits 9:7 (`rs1'`/`rd'`).
+#[inline(always)]
+fn rs1_prime(halfword: u16) -> Register {
+    Register::from(bits(halfword, 7, 3) as u8 + 8)
+}
+
+/// Popular register (x8-x15) from the 3-bit field at bits 4:2 (`rs2'`/`rd'`).
+#[inline(always)]
+fn rs2_prime(halfword: u16) -> Register {
+    Register::from(bits(halfword, 2, 3) as u8 + 8)
+}
+
+/// 6-bit signed immediate of the CI format: imm[5] = bit 12, imm[4:0] = bits 6:2.
+#[inline(always)]
+fn ci_imm(halfword: u16) -> u32 {
+    sign_extend((bits(halfword, 12, 1) << 5) | bits(halfword, 2, 5), 6)
+}
+
+/// Word offset of `c.lw`/`c.sw`: uimm[5:3] = bits 12:10, uimm[2] = bit 6, uimm[6] = bit 5.
+#[inline(always)]
+fn cl_word_offset(halfword: u16) -> u32 {
+    (bits(halfword, 10, 3) << 3) | (bits(halfword, 6, 1) << 2) | (bits(halfword, 5, 1) << 6)
+}
+
+/// Jump offset of `c.j`/`c.jal`: offset[11|4|9:8|10|6|7|3:1|5] = bits 12|11|10:9|8|7|6|5:3|2.
+#[inline(always)]
+fn cj_offset(halfword: u16) -> u32 {
+    let offset = (bits(halfword, 12, 1) << 11)
+        | (bits(halfword, 11, 1) << 4)
+        | (bits(halfword, 9, 2) << 8)
+        | (bits(halfword, 8, 1) << 10)
+        | (bits(halfword, 7, 1) << 6)
+        | (bits(halfword, 6, 1) << 7)
+        | (bits(halfword, 3, 3) << 1)
+        | (bits(halfword, 2, 1) << 5);
+    sign_extend(offset, 12)
+}
+
+/// Branch offset of `c.beqz`/`c.bnez`: offset[8|4:3] = bits 12|11:10,
+/// offset[7:6|2:1|5] = bits 6:5|4:3|2.
+#[inline(always)]
+fn cb_offset(halfword: u16) -> u32 {
+    let offset = (bits(halfword, 12, 1) << 8)
+        | (bits(halfword, 10, 2) << 3)
+        | (bits(halfword, 5, 2) << 6)
+        | (bits(halfword, 3, 2) << 1)
+        | (bits(halfword, 2, 1) << 5);
+    sign_extend(offset, 9)
+}
+
+fn i_type(opcode: BuiltinOpcode, rd: Register, rs1: Register, imm: u32) -> Instruction {
+    Instruction::new(Opcode::from(opcode), rd, rs1, imm, InstructionType::IType)
+}
+
+fn i_type_shamt(opcode: BuiltinOpcode, rd: Register, rs1: Register, shamt: u32) -> Instruction {
+    Instruction::new(
+        Opcode::from(opcode),
+        rd,
+        rs1,
+        shamt,
+        InstructionType::ITypeShamt,
+    )
+}
+
+fn r_type(opcode: BuiltinOpcode, rd: Register, rs1: Register, rs2: Register) -> Instruction {
+    Instruction::new(
+        Opcode::from(opcode),
+        rd,
+        rs1,
+        rs2 as u32,
+        InstructionType::RType,
+    )
+}
+
+fn s_type(opcode: BuiltinOpcode, rs1: Register, rs2: Register, imm: u32) -> Instruction {
+    Instruction::new(Opcode::from(opcode), rs1, rs2, imm, InstructionType::SType)
+}
+
+fn b_type(opcode: BuiltinOpcode, rs1: Register, rs2: Register, imm: u32) -> Instruction {
+    Instruction::new(Opcode::from(opcode), rs1, rs2, imm, InstructionType::BType)
+}
+
+fn jal(rd: Register, imm: u32) -> Instruction {
+    Instruction::new(
+        Opcode::from(BuiltinOpcode::JAL),
+        rd,
+        Register::X0,
+        imm,
+        InstructionType::JType,
+    )
+}
+
+/// Quadrant 0: stack-pointer based `addi`, and word loads and stores.
+fn decode_quadrant_0(halfword: u16) -> Option<Instruction> {
+    match bits(halfword, 13, 3) {
+        // c.addi4spn: addi rd', x2, nzuimm
+        0b000 => {
+            let nzuimm = (bits(halfword, 11, 2) << 4)
+                | (bits(halfword, 7, 4) << 6)
+                | (bits(halfword, 6, 1) << 2)
+                | (bits(halfword, 5, 1) << 3);
+            // nzuimm == 0 is reserved, and covers the all-zero illegal instruction.
+            (nzuimm != 0).then(|| {
+                i_type(
+                    BuiltinOpcode::ADDI,
+                    rs2_prime(halfword),
+                    Register::X2,
+                    nzuimm,
+                )
+            })
+        }
+        // c.lw: lw rd', offset(rs1')
+        0b010 => Some(i_type(
+            BuiltinOpcode::LW,
+            rs2_prime(halfword),
+            rs1_prime(halfword),
+            cl_word_offset(halfword),
+        )),
+        // c.sw: sw rs2', offset(rs1')
+        0b110 => Some(s_type(
+            BuiltinOpcode::SW,
+            rs1_prime(halfword),
+            rs2_prime(halfword),
+            cl_word_offset(halfword),
+        )),
+        // c.fld, c.flw, c.fsd, c.fsw and the reserved encoding.
+        _ => None,
+    }
+}
+
+/// Quadrant 1: immediate arithmetic, register-register arithmetic on popular registers,
+/// jumps and branches.
+fn decode_quadrant_1(halfword: u16) -> Option<Instruction> {
+    match bits(halfword, 13, 3) {
+        // c.addi (c.nop when rd == x0): addi rd, rd, nzimm
+        0b000 => Some(i_type(
+            BuiltinOpcode::ADDI,
+            rd_full(halfword),
+            rd_full(halfword),
+            ci_imm(halfword),
+        )),
+        // c.jal: jal x1, offset
+        0b001 => Some(jal(Register::X1, cj_offset(halfword))),
+        // c.li: addi rd, x0, imm
+        0b010 => Some(i_type(
+            BuiltinOpcode::ADDI,
+            rd_full(halfword),
+            Register::X0,
+            ci_imm(halfword),
+        )),
+        0b011 if rd_full(halfword) == Register::X2 => {
+            // c.addi16sp: addi x2, x2, nzimm
+            let nzimm = (bits(halfword, 12, 1) << 9)
+                | (bits(halfword, 6, 1) << 4)
+                | (bits(halfword, 5, 1) << 6)
+                | (bits(halfword, 3, 2) << 7)
+                | (bits(halfword, 2, 1) << 5);
+            (nzimm != 0).then(|| {
+                i_type(
+                    BuiltinOpcode::ADDI,
+                    Register::X2,
+                    Register::X2,
+                    sign_extend(nzimm, 10),
+                )
+            })
+        }
+        0b011 => {
+            // c.lui: lui rd, nzimm
+            let nzimm = ci_imm(halfword);
+            (nzimm != 0).then(|| {
+                Instruction::new(
+                    Opcode::from(BuiltinOpcode::LUI),
+                    rd_full(halfword),
+                    Register::X0,
+                    nzimm & 0xFFFFF,
+                    InstructionType::UType,
+                )
+            })
+        }
+        0b100 => {
+            let rd = rs1_prime(halfword);
+            match bits(halfword, 10, 2) {
+                // c.srli, c.srai: shamt[5] must be zero on RV32.
+                0b00 | 0b01 if bits(halfword, 12, 1) == 1 => None,
+                0b00 => Some(i_type_shamt(
+                    BuiltinOpcode::SRLI,
+                    rd,
+                    rd,
+                    bits(halfword, 2, 5),
+                )),
+                0b01 => Some(i_type_shamt(
+                    BuiltinOpcode::SRAI,
+                    rd,
+                    rd,
+                    bits(halfword, 2, 5),
+                )),
+                // c.andi: andi rd', rd', imm
+                0b10 => Some(i_type(BuiltinOpcode::ANDI, rd, rd, ci_imm(halfword))),
+                // c.subw and c.addw are RV64 only.
+                _ if bits(halfword, 12, 1) == 1 => None,
+                _ => {
+                    let opcode = match bits(halfword, 5, 2) {
+                        0b00 => BuiltinOpcode::SUB,
+                        0b01 => BuiltinOpcode::XOR,
+                        0b10 => BuiltinOpcode::OR,
+                        _ => BuiltinOpcode::AND,
+                    };
+                    Some(r_type(opcode, rd, rd, rs2_prime(halfword)))
+                }
+            }
+        }
+        // c.j: jal x0, offset
+        0b101 => Some(jal(Register::X0, cj_offset(halfword))),
+        // c.beqz: beq rs1', x0, offset
+        0b110 => Some(b_type(
+            BuiltinOpcode::BEQ,
+            rs1_prime(halfword),
+            Register::X0,
+            cb_offset(halfword),
+        )),
+        // c.bnez: bne rs1', x0, offset
+        _ => Some(b_type(
+            BuiltinOpcode::BNE,
+            rs1_prime(halfword),
+            Register::X0,
+            cb_offset(halfword),
+        )),
+    }
+}
+
+/// Quadrant 2: shifts, stack-pointer based loads and stores, and register moves and jumps.
+fn decode_quadrant_2(halfword: u16) -> Option<Instruction> {
+    let rd = rd_full(halfword);
+    let rs2 = rs2_full(halfword);
+
+    match bits(halfword, 13, 3) {
+        // c.slli: shamt[5] must be zero on RV32.
+        0b000 if bits(halfword, 12, 1) == 1 => None,
+        0b000 => Some(i_type_shamt(
+            BuiltinOpcode::SLLI,
+            rd,
+            rd,
+            bits(halfword, 2, 5),
+        )),
+        // c.lwsp: lw rd, offset(x2), reserved for rd == x0
+        0b010 => {
+            let offset = (bits(halfword, 12, 1) << 5)
+                | (bits(halfword, 4, 3) << 2)
+                | (bits(halfword, 2, 2) << 6);
+            (rd != Register::X0).then(|| i_type(BuiltinOpcode::LW, rd, Register::X2, offset))
+        }
+        0b100 => match (bits(halfword, 12, 1), rd, rs2) {
+            // c.jr: jalr x0, 0(rs1), reserved for rs1 == x0
+            (0, Register::X0, Register::X0) => None,
+            (0, _, Register::X0) => Some(i_type(BuiltinOpcode::JALR, Register::X0, rd, 0)),
+            // c.mv: add rd, x0, rs2
+            (0, _, _) => Some(r_type(BuiltinOpcode::ADD, rd, Register::X0, rs2)),
+            // c.ebreak
+            (_, Register::X0, Register::X0) => {
+                Some(i_type(BuiltinOpcode::EBREAK, Register::X0, Register::X0, 0))
+            }
+            // c.jalr: jalr x1, 0(rs1)
+            (_, _, Register::X0) => Some(i_type(BuiltinOpcode::JALR, Register::X1, rd, 0)),
+            // c.add: add rd, rd, rs2
+            (_, _, _) => Some(r_type(BuiltinOpcode::ADD, rd, rd, rs2)),
+        },
+        // c.swsp: sw rs2, offset(x2)
+        0b110 => {
+            let offset = (bits(halfword, 9, 4) << 2) | (bits(halfword, 7, 2) << 6);
+            Some(s_type(BuiltinOpcode::SW, Register::X2, rs2, offset))
+        }
+        // c.fldsp, c.flwsp, c.fsdsp and c.fswsp.
+        _ => None,
+    }
+}
+
+/// Decodes a 16-bit compressed instruction into its 32-bit equivalent.
+///
+/// Unsupported, reserved and illegal encodings decode to `Instruction::unimpl()`, which is still
+/// marked as compressed so that the instruction stream stays aligned.
+pub fn decode_compressed_instruction(halfword: u16) -> Instruction {
+    let decoded = match halfword & QUADRANT_MASK {
+        0b00 => decode_quadrant_0(halfword),
+        0b01 => decode_quadrant_1(halfword),
+        0b10 => decode_quadrant_2(halfword),
+        _ => None,
+    };
+
+    decoded
+        .unwrap_or_else(Instruction::unimpl)
+        .into_compressed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that each compressed encoding expands to the same instruction as its 32-bit
+    /// counterpart, apart from the compressed marker.
+    #[test]
+    fn test_decode_compressed_matches_expansion() {
+        // (compressed encoding, equivalent 32-bit encoding), generated with llvm-mc.
+        let test_cases: [(u16, u32); 22] = [
+            (0x0040, 0x00410413), // c.addi4spn s0, sp, 4  => addi s0, sp, 4
+            (0x4080, 0x0004a403), // c.lw s0, 0(s1)        => lw s0, 0(s1)
+            (0x0001, 0x00000013), // c.nop                 => addi x0, x0, 0
+            (0x157d, 0xfff50513), // c.addi a0, -1         => addi a0, a0, -1
+            (0x4505, 0x00100513), // c.li a0, 1            => addi a0, x0, 1
+            (0x7179, 0xfd010113), // c.addi16sp sp, -48    => addi sp, sp, -48
+            (0x6505, 0x00001537), // c.lui a0, 1           => lui a0, 1
+            (0x8105, 0x00155513), // c.srli a0, 1          => srli a0, a0, 1
+            (0x8505, 0x40155513), // c.srai a0, 1          => srai a0, a0, 1
+            (0x893d, 0x00f57513), // c.andi a0, 15         => andi a0, a0, 15
+            (0x8d0d, 0x40b50533), // c.sub a0, a1          => sub a0, a0, a1
+            (0x8d2d, 0x00b54533), // c.xor a0, a1          => xor a0, a0, a1
+            (0x8d4d, 0x00b56533), // c.or a0, a1           => or a0, a0, a1
+            (0x8d6d, 0x00b57533), // c.and a0, a1          => and a0, a0, a1
+            (0x0506, 0x00151513), // c.slli a0, 1          => slli a0, a0, 1
+            (0x4502, 0x00012503), // c.lwsp a0, 0(sp)      => lw a0, 0(sp)
+            (0x8502, 0x00050067), // c.jr a0               => jalr x0, 0(a0)
+            (0x852e, 0x00b00533), // c.mv a0, a1           => add a0, x0, a1
+            (0x9002, 0x00100073), // c.ebreak              => ebreak
+            (0x9502, 0x000500e7), // c.jalr a0             => jalr ra, 0(a0)
+            (0x952e, 0x00b50533), // c.add a0, a1          => add a0, a0, a1
+            (0xc02a, 0x00a12023), // c.swsp a0, 0(sp)      => sw a0, 0(sp)
+        ];
+
+        for (compressed, expanded) in test_cases {
+            let decoded = decode_compressed_instruction(compressed);
+            let want = crate::riscv::decode_instruction(expanded).into_compressed();
+
+            assert_eq!(
+                decoded, want,
+                "compressed {:#06x} should expand to {:#010x}",
+                compressed, expanded
+            );
+            assert_eq!(decoded.size(), 2);
+        }
+    }
+
+    #[test]
+    fn test_decode_compressed_load_store() {
+        // c.lw a4, 4(a0): rd' = 0b110 (a4), rs1' = 0b010 (a0), uimm[2] = 1
+        let decoded = decode_compressed_instruction(0x4158);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::LW));
+        assert_eq!(decoded.op_a, Register::X14);
+        assert_eq!(decoded.op_b, Register::X10);
+        assert_eq!(decoded.op_c, 4);
+
+        // c.sw s0, 4(s1): rs2' = 0b000 (s0), rs1' = 0b001 (s1), uimm[2] = 1
+        let decoded = decode_compressed_instruction(0xc0c0);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::SW));
+        assert_eq!(decoded.op_a, Register::X9);
+        assert_eq!(decoded.op_b, Register::X8);
+        assert_eq!(decoded.op_c, 4);
+    }
+
+    #[test]
+    fn test_decode_compressed_control_flow() {
+        // c.j -2 (jump back to the previous halfword)
+        let decoded = decode_compressed_instruction(0xbffd);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::JAL));
+        assert_eq!(decoded.op_a, Register::X0);
+        assert_eq!(decoded.op_c as i32, -2);
+
+        // c.jal 8
+        let decoded = decode_compressed_instruction(0x2021);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::JAL));
+        assert_eq!(decoded.op_a, Register::X1);
+        assert_eq!(decoded.op_c, 8);
+
+        // c.beqz a0, 8
+        let decoded = decode_compressed_instruction(0xc501);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::BEQ));
+        assert_eq!(decoded.op_a, Register::X10);
+        assert_eq!(decoded.op_b, Register::X0);
+        assert_eq!(decoded.op_c, 8);
+
+        // c.bnez a0, -4
+        let decoded = decode_compressed_instruction(0xfd75);
+        assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::BNE));
+        assert_eq!(decoded.op_c as i32, -4);
+    }
+
+    #[test]
+    fn test_decode_compressed_illegal() {
+        for halfword in [
+            0x0000, // all zeros is defined to be illegal
+            0x6001, // c.lui with nzimm == 0
+            0x6101, // c.addi16sp with nzimm == 0
+            0x8002, // c.jr x0
+            0x4002, // c.lwsp x0
+            0x2000, // c.fld
+            0x1502, // c.slli with shamt[5] set
+        ] {
+            let decoded = decode_compressed_instruction(halfword);
+            assert_eq!(decoded.opcode, Opcode::from(BuiltinOpcode::UNIMPL));
+            assert_eq!(decoded.size(), 2);
+        }
+    }
+}
@@ -21,9 +21,18 @@
 //! ## Main Functions
 //!
 //! - `decode_instruction`: Decodes a single RISC-V instruction from its raw 32-bit representation.
+//! - `decode_compressed_instruction`: Expands a single 16-bit compressed (RVC) instruction.
 //! - `decode_instructions`: Decodes a series of RISC-V instructions and organizes them into basic blocks.
 //! - `decode_until_end_of_a_block`: Decodes instructions until the end of a single basic block is reached.
 //!
+//! ## Compressed Instructions
+//!
+//! Instruction memory is stored as 32-bit words, but with the C extension instructions are only
+//! 16-bit aligned. The block decoders therefore read the words as a stream of little-endian
+//! halfwords: a halfword whose two least significant bits are not `0b11` is a compressed
+//! instruction, otherwise it is the lower half of a 32-bit instruction, which may straddle
+//! two words.
+//!
 //! ## Usage Example
 //!
 //! The following example demonstrates how to use this module to decode instructions from an ELF file:
@@ -57,9 +66,12 @@
 //! This module is particularly useful for tasks such as control flow analysis, optimization,
 //! and instruction-level parallelism detection in RISC-V programs.
 
+use crate::riscv::compressed::{decode_compressed_instruction, is_compressed};
 use crate::riscv::instructions::{BasicBlock, BasicBlockProgram, Instruction, InstructionDecoder};
+use nexus_common::constants::WORD_SIZE_HALVED;
 use nexus_common::riscv::{instruction::InstructionType, register::Register, Opcode};
 use rrs_lib::process_instruction;
+use std::iter::Peekable;
 
 #[inline(always)]
 fn extract_opcode(u32_instruction: u32) -> u8 {
@@ -171,6 +183,36 @@ pub fn decode_instruction(u32_instruction: u32) -> Instruction {
     })
 }
 
+/// Splits a slice of little-endian instruction words into a stream of halfwords.
+fn halfwords(u32_instructions: &[u32]) -> impl Iterator<Item = u16> + '_ {
+    u32_instructions
+        .iter()
+        .flat_map(|&word| [word as u16, (word >> 16) as u16])
+}
+
+/// Decodes the next instruction from a halfword stream, which is either a single compressed
+/// halfword or two halfwords forming a 32-bit instruction.
+///
+/// An all-zero word decodes as a single 32-bit `unimpl`, the same way `Instruction::unimpl()`
+/// encodes, rather than as two illegal compressed halfwords.
+///
+/// Returns `None` once the stream is exhausted, including when only the lower half of a
+/// 32-bit instruction is left.
+fn decode_next_instruction(
+    halfwords: &mut Peekable<impl Iterator<Item = u16>>,
+) -> Option<Instruction> {
+    let low = halfwords.next()?;
+    if low == 0 && halfwords.next_if_eq(&0).is_some() {
+        return Some(decode_instruction(0));
+    }
+    if is_compressed(low) {
+        return Some(decode_compressed_instruction(low));
+    }
+
+    let high = halfwords.next()?;
+    Some(decode_instruction(((high as u32) << 16) | low as u32))
+}
+
 /// Decodes RISC-V instructions from an ELF file into basic blocks
 ///
 /// # Arguments
@@ -184,11 +226,10 @@ pub fn decode_instructions(u32_instructions: &[u32]) -> BasicBlockProgram {
     let mut program = BasicBlockProgram::default();
     let mut current_block = BasicBlock::default();
     let mut start_new_block = true;
+    let mut halfwords = halfwords(u32_instructions).peekable();
 
-    for &u32_instruction in u32_instructions.iter() {
-        // Decode the instruction, if the instruction is unrecognizable, it will be marked as unimplemented.
-        let decoded_instruction = decode_instruction(u32_instruction);
-
+    // Decode the instruction, if the instruction is unrecognizable, it will be marked as unimplemented.
+    while let Some(decoded_instruction) = decode_next_instruction(&mut halfwords) {
         // Start a new basic block if necessary
         if start_new_block && !current_block.0.is_empty() {
             program.blocks.push(current_block);
@@ -211,11 +252,21 @@ pub fn decode_instructions(u32_instructions: &[u32]) -> BasicBlockProgram {
 }
 
 pub fn decode_until_end_of_a_block(u32_instructions: &[u32]) -> BasicBlock {
-    let mut block = BasicBlock::default();
+    decode_until_end_of_a_block_at(u32_instructions, 0)
+}
 
-    for &u32_instruction in u32_instructions.iter() {
-        let decoded_instruction = decode_instruction(u32_instruction);
+/// Decodes instructions until the end of a single basic block is reached, starting `offset`
+/// bytes into the first word.
+///
+/// The offset is either 0, or 2 when the block starts at the upper half of a word, which can only
+/// happen in programs using compressed instructions.
+pub fn decode_until_end_of_a_block_at(u32_instructions: &[u32], offset: usize) -> BasicBlock {
+    let mut block = BasicBlock::default();
+    let mut halfwords = halfwords(u32_instructions)
+        .skip(offset / WORD_SIZE_HALVED)
+        .peekable();
 
+    while let Some(decoded_instruction) = decode_next_instruction(&mut halfwords) {
         let pc_changed = decoded_instruction.is_branch_or_jump_instruction();
 
         block.0.push(decoded_instruction);
@@ -302,4 +353,68 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_decode_mixed_compressed_block() {
+        // c.li a0, 1; addi a1, a0, 2; c.add a0, a1; c.jr ra
+        // The 32-bit addi straddles the first two words.
+        let words = [0x0593_4505, 0x952e_0025, 0x0000_8082];
+        let gold_test = [
+            "│   0: li a0, 1",
+            "│   1: addi a1, a0, 2",
+            "│   2: add a0, a0, a1",
+            "│   3: ret",
+        ];
+
+        let basic_block = decode_until_end_of_a_block(&words);
+        assert_eq!(basic_block.len(), gold_test.len());
+        assert_eq!(basic_block.byte_len(), 10);
+        for (asm, gold_asm) in basic_block
+            .to_string()
+            .split_terminator('\n')
+            .zip(gold_test)
+        {
+            assert_eq!(asm, gold_asm);
+        }
+
+        // Only offsets at the start of an instruction are found.
+        let starts: Vec<_> = (0..12)
+            .map(|offset| basic_block.index_at_offset(offset))
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                Some(0),
+                None,
+                Some(1),
+                None,
+                None,
+                None,
+                Some(2),
+                None,
+                Some(3),
+                None,
+                None,
+                None
+            ]
+        );
+
+        // Start at the upper half of the first word, i.e. at the addi.
+        let basic_block = decode_until_end_of_a_block_at(&words, 2);
+        assert_eq!(basic_block.len(), 3);
+        assert!(!basic_block[0].compressed);
+        assert!(basic_block[1].compressed);
+    }
+
+    #[test]
+    fn test_decode_counter_csrs() {
+        assert_eq!(decode_instruction(0xc000_2573).to_string(), "rdcycle a0");
+        assert_eq!(decode_instruction(0xc020_2573).to_string(), "rdinstret a0");
+        assert_eq!(decode_instruction(0xc810_25f3).to_string(), "rdtimeh a1");
+
+        // csrr a0, mstatus
+        assert_eq!(decode_instruction(0x3000_2573), Instruction::unimpl());
+        // csrrs a0, cycle, a1 would try to set counter bits
+        assert_eq!(decode_instruction(0xc005_a573), Instruction::unimpl());
+    }
 }
@@ -27,8 +27,10 @@ impl BasicBlock {
 
     pub fn print_with_offset(&self, offset: usize) {
         println!("┌─────────────────────────────────────────────────");
-        for (j, instruction) in self.0.iter().enumerate() {
-            println!("│ {:3x}: {}", j * 4 + offset, instruction);
+        let mut address = offset;
+        for instruction in self.0.iter() {
+            println!("│ {:3x}: {}", address, instruction);
+            address += instruction.size();
         }
         println!("└─────────────────────────────────────────────────");
     }
@@ -37,10 +39,35 @@ impl BasicBlock {
         self.0.len()
     }
 
+    /// Returns the size of the block in bytes, accounting for compressed instructions.
+    pub fn byte_len(&self) -> usize {
+        self.0.iter().map(|instruction| instruction.size()).sum()
+    }
+
+    /// Returns the index of the instruction starting `offset` bytes into the block.
+    ///
+    /// Returns `None` if no instruction starts exactly at `offset`, as for an offset into the middle of
+    /// an instruction.
+    pub fn index_at_offset(&self, offset: usize) -> Option<usize> {
+        let mut start = 0;
+        for (index, instruction) in self.0.iter().enumerate() {
+            if start == offset {
+                return Some(index);
+            }
+            if start > offset {
+                return None;
+            }
+            start += instruction.size();
+        }
+
+        None
+    }
+
     /// Encodes a basic block of RISC-V instructions into their binary representations.
     ///
     /// This function takes a reference to a `BasicBlock` and returns a vector of `u32`,
     /// where each `u32` represents the binary encoding of an instruction in the block.
+    /// Compressed instructions are encoded as their 32-bit expansion.
     pub fn encode(&self) -> Vec<u32> {
         self.0
             .iter()
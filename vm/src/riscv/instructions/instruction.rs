@@ -8,12 +8,14 @@
 //! - <https://github.com/riscv/riscv-opcodes/blob/master/rv32_i>
 //! - <https://github.com/riscv/riscv-opcodes/blob/master/rv_i>
 //! - <https://github.com/riscv/riscv-opcodes/blob/master/rv_m>
+//! - <https://github.com/riscv/riscv-opcodes/blob/master/rv_zicsr>
 
 use crate::riscv::instructions::macros::{
     impl_b_type_instructions, impl_i_type_instructions, impl_i_type_shamt_instructions,
     impl_r_type_instructions, impl_s_type_instructions, impl_systemcall_instructions,
     impl_u_type_instructions, unimplemented_instructions,
 };
+use nexus_common::riscv::csr::CounterCsr;
 use nexus_common::riscv::instruction::{Instruction, InstructionType};
 use nexus_common::riscv::opcode::BuiltinOpcode;
 use nexus_common::riscv::register::Register;
@@ -124,10 +126,25 @@ impl InstructionProcessor for InstructionDecoder {
         )
     }
 
+    // Only counter reads (`rdcycle`, `rdtime`, `rdinstret` and their upper halves) are supported,
+    // any other CSR access is left unimplemented.
+    fn process_csrrs(&mut self, dec_insn: ITypeCSR) -> Self::InstructionResult {
+        if dec_insn.rs1 != 0 || CounterCsr::from_address(dec_insn.csr).is_none() {
+            return Instruction::unimpl();
+        }
+
+        Instruction::new(
+            Opcode::from(BuiltinOpcode::CSRRS),
+            Register::from(dec_insn.rd as u8),
+            Register::X0,
+            dec_insn.csr,
+            InstructionType::IType,
+        )
+    }
+
     unimplemented_instructions! {
         process_csrrc(dec_insn: ITypeCSR),
         process_csrrci(dec_insn: ITypeCSR),
-        process_csrrs(dec_insn: ITypeCSR),
         process_csrrsi(dec_insn: ITypeCSR),
         process_csrrw(dec_insn: ITypeCSR),
         process_csrrwi(dec_insn: ITypeCSR),
@@ -1,7 +1,12 @@
+pub(crate) mod compressed;
 pub(crate) mod decoder;
 pub(crate) mod instructions;
 
-pub use decoder::{decode_instruction, decode_instructions, decode_until_end_of_a_block};
+pub use compressed::decode_compressed_instruction;
+pub use decoder::{
+    decode_instruction, decode_instructions, decode_until_end_of_a_block,
+    decode_until_end_of_a_block_at,
+};
 pub use instructions::{
     BasicBlock, BasicBlockProgram, BuiltinOpcode, Instruction, InstructionType, Opcode,
 };
@@ -1,3 +1,3 @@
 mod syscall;
 
-pub use syscall::{SyscallCode, SyscallInstruction};
+pub use syscall::{SyscallCode, SyscallInstruction, ALLOC_STATS_FD};
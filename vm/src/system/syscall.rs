@@ -13,10 +13,12 @@
 //!
 //! 1. Decoding syscall instructions from CPU state.
 //! 2. Executing various syscalls, such as:
-//!    - Write: Output data to a file descriptor (currently only supports stdout).
+//!    - Write: Output data to a file descriptor (stdout, or allocator statistics on
+//!      `ALLOC_STATS_FD`).
 //!    - Exit: Terminate the program with a specified error code.
 //!    - CycleCount: Profile function execution time.
 //!    - ReadFromPrivateInput: Read data from a private input tape.
+//!    - ReadFromPrivateInputBulk: Copy a chunk of the private input tape into memory.
 //!    - OverwriteStackPointer: Modify the stack pointer based on memory layout.
 //!    - OverwriteHeapPointer: Modify the heap pointer based on memory layout.
 //! 3. Handling memory interactions for syscalls.
@@ -32,12 +34,16 @@ use nexus_common::cpu::Registers;
 
 use crate::{
     cpu::Cpu,
-    emulator::{Executor, LinearMemoryLayout},
+    emulator::{AllocStats, Executor, LinearMemoryLayout},
     error::{Result, VMError},
-    memory::{LoadOp, MemoryProcessor, StoreOp},
+    memory::{LoadOp, MemAccessSize, MemoryProcessor, StoreOp},
     riscv::{BuiltinOpcode, Instruction, Register},
 };
 
+/// File descriptor on which guests built with the runtime's `alloc-stats` feature write
+/// their allocator statistics.
+pub const ALLOC_STATS_FD: u32 = 3;
+
 pub enum SyscallCode {
     // Syscall code defines opcodes start from 0x200
     Write = 0x200, // Is converted to NOP for tracing
@@ -48,6 +54,7 @@ pub enum SyscallCode {
     OverwriteStackPointer = 0x402,
     OverwriteHeapPointer = 0x403,
     ReadFromAuxiliaryInput = 0x404,
+    ReadFromPrivateInputBulk = 0x405, // Not yet supported by the prover
 }
 
 impl SyscallCode {
@@ -60,6 +67,7 @@ impl SyscallCode {
             0x402 => SyscallCode::OverwriteStackPointer,
             0x403 => SyscallCode::OverwriteHeapPointer,
             //0x404 => SyscallCode::ReadFromAuxiliaryInput,
+            0x405 => SyscallCode::ReadFromPrivateInputBulk,
             _ => return Err(VMError::UnimplementedSyscall(value, pc)),
         };
         Ok(code)
@@ -76,6 +84,7 @@ impl From<u32> for SyscallCode {
             0x402 => SyscallCode::OverwriteStackPointer,
             0x403 => SyscallCode::OverwriteHeapPointer,
             0x404 => SyscallCode::ReadFromAuxiliaryInput,
+            0x405 => SyscallCode::ReadFromPrivateInputBulk,
             _ => panic!("Invalid syscall code"),
         }
     }
@@ -91,6 +100,7 @@ impl From<SyscallCode> for u32 {
             SyscallCode::OverwriteStackPointer => 0x402,
             SyscallCode::OverwriteHeapPointer => 0x403,
             SyscallCode::ReadFromAuxiliaryInput => 0x404,
+            SyscallCode::ReadFromPrivateInputBulk => 0x405,
         }
     }
 }
@@ -117,6 +127,11 @@ pub struct SyscallInstruction {
     /// These correspond to registers X10 through X16 ("a0" through "a6").
     /// The number and meaning of arguments depend on the specific system call.
     args: Vec<u32>,
+
+    /// Bytes written to memory by `memory_write`, as a tuple of (address, bytes).
+    ///
+    /// Only the bulk private input read writes to memory.
+    pending_write: Option<(u32, Vec<u8>)>,
 }
 
 impl SyscallInstruction {
@@ -139,6 +154,7 @@ impl SyscallInstruction {
                 cpu.registers[Register::X15],
                 cpu.registers[Register::X16],
             ],
+            pending_write: None,
         })
     }
 
@@ -172,6 +188,27 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Records the allocator statistics written by the guest runtime.
+    ///
+    /// Malformed reports are ignored and return -1, the latest valid report wins.
+    fn execute_alloc_stats(
+        &mut self,
+        executor: &mut Executor,
+        memory: &impl MemoryProcessor,
+        buf_addr: u32,
+        count: u32,
+    ) -> Result<()> {
+        let buffer = memory.read_bytes(buf_addr, count as _)?;
+
+        if let Some(stats) = AllocStats::from_bytes(&buffer) {
+            executor.alloc_stats = Some(stats);
+            self.result = Some((Register::X10, count));
+        } else {
+            self.result = Some((Register::X10, u32::MAX));
+        }
+        Ok(())
+    }
+
     /// Executes the exit syscall to terminate the program.
     ///
     /// This function sets the exit code and signals the VM to terminate execution.
@@ -253,6 +290,24 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Copies up to `count` bytes of the private input tape to `buf_addr`.
+    ///
+    /// The bytes are written to memory by `memory_write`, and the number of bytes copied is
+    /// returned in a0, 0 once the tape is exhausted.
+    fn execute_read_from_private_input_bulk(
+        &mut self,
+        private_input_tape: &mut VecDeque<u8>,
+        buf_addr: u32,
+        count: u32,
+    ) -> Result<()> {
+        let len = private_input_tape.len().min(count as usize);
+        let bytes: Vec<u8> = private_input_tape.drain(..len).collect();
+
+        self.result = Some((Register::X10, len as u32));
+        self.pending_write = Some((buf_addr, bytes));
+        Ok(())
+    }
+
     fn execute_overwrite_stack_pointer(
         &mut self,
         memory_layout: Option<LinearMemoryLayout>,
@@ -302,16 +357,24 @@ impl SyscallInstruction {
         let second_pass = memory_layout.is_some() || force_second_pass;
         match self.code {
             SyscallCode::Write => {
-                // No-op on second pass.
+                let fd = self.args[0];
+                let buf = self.args[1];
+                let count = self.args[2];
+
+                // No-op on second pass, except for capturing the logs to check them against the first pass.
                 if second_pass {
+                    if fd == 1 && executor.logs.is_some() {
+                        self.execute_write(&mut executor.logs, memory, fd, buf, count)?;
+                    }
                     self.result = None;
                     return Ok(());
                 }
 
-                let fd = self.args[0];
-                let buf = self.args[1];
-                let count = self.args[2];
-                self.execute_write(&mut executor.logs, memory, fd, buf, count)
+                if fd == ALLOC_STATS_FD {
+                    self.execute_alloc_stats(executor, memory, buf, count)
+                } else {
+                    self.execute_write(&mut executor.logs, memory, fd, buf, count)
+                }
             }
 
             SyscallCode::CycleCount => {
@@ -342,8 +405,24 @@ impl SyscallInstruction {
                 self.execute_read_from_private_input(&mut executor.private_input_tape)
             }
 
+            SyscallCode::ReadFromPrivateInputBulk => {
+                let buf = self.args[0];
+                let count = self.args[1];
+                self.execute_read_from_private_input_bulk(
+                    &mut executor.private_input_tape,
+                    buf,
+                    count,
+                )
+            }
+
             SyscallCode::OverwriteStackPointer => {
-                self.execute_overwrite_stack_pointer(memory_layout)
+                self.execute_overwrite_stack_pointer(memory_layout)?;
+                // Without a layout the guest keeps the stack pointer it set up itself.
+                executor.stack_top = Some(match self.result {
+                    Some((Register::X2, sp)) => sp,
+                    _ => executor.cpu.registers.read(Register::X2),
+                });
+                Ok(())
             }
 
             SyscallCode::OverwriteHeapPointer => self.execute_overwrite_heap_pointer(memory_layout),
@@ -353,8 +432,18 @@ impl SyscallInstruction {
     }
 
     // Writes to memory for syscall instructions.
-    pub fn memory_write(&self, _memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
-        Ok(HashSet::<StoreOp>::new())
+    pub fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
+        let mut store_ops = HashSet::<StoreOp>::new();
+        if let Some((address, bytes)) = &self.pending_write {
+            for (i, &byte) in bytes.iter().enumerate() {
+                store_ops.insert(memory.write(
+                    address + i as u32,
+                    MemAccessSize::Byte,
+                    byte as u32,
+                )?);
+            }
+        }
+        Ok(store_ops)
     }
 
     // All the write back to registers is done in the write_back function
@@ -397,6 +486,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         emulator
@@ -425,6 +515,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         emulator
@@ -442,6 +533,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_write_alloc_stats() {
+        let words: [u32; 11] = [2, 0, 48, 48, 0x1000, 0x1030, 0, 32, 16, 0, 0];
+        let buf: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let buf_addr = 0;
+        let mut emulator = setup_emulator();
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Write,
+            result: None,
+            args: vec![ALLOC_STATS_FD, buf_addr, buf.len() as _, 0, 0, 0, 0],
+            pending_write: None,
+        };
+
+        emulator
+            .data_memory
+            .write_bytes(buf_addr, &buf)
+            .expect("Failed to write to memory");
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .expect("Failed to execute write syscall");
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+
+        assert_eq!(
+            emulator.executor.cpu.registers.read(Register::X10),
+            buf.len() as u32
+        );
+        let stats = emulator.executor.alloc_stats.expect("stats not recorded");
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.peak_heap_usage(), 0x30);
+        assert_eq!(stats.largest_allocations, [32, 16, 0, 0]);
+    }
+
     #[test]
     fn test_execute_exit() {
         let error_code = 42;
@@ -450,6 +573,7 @@ mod tests {
             code: SyscallCode::Exit,
             result: Some((Register::X10, 0)),
             args: vec![error_code, 0, 0, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         let result = syscall_instruction.execute_exit(error_code);
@@ -466,6 +590,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         let _ = syscall_instruction.execute_overwrite_stack_pointer(Some(memory_layout));
@@ -485,6 +610,7 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         let _ = syscall_instruction.execute_overwrite_heap_pointer(Some(memory_layout));
@@ -506,6 +632,7 @@ mod tests {
             code: SyscallCode::CycleCount,
             result: Some((Register::X10, 0)),
             args: vec![buf_addr, buf_len as _, 0, 0, 0, 0, 0],
+            pending_write: None,
         };
 
         emulator
@@ -573,6 +700,7 @@ mod tests {
             code: SyscallCode::ReadFromPrivateInput,
             result: Some((Register::X10, 0)),
             args: vec![],
+            pending_write: None,
         };
 
         // Test reading values
@@ -593,4 +721,46 @@ mod tests {
             .result
             .is_some_and(|(reg, value)| { reg == Register::X10 && value == u32::MAX }));
     }
+
+    #[test]
+    fn test_execute_read_from_private_input_bulk() {
+        let buf_addr = 0x10;
+        let mut emulator = setup_emulator();
+        emulator.executor.private_input_tape = VecDeque::from(vec![1, 2, 3, 4, 5]);
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::ReadFromPrivateInputBulk,
+            result: None,
+            args: vec![buf_addr, 4, 0, 0, 0, 0, 0],
+            pending_write: None,
+        };
+
+        // Copy the first four bytes.
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .expect("Failed to execute bulk read from private input");
+        let store_ops = syscall_instruction
+            .memory_write(&mut emulator.data_memory)
+            .expect("Failed to write to memory");
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+
+        assert_eq!(store_ops.len(), 4);
+        assert_eq!(emulator.executor.cpu.registers.read(Register::X10), 4);
+        assert_eq!(
+            emulator.data_memory.read_bytes(buf_addr, 4).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+
+        // Only one byte is left on the tape, then none.
+        for expected_len in [1, 0] {
+            syscall_instruction.pending_write = None;
+            syscall_instruction
+                .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+                .expect("Failed to execute bulk read from private input");
+            syscall_instruction.write_back(&mut emulator.executor.cpu);
+            assert_eq!(
+                emulator.executor.cpu.registers.read(Register::X10),
+                expected_len
+            );
+        }
+    }
 }
@@ -3,13 +3,18 @@ use serde::{Deserialize, Serialize};
 use crate::{
     cpu::{instructions::InstructionResult, RegisterFile},
     elf::ElfFile,
-    emulator::{Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, View},
+    emulator::{
+        check_consistency, Emulator, HarvardEmulator, InternalView, LayoutOverrides,
+        LinearEmulator, LinearMemoryLayout, View,
+    },
     error::{Result, VMError},
     memory::MemoryRecords,
     riscv::{BasicBlock, Instruction},
-    WORD_SIZE,
 };
 
+/// Whether tracing checks that the Harvard and Linear passes agree, see [`check_consistency`].
+const CHECK_CONSISTENCY: bool = cfg!(any(debug_assertions, feature = "consistency-check"));
+
 /// A program step.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Step {
@@ -206,7 +211,7 @@ fn k_step(
     };
 
     for _ in 0..k {
-        match vm.fetch_block(vm.get_executor().cpu.pc.value) {
+        match vm.fetch_block_at(vm.get_executor().cpu.pc.value) {
             Err(e) => {
                 // When the block is not fully filled with 'k' instructions,
                 // we still return the block we have,
@@ -238,11 +243,7 @@ fn k_step(
 
                 return (Some(block), Err(e));
             }
-            Ok(basic_block_entry) => {
-                let at = (vm.get_executor().cpu.pc.value as usize
-                    - basic_block_entry.start as usize)
-                    / WORD_SIZE;
-
+            Ok((basic_block_entry, at)) => {
                 for instruction in basic_block_entry.block.0[at..].iter() {
                     if block.steps.len() == k {
                         return (Some(block), Ok(()));
@@ -292,6 +293,26 @@ pub fn k_trace(
     public_input: &[u8],
     private_input: &[u8],
     k: usize,
+) -> Result<(View, UniformTrace)> {
+    k_trace_with_overrides(
+        elf,
+        ad,
+        public_input,
+        private_input,
+        k,
+        &LayoutOverrides::default(),
+    )
+}
+
+/// Same as [`k_trace`], but lays out the memory of the traced execution with the heap and stack sizes
+/// pinned in `overrides`.
+pub fn k_trace_with_overrides(
+    elf: ElfFile,
+    ad: &[u8],
+    public_input: &[u8],
+    private_input: &[u8],
+    k: usize,
+    overrides: &LayoutOverrides,
 ) -> Result<(View, UniformTrace)> {
     assert!(k > 0);
     let mut harvard = HarvardEmulator::from_elf(&elf, public_input, private_input);
@@ -299,8 +320,16 @@ pub fn k_trace(
 
     match harvard.execute(false) {
         Err(VMError::VMExited(_)) => {
-            // todo: consistency check i/o between harvard and linear?
-            let mut linear = LinearEmulator::from_harvard(&harvard, elf, ad, private_input)?;
+            let mut linear = LinearEmulator::from_harvard_with_overrides(
+                &harvard,
+                elf,
+                ad,
+                private_input,
+                overrides,
+            )?;
+            if CHECK_CONSISTENCY {
+                linear.get_executor_mut().capture_logs(true);
+            }
 
             let mut trace = UniformTrace {
                 memory_layout: linear.memory_layout,
@@ -319,6 +348,9 @@ pub fn k_trace(
 
                         match e {
                             VMError::VMExited(_) => {
+                                if CHECK_CONSISTENCY {
+                                    check_consistency(&harvard, &linear)?;
+                                }
                                 let mut view = linear.finalize();
                                 view.add_logs(&harvard);
                                 return Ok((view, trace));
@@ -375,12 +407,9 @@ fn bb_step(vm: &mut impl Emulator) -> (Option<Block>, Result<()>) {
         steps: Vec::new(),
     };
 
-    match vm.fetch_block(vm.get_executor().cpu.pc.value) {
+    match vm.fetch_block_at(vm.get_executor().cpu.pc.value) {
         Err(e) => return (None, Err(e)),
-        Ok(basic_block_entry) => {
-            let at = (vm.get_executor().cpu.pc.value as usize - basic_block_entry.start as usize)
-                / WORD_SIZE;
-
+        Ok((basic_block_entry, at)) => {
             for instruction in basic_block_entry.block.0[at..].iter() {
                 let pc = vm.get_executor().cpu.pc.value;
                 let timestamp = vm.get_executor().global_clock as u32;
@@ -421,8 +450,10 @@ pub fn bb_trace(
 
     match harvard.execute(false) {
         Err(VMError::VMExited(_)) => {
-            // todo: consistency check i/o between harvard and linear?
             let mut linear = LinearEmulator::from_harvard(&harvard, elf, ad, private_input)?;
+            if CHECK_CONSISTENCY {
+                linear.get_executor_mut().capture_logs(true);
+            }
 
             let mut trace = BBTrace {
                 memory_layout: linear.memory_layout,
@@ -440,6 +471,9 @@ pub fn bb_trace(
 
                         match e {
                             VMError::VMExited(_) => {
+                                if CHECK_CONSISTENCY {
+                                    check_consistency(&harvard, &linear)?;
+                                }
                                 let mut view = linear.finalize();
                                 view.add_logs(&harvard);
                                 return Ok((view, trace));
@@ -535,7 +569,7 @@ mod tests {
         assert!(step
             .memory_records
             .take(&MemoryRecord::StoreRecord(
-                (MemAccessSize::Word, 0x3C1C, 4128, 0),
+                (MemAccessSize::Word, 0x3A14, 4128, 0),
                 13,
             ))
             .is_some());
@@ -595,7 +629,7 @@ mod tests {
         assert!(step
             .memory_records
             .take(&MemoryRecord::StoreRecord(
-                (MemAccessSize::Word, 0x3C1C, 4128, 0),
+                (MemAccessSize::Word, 0x3A14, 4128, 0),
                 13,
             ))
             .is_some());
@@ -657,7 +691,7 @@ mod tests {
         assert!(step
             .memory_records
             .take(&MemoryRecord::StoreRecord(
-                (MemAccessSize::Word, 0x3C1C, 4128, 0),
+                (MemAccessSize::Word, 0x3A14, 4128, 0),
                 13,
             ))
             .is_some());